@@ -0,0 +1,518 @@
+// ============================================
+// Composer Projects
+// ============================================
+// A host is a Composer project when `composer.json` sits in its docroot or
+// above it (Laravel and Symfony serve `public/`). Its platform requirements
+// (`php` and `ext-*`, from composer.json and every locked package) are
+// checked against the PHP that actually serves the host: the profile's or
+// mapped pool's version, otherwise whichever version owns the shared pool.
+//
+// `composer install/update` runs as a job with that same PHP binary, so
+// Composer resolves against the version the site will run on.
+
+use crate::fastcgi::Address;
+use crate::hosts_manager::get_virtual_hosts;
+use crate::jobs::Jobs;
+use crate::php_discovery;
+use crate::php_extensions;
+use crate::php_fpm;
+use crate::php_switcher;
+use crate::projects;
+use crate::settings::Settings;
+use crate::types::{PhpVersion, VirtualHost};
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+pub const ACTIONS: &[&str] = &["install", "update"];
+
+/// A platform requirement and who asks for it
+#[derive(Debug, Clone, Serialize)]
+pub struct Requirement {
+    /// `php` or `ext-<name>`
+    pub name: String,
+    pub constraint: String,
+    /// `root` for composer.json itself, otherwise package names
+    pub required_by: Vec<String>,
+    /// None when the constraint couldn't be parsed or PHP is unknown
+    pub satisfied: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ComposerProject {
+    pub domain: String,
+    /// Directory holding composer.json
+    pub directory: String,
+    pub has_lock: bool,
+    pub vendor_installed: bool,
+    /// Full version of the PHP serving the host
+    pub php_version: Option<String>,
+    /// `profile`, `pool` or `shared`
+    pub php_source: Option<String>,
+    pub requirements: Vec<Requirement>,
+    pub warnings: Vec<String>,
+    /// Composer that jobs run; None when it isn't installed
+    pub composer: Option<String>,
+}
+
+/// Nearest directory at or above `docroot` with a composer.json
+pub fn find_project(docroot: &Path) -> Option<PathBuf> {
+    docroot
+        .ancestors()
+        .find(|dir| dir.join("composer.json").is_file())
+        .map(Path::to_path_buf)
+}
+
+/// The PHP serving a host, and where that choice comes from
+pub fn host_php(host: &VirtualHost) -> Option<(PhpVersion, &'static str)> {
+    if let Some(profile) = host.profile.as_deref() {
+        let profile = projects::load(profile).ok()?;
+        return Some((
+            php_discovery::find_installed(&profile.php_version)?,
+            "profile",
+        ));
+    }
+    if let Some(pool) = &host.fpm_pool {
+        return Some((php_discovery::find_installed(&pool.php_version)?, "pool"));
+    }
+
+    // The shared handler goes to whichever master listens on ports.phpFpm
+    let ports = Settings::load().unwrap_or_default().ports;
+    let shared = match ports.php_fpm_socket.as_deref().filter(|s| !s.is_empty()) {
+        Some(socket) => Address::parse(socket),
+        None => Address::parse(&format!("{}:{}", ports.php_fpm_host, ports.php_fpm)),
+    };
+    let version = php_fpm::all_pools()
+        .into_iter()
+        .find(|pool| Address::parse(&pool.listen) == shared)
+        .map(|pool| pool.php_version)
+        .or_else(|| {
+            php_switcher::active(&php_discovery::installed_versions(), None).map(|a| a.version)
+        })?;
+    Some((php_discovery::find_installed(&version)?, "shared"))
+}
+
+/// `8.3.14`, `v8.3`, `8.3.0-RC1`, `8.3.0RC1` (as `php -v` prints it) →
+/// numeric parts; missing ones compare as 0
+fn parse_version(version: &str) -> Option<Vec<u64>> {
+    let version = version.trim().trim_start_matches(['v', '=']);
+    let version = version.split(['-', '+', '@']).next()?;
+    let parts: Vec<&str> = version.split('.').collect();
+    let last = parts.len() - 1;
+    parts
+        .iter()
+        .enumerate()
+        .map(|(i, part)| {
+            // Only the last part may carry a stability suffix
+            let digits = match part.find(|c: char| c.is_ascii_alphabetic()) {
+                Some(suffix) if i == last && suffix > 0 => &part[..suffix],
+                _ => part,
+            };
+            digits.parse().ok()
+        })
+        .collect::<Option<Vec<u64>>>()
+        .filter(|parts| !parts.is_empty())
+}
+
+fn compare(a: &[u64], b: &[u64]) -> Ordering {
+    (0..a.len().max(b.len()))
+        .map(|i| a.get(i).unwrap_or(&0).cmp(b.get(i).unwrap_or(&0)))
+        .find(|o| o.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// Upper bound of a `^` constraint: the first non-zero part is the major one
+fn caret_upper(parts: &[u64]) -> Vec<u64> {
+    let i = parts
+        .iter()
+        .position(|p| *p != 0)
+        .unwrap_or(parts.len() - 1);
+    let mut upper = parts[..=i].to_vec();
+    upper[i] += 1;
+    upper
+}
+
+/// Upper bound when the last given part may vary (`~8.1` → 9, `8.1.*` → 8.2)
+fn bump_parent(parts: &[u64]) -> Vec<u64> {
+    let mut upper = parts[..parts.len().saturating_sub(1).max(1)].to_vec();
+    if let Some(last) = upper.last_mut() {
+        *last += 1;
+    }
+    upper
+}
+
+fn term_matches(version: &[u64], term: &str) -> Option<bool> {
+    let term = term.split('@').next()?.trim();
+    if term.is_empty() || term == "*" {
+        return Some(true);
+    }
+
+    if let Some(rest) = term.strip_prefix('^') {
+        let parts = parse_version(rest)?;
+        return Some(
+            compare(version, &parts).is_ge() && compare(version, &caret_upper(&parts)).is_lt(),
+        );
+    }
+    if let Some(rest) = term.strip_prefix('~') {
+        let parts = parse_version(rest)?;
+        return Some(
+            compare(version, &parts).is_ge() && compare(version, &bump_parent(&parts)).is_lt(),
+        );
+    }
+    if let Some(prefix) = term.strip_suffix(".*").or_else(|| term.strip_suffix(".x")) {
+        let parts = parse_version(prefix)?;
+        let mut upper = parts.clone();
+        *upper.last_mut()? += 1;
+        return Some(compare(version, &parts).is_ge() && compare(version, &upper).is_lt());
+    }
+
+    let (op, rest) = [">=", "<=", "!=", "==", "<>", ">", "<", "="]
+        .iter()
+        .find_map(|op| term.strip_prefix(op).map(|rest| (*op, rest)))
+        .unwrap_or(("==", term));
+    let ordering = compare(version, &parse_version(rest)?);
+    Some(match op {
+        ">=" => ordering.is_ge(),
+        "<=" => ordering.is_le(),
+        ">" => ordering.is_gt(),
+        "<" => ordering.is_lt(),
+        "!=" | "<>" => ordering.is_ne(),
+        _ => ordering.is_eq(),
+    })
+}
+
+/// Whether `version` satisfies a Composer constraint (`^8.1 || ^7.4`,
+/// `>=8.0 <8.4`, `8.1 - 8.3`, `8.2.*`); None when it can't be parsed
+pub fn satisfies(version: &str, constraint: &str) -> Option<bool> {
+    let version = parse_version(version)?;
+    let mut any = false;
+
+    for alternative in constraint.split("||").flat_map(|a| a.split('|')) {
+        let alternative = alternative.trim();
+
+        let matched = if let Some((low, high)) = alternative.split_once(" - ") {
+            // An incomplete upper bound includes its whole branch: 8.1 - 8.3 allows 8.3.x
+            let high = parse_version(high)?;
+            compare(&version, &parse_version(low)?).is_ge()
+                && compare(&version, &bump_parent(&[high.as_slice(), &[0]].concat())).is_lt()
+        } else {
+            // Operators may be written apart from their version: `>= 8.1`
+            let mut terms: Vec<String> = Vec::new();
+            let mut pending = String::new();
+            for token in alternative.split([' ', ',']).filter(|t| !t.is_empty()) {
+                pending.push_str(token);
+                if !token.chars().all(|c| "<>=!~^".contains(c)) {
+                    terms.push(std::mem::take(&mut pending));
+                }
+            }
+            let mut all = true;
+            for term in &terms {
+                all &= term_matches(&version, term)?;
+            }
+            all
+        };
+        any |= matched;
+    }
+
+    Some(any)
+}
+
+/// `ext-Zend-OPcache`, `ext-pdo_mysql` → names as PHP lists them
+fn extension_of(requirement: &str) -> Option<String> {
+    let name = requirement.strip_prefix("ext-")?.to_lowercase();
+    Some(
+        name.strip_prefix("zend-")
+            .unwrap_or(&name)
+            .replace(' ', "_"),
+    )
+}
+
+/// `php` and `ext-*` entries of a `require` map
+fn platform_requirements(require: Option<&serde_json::Value>) -> Vec<(String, String)> {
+    require
+        .and_then(|r| r.as_object())
+        .map(|r| {
+            r.iter()
+                .filter(|(name, _)| *name == "php" || name.starts_with("ext-"))
+                .filter_map(|(name, c)| Some((name.to_lowercase(), c.as_str()?.to_string())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn read_json(path: &Path) -> Option<serde_json::Value> {
+    serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()
+}
+
+/// Requirements of the root package and of every locked package
+fn requirements(dir: &Path) -> Result<Vec<Requirement>, String> {
+    let manifest = read_json(&dir.join("composer.json"))
+        .ok_or_else(|| format!("Failed to parse {}", dir.join("composer.json").display()))?;
+
+    let mut found: BTreeMap<(String, String), BTreeSet<String>> = BTreeMap::new();
+    let mut add = |package: &str, require: Option<&serde_json::Value>| {
+        for key in platform_requirements(require) {
+            found.entry(key).or_default().insert(package.to_string());
+        }
+    };
+
+    add("root", manifest.get("require"));
+    add("root", manifest.get("require-dev"));
+
+    if let Some(lock) = read_json(&dir.join("composer.lock")) {
+        for section in ["packages", "packages-dev"] {
+            for package in lock
+                .get(section)
+                .and_then(|p| p.as_array())
+                .into_iter()
+                .flatten()
+            {
+                let name = package.get("name").and_then(|n| n.as_str()).unwrap_or("?");
+                add(name, package.get("require"));
+            }
+        }
+    }
+
+    Ok(found
+        .into_iter()
+        .map(|((name, constraint), packages)| Requirement {
+            name,
+            constraint,
+            required_by: packages.into_iter().collect(),
+            satisfied: None,
+        })
+        .collect())
+}
+
+/// Composer to run in `dir`: the project's own composer.phar, then PATH
+fn find_composer(dir: &Path) -> Option<PathBuf> {
+    Some(dir.join("composer.phar"))
+        .filter(|p| p.is_file())
+        .or_else(php_switcher::find_composer)
+}
+
+fn inspect(host: &VirtualHost) -> Result<Option<ComposerProject>, String> {
+    let Some(dir) = find_project(Path::new(&host.docroot)) else {
+        return Ok(None);
+    };
+
+    let mut requirements = requirements(&dir)?;
+    let mut warnings = Vec::new();
+    let php = host_php(host);
+
+    match &php {
+        Some((php, source)) => {
+            let mut enabled: BTreeSet<String> = php_extensions::inventory(php)?
+                .into_iter()
+                .filter(|e| e.enabled)
+                .map(|e| e.name)
+                .collect();
+            // A profile's pool loads its own extensions on top
+            if *source == "profile" {
+                if let Some(profile) = host
+                    .profile
+                    .as_deref()
+                    .and_then(|id| projects::load(id).ok())
+                {
+                    enabled.extend(profile.extensions.iter().map(|e| e.trim().to_lowercase()));
+                }
+            }
+
+            for requirement in &mut requirements {
+                let by = requirement.required_by.join(", ");
+                if requirement.name == "php" {
+                    requirement.satisfied = satisfies(&php.version, &requirement.constraint);
+                    if requirement.satisfied == Some(false) {
+                        warnings.push(format!(
+                            "PHP {} doesn't satisfy php {} (required by {})",
+                            php.version, requirement.constraint, by
+                        ));
+                    }
+                } else if let Some(extension) = extension_of(&requirement.name) {
+                    // Extension versions rarely matter; being loaded does
+                    let loaded = enabled.contains(&extension);
+                    requirement.satisfied = Some(loaded);
+                    if !loaded {
+                        warnings.push(format!(
+                            "{} isn't enabled for PHP {} (required by {})",
+                            extension, php.version, by
+                        ));
+                    }
+                }
+            }
+        }
+        None => warnings.push(format!(
+            "Couldn't tell which PHP serves {}; requirements weren't checked",
+            host.domain
+        )),
+    }
+
+    let composer = find_composer(&dir);
+    if composer.is_none() {
+        warnings.push(
+            "Composer isn't installed (no composer.phar in the project or on PATH)".to_string(),
+        );
+    }
+
+    Ok(Some(ComposerProject {
+        domain: host.domain.clone(),
+        directory: dir.to_string_lossy().to_string(),
+        has_lock: dir.join("composer.lock").is_file(),
+        vendor_installed: dir.join("vendor").join("autoload.php").is_file(),
+        php_version: php.as_ref().map(|(php, _)| php.version.clone()),
+        php_source: php.map(|(_, source)| source.to_string()),
+        requirements,
+        warnings,
+        composer: composer.map(|c| c.to_string_lossy().to_string()),
+    }))
+}
+
+async fn find_host(domain: &str) -> Result<VirtualHost, String> {
+    get_virtual_hosts()
+        .await?
+        .remove(domain)
+        .ok_or_else(|| format!("Host '{}' not found", domain))
+}
+
+/// Composer project behind a host, with its platform requirements checked
+/// against the host's PHP; None when the host isn't a Composer project
+#[tauri::command]
+pub async fn get_composer_project(domain: String) -> Result<Option<ComposerProject>, String> {
+    let host = find_host(&domain).await?;
+    tauri::async_runtime::spawn_blocking(move || inspect(&host))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Start `composer install` or `composer update` for a host as a background
+/// job, run by the host's PHP binary; returns the job ID
+#[tauri::command]
+pub async fn run_composer(
+    domain: String,
+    action: String,
+    window: tauri::Window,
+    jobs: tauri::State<'_, Jobs>,
+) -> Result<String, String> {
+    if !ACTIONS.contains(&action.as_str()) {
+        return Err(format!("Unknown Composer action '{}'", action));
+    }
+
+    let host = find_host(&domain).await?;
+    let (dir, php, composer) = tauri::async_runtime::spawn_blocking(move || {
+        let dir = find_project(Path::new(&host.docroot))
+            .ok_or_else(|| format!("No composer.json found for {}", host.domain))?;
+        let (php, _) = host_php(&host)
+            .ok_or_else(|| format!("Couldn't tell which PHP serves {}", host.domain))?;
+        let composer = find_composer(&dir).ok_or_else(|| "Composer isn't installed".to_string())?;
+        Ok::<_, String>((dir, php, composer))
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    let binary = php
+        .binary
+        .as_deref()
+        .ok_or_else(|| format!("PHP {} has no CLI binary", php.version))?;
+
+    let mut cmd = Command::new(binary);
+    cmd.arg(&composer)
+        .args([action.as_str(), "--no-interaction", "--no-ansi"])
+        .current_dir(&dir)
+        .env("COMPOSER_NO_INTERACTION", "1");
+
+    jobs.spawn(
+        &window,
+        cmd,
+        &format!("composer {} for {} (PHP {})", action, domain, php.version),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounds() {
+        assert_eq!(caret_upper(&[8, 1]), vec![9]);
+        assert_eq!(caret_upper(&[0, 3, 1]), vec![0, 4]);
+        assert_eq!(caret_upper(&[0, 0, 3]), vec![0, 0, 4]);
+        assert_eq!(bump_parent(&[8, 1]), vec![9]);
+        assert_eq!(bump_parent(&[8, 1, 2]), vec![8, 2]);
+        assert_eq!(bump_parent(&[8]), vec![9]);
+    }
+
+    #[test]
+    fn terms() {
+        let cases: &[(&[u64], &str, bool)] = &[
+            (&[8, 1, 0], "^8.1", true),
+            (&[9, 0, 0], "^8.1", false),
+            (&[8, 0, 30], "^8.1", false),
+            (&[8, 4, 1], "~8.1", true),
+            (&[8, 1, 9], "~8.1.2", true),
+            (&[8, 2, 0], "~8.1.2", false),
+            (&[8, 2, 5], "8.2.*", true),
+            (&[8, 3, 0], "8.2.*", false),
+            (&[8, 1, 0], ">=8.1", true),
+            (&[8, 4, 0], "<8.4", false),
+            (&[8, 3, 0], "!=8.3.0", false),
+            (&[8, 3, 0], "*", true),
+            (&[8, 3, 0], "8.3.0@dev", true),
+        ];
+        for (version, term, expected) in cases {
+            assert_eq!(
+                term_matches(version, term),
+                Some(*expected),
+                "{:?} against {}",
+                version,
+                term
+            );
+        }
+        assert_eq!(term_matches(&[8, 3, 0], "^banana"), None);
+    }
+
+    #[test]
+    fn constraints() {
+        let cases = [
+            ("8.1.0", "^8.1", Some(true)),
+            ("8.4.2", "^8.1", Some(true)),
+            ("9.0.0", "^8.1", Some(false)),
+            ("8.0.30", "^8.1", Some(false)),
+            ("8.3.0", "~8.1", Some(true)),
+            ("9.0.0", "~8.1", Some(false)),
+            ("8.1.2", "~8.1.2", Some(true)),
+            ("8.1.1", "~8.1.2", Some(false)),
+            ("8.2.0", "~8.1.2", Some(false)),
+            ("8.2.27", "8.2.*", Some(true)),
+            ("8.3.0", "8.2.*", Some(false)),
+            ("8.1.0", "8.1 - 8.3", Some(true)),
+            ("8.3.14", "8.1 - 8.3", Some(true)),
+            ("8.4.0", "8.1 - 8.3", Some(false)),
+            ("8.0.0", "8.1 - 8.3", Some(false)),
+            ("8.1.0", ">= 8.1 <8.4", Some(true)),
+            ("8.3.9", ">= 8.1 <8.4", Some(true)),
+            ("8.4.0", ">= 8.1 <8.4", Some(false)),
+            ("8.0.0", ">=8.1,<8.4", Some(false)),
+            ("7.4.33", "^7.4 || ^8.0", Some(true)),
+            ("8.4.1", "^7.4 || ^8.0", Some(true)),
+            ("7.3.0", "^7.4 || ^8.0", Some(false)),
+            ("7.4.0", "^7.4 | ^8.0", Some(true)),
+            // Pre-releases match as the release they lead to
+            ("8.3.0-RC1", "^8.3", Some(true)),
+            ("8.3.0RC1", ">=8.3", Some(true)),
+            ("8.3.0-RC1", "8.1 - 8.2", Some(false)),
+            ("8.3", "not a constraint", None),
+            ("unknown", "^8.1", None),
+        ];
+        for (version, constraint, expected) in cases {
+            assert_eq!(
+                satisfies(version, constraint),
+                expected,
+                "{} against {}",
+                version,
+                constraint
+            );
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::paths::config_dir;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
@@ -8,53 +9,9 @@ pub struct AppConfig {
     pub default_php_version: Option<String>,
 }
 
-/// Get the home directory in a cross-platform way
-fn get_home_dir() -> PathBuf {
-    #[cfg(target_os = "windows")]
-    {
-        std::env::var("USERPROFILE")
-            .map(PathBuf::from)
-            .unwrap_or_else(|_| PathBuf::from("C:\\Users\\Default"))
-    }
-
-    #[cfg(not(target_os = "windows"))]
-    {
-        std::env::var("HOME")
-            .map(PathBuf::from)
-            .unwrap_or_else(|_| PathBuf::from("/tmp"))
-    }
-}
-
-/// Get the config directory in a cross-platform way
-fn get_config_dir() -> PathBuf {
-    #[cfg(target_os = "windows")]
-    {
-        let appdata = std::env::var("APPDATA")
-            .map(PathBuf::from)
-            .unwrap_or_else(|_| get_home_dir().join("AppData").join("Roaming"));
-        appdata.join("localhost-manager")
-    }
-
-    #[cfg(target_os = "macos")]
-    {
-        get_home_dir()
-            .join("Library")
-            .join("Application Support")
-            .join("localhost-manager")
-    }
-
-    #[cfg(target_os = "linux")]
-    {
-        std::env::var("XDG_CONFIG_HOME")
-            .map(|p| PathBuf::from(p).join("localhost-manager"))
-            .unwrap_or_else(|_| get_home_dir().join(".config").join("localhost-manager"))
-    }
-}
-
 impl Default for AppConfig {
     fn default() -> Self {
-        let _home = get_home_dir();
-        let config_dir = get_config_dir();
+        let config_dir = config_dir();
 
         Self {
             php_install_dir: config_dir.join("php-versions"),
@@ -66,7 +23,7 @@ impl Default for AppConfig {
 
 impl AppConfig {
     pub fn load() -> anyhow::Result<Self> {
-        let config_dir = get_config_dir();
+        let config_dir = config_dir();
         let config_path = config_dir.join("config.json");
 
         if config_path.exists() {
@@ -77,9 +34,8 @@ impl AppConfig {
         }
     }
 
-    #[allow(dead_code)]
     pub fn save(&self) -> anyhow::Result<()> {
-        let config_dir = get_config_dir();
+        let config_dir = config_dir();
         std::fs::create_dir_all(&config_dir)?;
 
         let config_path = config_dir.join("config.json");
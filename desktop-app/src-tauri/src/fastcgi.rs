@@ -0,0 +1,407 @@
+// ============================================
+// FastCGI Client
+// ============================================
+// Just enough of the FastCGI protocol to run a request against a PHP-FPM pool
+// directly, without a web server in between: one responder request per
+// connection over TCP or a unix socket, answered with the script's CGI
+// output. Used to look inside pools (OPcache, ping), or just to see that
+// something accepts connections on a pool's address.
+
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+const VERSION: u8 = 1;
+const BEGIN_REQUEST: u8 = 1;
+const END_REQUEST: u8 = 3;
+const PARAMS: u8 = 4;
+const STDIN: u8 = 5;
+const STDOUT: u8 = 6;
+const STDERR: u8 = 7;
+
+const RESPONDER: u16 = 1;
+const REQUEST_ID: u16 = 1;
+
+/// Largest content a single record can carry
+const MAX_CONTENT: usize = 0xffff;
+
+/// Where a pool listens, as written in its `listen` directive
+#[derive(Debug, Clone, PartialEq)]
+pub enum Address {
+    Tcp(String),
+    Unix(PathBuf),
+}
+
+impl Address {
+    /// `127.0.0.1:9000`, `[::1]:9000`, a bare port, or a socket path
+    pub fn parse(listen: &str) -> Self {
+        let listen = listen.trim();
+        if listen.starts_with('/') {
+            Address::Unix(PathBuf::from(listen))
+        } else if listen.chars().all(|c| c.is_ascii_digit()) {
+            // A bare port listens on every address; loopback reaches it
+            Address::Tcp(format!("127.0.0.1:{}", listen))
+        } else {
+            Address::Tcp(listen.to_string())
+        }
+    }
+}
+
+impl std::fmt::Display for Address {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Address::Tcp(addr) => write!(f, "{}", addr),
+            Address::Unix(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Response {
+    /// From the `Status:` header; 200 when the script didn't set one
+    pub status: u16,
+    pub body: Vec<u8>,
+    /// What the pool wrote to FCGI_STDERR (PHP warnings, "Primary script unknown")
+    pub stderr: String,
+}
+
+fn record(kind: u8, content: &[u8]) -> Vec<u8> {
+    let padding = (8 - content.len() % 8) % 8;
+    let mut out = Vec::with_capacity(8 + content.len() + padding);
+    out.push(VERSION);
+    out.push(kind);
+    out.extend_from_slice(&REQUEST_ID.to_be_bytes());
+    out.extend_from_slice(&(content.len() as u16).to_be_bytes());
+    out.push(padding as u8);
+    out.push(0);
+    out.extend_from_slice(content);
+    out.resize(out.len() + padding, 0);
+    out
+}
+
+/// A stream's content split into records, followed by the empty record ending it
+fn stream(kind: u8, content: &[u8]) -> Vec<u8> {
+    let mut out: Vec<u8> = content
+        .chunks(MAX_CONTENT)
+        .flat_map(|chunk| record(kind, chunk))
+        .collect();
+    out.extend(record(kind, &[]));
+    out
+}
+
+fn encode_length(out: &mut Vec<u8>, len: usize) {
+    if len < 128 {
+        out.push(len as u8);
+    } else {
+        out.extend_from_slice(&((len as u32) | 0x8000_0000).to_be_bytes());
+    }
+}
+
+fn encode_params(params: &[(&str, &str)]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for (name, value) in params {
+        encode_length(&mut out, name.len());
+        encode_length(&mut out, value.len());
+        out.extend_from_slice(name.as_bytes());
+        out.extend_from_slice(value.as_bytes());
+    }
+    out
+}
+
+/// Split CGI output into status and body
+fn parse_output(stdout: Vec<u8>, stderr: String) -> Response {
+    let split = stdout
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .map(|i| (i, 4))
+        .or_else(|| stdout.windows(2).position(|w| w == b"\n\n").map(|i| (i, 2)));
+
+    let (head, body) = match split {
+        Some((i, sep)) => (
+            String::from_utf8_lossy(&stdout[..i]).to_string(),
+            stdout[i + sep..].to_vec(),
+        ),
+        None => (String::new(), stdout),
+    };
+
+    let status = head
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("status"))
+        .and_then(|(_, value)| value.split_whitespace().next()?.parse().ok())
+        .unwrap_or(200);
+
+    Response {
+        status,
+        body,
+        stderr,
+    }
+}
+
+/// CGI variables for a GET of `script_name`, executed as `script_filename`
+pub fn cgi_params<'a>(
+    script_filename: &'a str,
+    script_name: &'a str,
+    query: &'a str,
+) -> Vec<(&'a str, &'a str)> {
+    vec![
+        ("GATEWAY_INTERFACE", "CGI/1.1"),
+        ("SERVER_PROTOCOL", "HTTP/1.1"),
+        ("SERVER_SOFTWARE", "localhost-manager"),
+        ("SERVER_NAME", "localhost"),
+        ("REMOTE_ADDR", "127.0.0.1"),
+        ("REQUEST_METHOD", "GET"),
+        ("SCRIPT_FILENAME", script_filename),
+        // FPM matches ping.path and pm.status_path against SCRIPT_NAME
+        ("SCRIPT_NAME", script_name),
+        ("REQUEST_URI", script_name),
+        ("QUERY_STRING", query),
+    ]
+}
+
+async fn exchange<S>(mut conn: S, params: &[(&str, &str)], body: &[u8]) -> Result<Response, String>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut begin = RESPONDER.to_be_bytes().to_vec();
+    // flags = 0: close the connection after the request
+    begin.extend_from_slice(&[0; 6]);
+
+    let mut request = record(BEGIN_REQUEST, &begin);
+    request.extend(stream(PARAMS, &encode_params(params)));
+    request.extend(stream(STDIN, body));
+
+    conn.write_all(&request)
+        .await
+        .map_err(|e| format!("Failed to send request: {}", e))?;
+
+    let mut stdout = Vec::new();
+    let mut stderr = Vec::new();
+
+    loop {
+        let mut header = [0u8; 8];
+        conn.read_exact(&mut header)
+            .await
+            .map_err(|e| format!("Connection closed before the response ended: {}", e))?;
+
+        let len = u16::from_be_bytes([header[4], header[5]]) as usize;
+        let mut content = vec![0u8; len + header[6] as usize];
+        conn.read_exact(&mut content)
+            .await
+            .map_err(|e| format!("Truncated response: {}", e))?;
+        content.truncate(len);
+
+        match header[1] {
+            STDOUT => stdout.extend(content),
+            STDERR => stderr.extend(content),
+            END_REQUEST => break,
+            _ => {}
+        }
+    }
+
+    Ok(parse_output(
+        stdout,
+        String::from_utf8_lossy(&stderr).trim().to_string(),
+    ))
+}
+
+/// Run one request against a FastCGI server. `params` are the CGI variables
+/// (SCRIPT_FILENAME, REQUEST_METHOD, ...); `body` is sent as FCGI_STDIN.
+pub async fn request(
+    address: &Address,
+    params: &[(&str, &str)],
+    body: &[u8],
+    timeout: Duration,
+) -> Result<Response, String> {
+    let run = async {
+        match address {
+            Address::Tcp(addr) => {
+                let conn = tokio::net::TcpStream::connect(addr)
+                    .await
+                    .map_err(|e| format!("Failed to connect to {}: {}", addr, e))?;
+                exchange(conn, params, body).await
+            }
+            #[cfg(unix)]
+            Address::Unix(path) => {
+                let conn = tokio::net::UnixStream::connect(path)
+                    .await
+                    .map_err(|e| format!("Failed to connect to {}: {}", path.display(), e))?;
+                exchange(conn, params, body).await
+            }
+            #[cfg(not(unix))]
+            Address::Unix(path) => Err(format!(
+                "Unix sockets aren't supported here: {}",
+                path.display()
+            )),
+        }
+    };
+
+    tokio::time::timeout(timeout, run)
+        .await
+        .map_err(|_| format!("{} did not answer within {:?}", address, timeout))?
+}
+
+/// Open a connection and close it again without sending anything: proves
+/// something listens on `address` without a request showing up in the pool's log
+pub async fn connect(address: &Address, timeout: Duration) -> Result<(), String> {
+    let run = async {
+        match address {
+            Address::Tcp(addr) => tokio::net::TcpStream::connect(addr)
+                .await
+                .map(|_| ())
+                .map_err(|e| format!("Failed to connect to {}: {}", addr, e)),
+            #[cfg(unix)]
+            Address::Unix(path) => tokio::net::UnixStream::connect(path)
+                .await
+                .map(|_| ())
+                .map_err(|e| format!("Failed to connect to {}: {}", path.display(), e)),
+            #[cfg(not(unix))]
+            Address::Unix(path) => Err(format!(
+                "Unix sockets aren't supported here: {}",
+                path.display()
+            )),
+        }
+    };
+
+    tokio::time::timeout(timeout, run)
+        .await
+        .map_err(|_| format!("{} did not answer within {:?}", address, timeout))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(record: &[u8]) -> (u8, u8, u16, usize, usize) {
+        (
+            record[0],
+            record[1],
+            u16::from_be_bytes([record[2], record[3]]),
+            u16::from_be_bytes([record[4], record[5]]) as usize,
+            record[6] as usize,
+        )
+    }
+
+    #[test]
+    fn records_are_padded_to_eight_bytes() {
+        for (len, padding) in [(0, 0), (1, 7), (5, 3), (8, 0), (9, 7), (16, 0)] {
+            let content = vec![b'x'; len];
+            let out = record(STDOUT, &content);
+
+            assert_eq!(out.len(), 8 + len + padding, "content of {}", len);
+            assert_eq!(out.len() % 8, 0);
+            assert_eq!(header(&out), (VERSION, STDOUT, REQUEST_ID, len, padding));
+            assert_eq!(&out[8..8 + len], &content[..]);
+            assert!(out[8 + len..].iter().all(|b| *b == 0));
+        }
+    }
+
+    #[test]
+    fn streams_are_split_and_terminated() {
+        let content = vec![b'x'; MAX_CONTENT + 1];
+        let out = stream(PARAMS, &content);
+
+        // 0xffff bytes need one byte of padding
+        let first = 8 + MAX_CONTENT + 1;
+        assert_eq!(header(&out), (VERSION, PARAMS, REQUEST_ID, MAX_CONTENT, 1));
+        assert_eq!(header(&out[first..]), (VERSION, PARAMS, REQUEST_ID, 1, 7));
+        assert_eq!(
+            header(&out[first + 16..]),
+            (VERSION, PARAMS, REQUEST_ID, 0, 0)
+        );
+        assert_eq!(out.len(), first + 16 + 8);
+
+        assert_eq!(stream(STDIN, &[]), record(STDIN, &[]));
+    }
+
+    #[test]
+    fn param_lengths_switch_to_four_bytes_at_128() {
+        let short = "n".repeat(127);
+        let long = "v".repeat(128);
+        let out = encode_params(&[(short.as_str(), long.as_str())]);
+
+        assert_eq!(out[0], 127);
+        assert_eq!(&out[1..5], &[0x80, 0, 0, 128]);
+        assert_eq!(&out[5..132], short.as_bytes());
+        assert_eq!(&out[132..], long.as_bytes());
+
+        let out = encode_params(&[("A", ""), ("BC", "d")]);
+        assert_eq!(out, b"\x01\x00A\x02\x01BCd");
+    }
+
+    #[test]
+    fn output_is_split_at_the_first_blank_line() {
+        let response = parse_output(
+            b"Content-type: text/plain\r\n\r\npong\r\n\r\nrest".to_vec(),
+            String::new(),
+        );
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, b"pong\r\n\r\nrest");
+
+        let response = parse_output(
+            b"Status: 404 Not Found\nContent-type: text/html\n\nFile not found.\n".to_vec(),
+            "Primary script unknown".to_string(),
+        );
+        assert_eq!(response.status, 404);
+        assert_eq!(response.body, b"File not found.\n");
+        assert_eq!(response.stderr, "Primary script unknown");
+
+        let response = parse_output(
+            b"X-Powered-By: PHP\r\nstatus:503\r\n\r\n".to_vec(),
+            String::new(),
+        );
+        assert_eq!(response.status, 503);
+        assert!(response.body.is_empty());
+
+        let response = parse_output(b"no headers".to_vec(), String::new());
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, b"no headers");
+    }
+
+    #[test]
+    fn addresses() {
+        assert_eq!(
+            Address::parse("9000"),
+            Address::Tcp("127.0.0.1:9000".into())
+        );
+        assert_eq!(
+            Address::parse(" [::1]:9001 "),
+            Address::Tcp("[::1]:9001".into())
+        );
+        assert_eq!(
+            Address::parse("/run/php/php8.3-fpm.sock"),
+            Address::Unix(PathBuf::from("/run/php/php8.3-fpm.sock"))
+        );
+    }
+
+    #[tokio::test]
+    async fn exchange_reads_records_until_the_end_request() {
+        let (client, mut server) = tokio::io::duplex(4096);
+
+        let fpm = tokio::spawn(async move {
+            let mut request = vec![0u8; 1024];
+            let n = server.read(&mut request).await.unwrap();
+            request.truncate(n);
+
+            let mut reply = record(STDERR, b"PHP Notice: something\n");
+            reply.extend(record(STDOUT, b"Status: 201 Created\r\n"));
+            reply.extend(record(STDOUT, b"\r\ncreated"));
+            reply.extend(record(STDOUT, &[]));
+            reply.extend(record(END_REQUEST, &[0; 8]));
+            server.write_all(&reply).await.unwrap();
+            request
+        });
+
+        let response = exchange(client, &[("SCRIPT_NAME", "/ping")], b"")
+            .await
+            .unwrap();
+        assert_eq!(response.status, 201);
+        assert_eq!(response.body, b"created");
+        assert_eq!(response.stderr, "PHP Notice: something");
+
+        let request = fpm.await.unwrap();
+        assert_eq!(header(&request), (VERSION, BEGIN_REQUEST, REQUEST_ID, 8, 0));
+        assert_eq!(&request[8..10], &RESPONDER.to_be_bytes());
+        assert_eq!(header(&request[16..]).1, PARAMS);
+    }
+}
@@ -0,0 +1,693 @@
+// ============================================
+// PHP-FPM Pool Manager
+// ============================================
+// Pools of each PHP version as defined in its pool directory, editable from
+// the app: listen address, process manager, max_children and user. Edits
+// rewrite only the directives they change, so comments and everything else
+// in hand-written pool files survive. `project-*` pools belong to project
+// profiles and are edited there.
+//
+// A virtual host can be mapped to a pool (`fpm_pool` in hosts.json, holding
+// the pool's listen address for the vhost generator). After a change only the
+// FPM master of that version is reloaded.
+
+use crate::fastcgi::Address;
+use crate::hosts_manager::{get_hosts_file_path, regenerate_and_apply, write_hosts_file};
+use crate::php_fpm::{self, Pool, PoolHealth};
+use crate::settings::{Settings, WebServer};
+use crate::system::{remove_file_privileged, write_file_privileged};
+use crate::types::FpmPoolRef;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+
+pub const PM_MODES: &[&str] = &["static", "dynamic", "ondemand"];
+
+/// Prefix of the pools project profiles generate
+const PROFILE_POOL_PREFIX: &str = "project-";
+
+/// What the app lets you edit in a pool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PoolSettings {
+    pub name: String,
+    /// `127.0.0.1:9001`, a bare port, or a unix socket path
+    pub listen: String,
+    pub pm: String,
+    pub max_children: u32,
+    /// Left as is when None
+    #[serde(default)]
+    pub user: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PoolOverview {
+    #[serde(flatten)]
+    pub pool: Pool,
+    /// Generated for a project profile; edit the profile instead
+    pub profile_pool: bool,
+    /// Virtual hosts mapped to the pool
+    pub hosts: Vec<String>,
+    pub health: PoolHealth,
+}
+
+/// Pool names end up in file names and section headers
+fn validate_name(name: &str) -> Result<(), String> {
+    if name.is_empty()
+        || name == "global"
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+    {
+        return Err(format!("Invalid pool name '{}'", name));
+    }
+    if name.starts_with(PROFILE_POOL_PREFIX) {
+        return Err(format!(
+            "Pools named {}* belong to project profiles; edit the profile instead",
+            PROFILE_POOL_PREFIX
+        ));
+    }
+    Ok(())
+}
+
+fn validate(settings: &PoolSettings) -> Result<(), String> {
+    validate_name(&settings.name)?;
+
+    let listen = settings.listen.trim();
+    if listen.is_empty() || listen.contains(|c: char| c.is_whitespace() || "\";".contains(c)) {
+        return Err(format!("Invalid listen address '{}'", settings.listen));
+    }
+    if !PM_MODES.contains(&settings.pm.as_str()) {
+        return Err(format!(
+            "Invalid process manager '{}'; use {}",
+            settings.pm,
+            PM_MODES.join(", ")
+        ));
+    }
+    if settings.max_children == 0 {
+        return Err("pm.max_children must be at least 1".to_string());
+    }
+    if let Some(user) = settings.user.as_deref() {
+        if user.is_empty()
+            || !user
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+        {
+            return Err(format!("Invalid user '{}'", user));
+        }
+    }
+    Ok(())
+}
+
+/// Key of an active `key = value` line
+fn directive_key(line: &str) -> Option<&str> {
+    let line = line.trim();
+    if line.starts_with(';') || line.starts_with('[') {
+        return None;
+    }
+    line.split_once('=').map(|(key, _)| key.trim())
+}
+
+/// Line range of a pool's section: header, then everything up to the next one
+fn section_range(lines: &[String], name: &str) -> Option<(usize, usize)> {
+    let header = format!("[{}]", name);
+    let start = lines.iter().position(|l| l.trim() == header)?;
+    let end = lines[start + 1..]
+        .iter()
+        .position(|l| l.trim().starts_with('['))
+        .map(|i| start + 1 + i)
+        .unwrap_or(lines.len());
+    Some((start, end))
+}
+
+/// Set directives inside a pool's section: existing lines are replaced in
+/// place, missing ones go after the section's last setting
+fn set_directives(content: &str, name: &str, directives: &[(&str, String)]) -> String {
+    let mut lines: Vec<String> = content.lines().map(String::from).collect();
+    let (start, end) = match section_range(&lines, name) {
+        Some(range) => range,
+        None => {
+            lines.push(format!("[{}]", name));
+            (lines.len() - 1, lines.len())
+        }
+    };
+
+    let mut missing = Vec::new();
+    for (key, value) in directives {
+        let line = format!("{} = {}", key, value);
+        match (start + 1..end).find(|&i| directive_key(&lines[i]) == Some(key)) {
+            Some(i) => lines[i] = line,
+            None => missing.push(line),
+        }
+    }
+
+    let mut at = end;
+    while at > start + 1 && lines[at - 1].trim().is_empty() {
+        at -= 1;
+    }
+    for (offset, line) in missing.into_iter().enumerate() {
+        lines.insert(at + offset, line);
+    }
+
+    lines.join("\n") + "\n"
+}
+
+/// Whether a dynamic pool's spare-server settings will let FPM start:
+/// min_spare <= start <= max_spare <= max_children
+fn spare_servers_valid(pool: &Pool, max_children: u32) -> bool {
+    match (
+        pool.min_spare_servers,
+        pool.start_servers,
+        pool.max_spare_servers,
+    ) {
+        (Some(min), Some(start), Some(max)) => {
+            min >= 1 && min <= start && start <= max && max <= max_children
+        }
+        _ => false,
+    }
+}
+
+/// User the web server's workers run as: the running server's, else the
+/// distribution's usual account
+fn web_server_user() -> Option<String> {
+    let is_user = |user: &str| {
+        Command::new("id")
+            .args(["-u", user])
+            .output()
+            .is_ok_and(|o| o.status.success())
+    };
+
+    // The master runs as root; its workers show the configured user
+    let running = Command::new("ps")
+        .args(["axo", "user=,comm="])
+        .output()
+        .ok()
+        .and_then(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .filter_map(|line| line.split_once(char::is_whitespace))
+                .find(|(user, comm)| {
+                    let comm = comm.trim().rsplit('/').next().unwrap_or_default();
+                    *user != "root" && ["apache2", "httpd", "nginx"].contains(&comm)
+                })
+                .map(|(user, _)| user.to_string())
+        });
+
+    let fallback = match Settings::load().unwrap_or_default().web_server {
+        WebServer::Apache => ["www-data", "_www", "apache", "http"],
+        WebServer::Nginx => ["www-data", "_www", "nginx", "http"],
+    };
+    running.or_else(|| {
+        fallback
+            .into_iter()
+            .find(|user| is_user(user))
+            .map(String::from)
+    })
+}
+
+fn primary_group(user: &str) -> Option<String> {
+    let output = Command::new("id").args(["-gn", user]).output().ok()?;
+    let group = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !group.is_empty()).then_some(group)
+}
+
+/// Directives a save writes
+fn directives(
+    settings: &PoolSettings,
+    existing: Option<&Pool>,
+    web_user: Option<&str>,
+) -> Vec<(&'static str, String)> {
+    let mut directives = vec![
+        ("listen", settings.listen.trim().to_string()),
+        ("pm", settings.pm.clone()),
+        ("pm.max_children", settings.max_children.to_string()),
+    ];
+
+    if let Some(user) = &settings.user {
+        directives.push(("user", user.clone()));
+    }
+
+    // dynamic refuses to start unless min <= start <= max spare <= max_children;
+    // values that already satisfy that are the user's tuning and stay
+    if settings.pm == "dynamic"
+        && !existing.is_some_and(|pool| spare_servers_valid(pool, settings.max_children))
+    {
+        let max_spare = settings.max_children.clamp(1, 3);
+        directives.extend([
+            ("pm.start_servers", "1".to_string()),
+            ("pm.min_spare_servers", "1".to_string()),
+            ("pm.max_spare_servers", max_spare.to_string()),
+        ]);
+    }
+
+    // Only the web server may reach the socket: whoever can connect runs PHP
+    // as the pool's user
+    if settings.listen.trim().starts_with('/') {
+        if let Some(user) = web_user {
+            let group = primary_group(user).unwrap_or_else(|| user.to_string());
+            directives.extend([
+                ("listen.owner", user.to_string()),
+                ("listen.group", group),
+                ("listen.mode", "0660".to_string()),
+            ]);
+        }
+    }
+
+    // Health checks and stats over FastCGI; web servers only forward .php here
+    if existing.is_none_or(|p| p.ping_path.is_none()) {
+        directives.push(("ping.path", "/ping".to_string()));
+    }
+    if existing.is_none_or(|p| p.status_path.is_none()) {
+        directives.push(("pm.status_path", "/status".to_string()));
+    }
+
+    directives
+}
+
+/// Apply `update` to every host in hosts.json; writes back when it reports a change
+fn update_hosts<F>(mut update: F) -> Result<bool, String>
+where
+    F: FnMut(&str, &mut serde_json::Map<String, serde_json::Value>) -> bool,
+{
+    let hosts_file = get_hosts_file_path();
+    let Ok(content) = std::fs::read_to_string(&hosts_file) else {
+        return Ok(false);
+    };
+
+    let mut hosts: HashMap<String, serde_json::Value> =
+        serde_json::from_str(&content).map_err(|e| format!("Failed to parse hosts file: {}", e))?;
+
+    let mut changed = false;
+    for (domain, host) in hosts.iter_mut() {
+        if let Some(host) = host.as_object_mut() {
+            changed |= update(domain, host);
+        }
+    }
+
+    if changed {
+        let json_content = serde_json::to_string_pretty(&hosts)
+            .map_err(|e| format!("Failed to serialize hosts: {}", e))?;
+        write_hosts_file(&hosts_file, &json_content)?;
+    }
+
+    Ok(changed)
+}
+
+/// Regenerate the vhosts after hosts.json changed their pools; until then
+/// the web server keeps proxying to the old addresses
+async fn apply_hosts() -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(regenerate_and_apply)
+        .await
+        .map_err(|e| e.to_string())?
+        .map(|_| ())
+}
+
+fn pool_ref(host: &serde_json::Map<String, serde_json::Value>) -> Option<FpmPoolRef> {
+    serde_json::from_value(host.get("fpm_pool")?.clone()).ok()
+}
+
+/// Point hosts mapped to a pool at its new address, or unmap them with None;
+/// true when a host changed
+fn rebind_hosts(php_version: &str, name: &str, listen: Option<&str>) -> Result<bool, String> {
+    update_hosts(|_, host| {
+        let Some(mut pool) =
+            pool_ref(host).filter(|p| p.php_version == php_version && p.name == name)
+        else {
+            return false;
+        };
+
+        match listen {
+            Some(listen) if listen == pool.listen => false,
+            Some(listen) => {
+                pool.listen = listen.to_string();
+                host.insert(
+                    "fpm_pool".to_string(),
+                    serde_json::to_value(pool).unwrap_or_default(),
+                );
+                true
+            }
+            None => host.remove("fpm_pool").is_some(),
+        }
+    })
+}
+
+/// Domains mapped to each pool of a version, by pool name
+fn mapped_hosts(php_version: &str) -> HashMap<String, Vec<String>> {
+    let mut mapped: HashMap<String, Vec<String>> = HashMap::new();
+    let _ = update_hosts(|domain, host| {
+        if let Some(pool) = pool_ref(host).filter(|p| p.php_version == php_version) {
+            mapped
+                .entry(pool.name)
+                .or_default()
+                .push(domain.to_string());
+        }
+        false
+    });
+    for domains in mapped.values_mut() {
+        domains.sort();
+    }
+    mapped
+}
+
+/// Pools of a PHP version with their health, status page numbers and hosts
+#[tauri::command]
+pub async fn get_fpm_pools(version: String) -> Result<Vec<PoolOverview>, String> {
+    let (php, pools) = tauri::async_runtime::spawn_blocking(move || {
+        let php = php_fpm::find(&version)?;
+        let pools = php_fpm::pools(&php);
+        Ok::<_, String>((php, pools))
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    let mut hosts = mapped_hosts(&php.branch());
+
+    let mut checks = tokio::task::JoinSet::new();
+    for (index, pool) in pools.into_iter().enumerate() {
+        checks.spawn(async move {
+            let health = php_fpm::check(&pool).await;
+            (index, pool, health)
+        });
+    }
+
+    let mut results = checks.join_all().await;
+    results.sort_by_key(|(index, _, _)| *index);
+
+    Ok(results
+        .into_iter()
+        .map(|(_, pool, health)| PoolOverview {
+            profile_pool: pool.name.starts_with(PROFILE_POOL_PREFIX),
+            hosts: hosts.remove(&pool.name).unwrap_or_default(),
+            pool,
+            health,
+        })
+        .collect())
+}
+
+/// Create a pool, or update the one with the same name, then reload its master.
+/// Hosts mapped to the pool follow a new listen address.
+#[tauri::command]
+pub async fn save_fpm_pool(version: String, settings: PoolSettings) -> Result<Pool, String> {
+    validate(&settings)?;
+    let php = php_fpm::find(&version)?;
+    let php_version = php.branch();
+
+    let existing = php_fpm::pools(&php)
+        .into_iter()
+        .find(|p| p.name == settings.name);
+
+    // Two masters can't bind the same address
+    let address = Address::parse(&settings.listen);
+    if let Some(other) = php_fpm::all_pools().into_iter().find(|p| {
+        Address::parse(&p.listen) == address
+            && !(p.php_version == php_version && p.name == settings.name)
+    }) {
+        return Err(format!(
+            "{} is already used by pool '{}' of PHP {}",
+            settings.listen, other.name, other.php_version
+        ));
+    }
+
+    let web_user = if settings.listen.trim().starts_with('/') {
+        let user = tauri::async_runtime::spawn_blocking(web_server_user)
+            .await
+            .map_err(|e| e.to_string())?;
+        Some(user.ok_or_else(|| {
+            "Couldn't tell which user the web server runs as; use a TCP address for this pool"
+                .to_string()
+        })?)
+    } else {
+        None
+    };
+
+    let directives = directives(&settings, existing.as_ref(), web_user.as_deref());
+    let (file, content) = match &existing {
+        Some(pool) => {
+            let content = std::fs::read_to_string(&pool.file)
+                .map_err(|e| format!("Failed to read {}: {}", pool.file, e))?;
+            (
+                Path::new(&pool.file).to_path_buf(),
+                set_directives(&content, &settings.name, &directives),
+            )
+        }
+        None => {
+            let dir = php_fpm::pool_dir(&php).ok_or_else(|| {
+                format!("No PHP-FPM pool directory found for PHP {}", php.version)
+            })?;
+            let file = dir.join(format!("{}.conf", settings.name));
+            if file.exists() {
+                return Err(format!("{} already exists", file.display()));
+            }
+            let mut directives = directives;
+            directives.extend([
+                ("pm.process_idle_timeout", "10s".to_string()),
+                ("catch_workers_output", "yes".to_string()),
+            ]);
+            let header = "; Created by Localhost Manager\n";
+            (file, set_directives(header, &settings.name, &directives))
+        }
+    };
+
+    write_file_privileged(&file, &content).await?;
+    php_fpm::forget_pools();
+    let rebound = rebind_hosts(&php_version, &settings.name, Some(settings.listen.trim()))?;
+
+    php_fpm::reload(&php)
+        .await
+        .map_err(|e| format!("Pool saved, but PHP-FPM could not be reloaded: {}", e))?;
+    if rebound {
+        apply_hosts().await.map_err(|e| {
+            format!(
+                "Pool saved, but the sites using it could not be updated: {}",
+                e
+            )
+        })?;
+    }
+
+    php_fpm::pools(&php)
+        .into_iter()
+        .find(|p| p.name == settings.name)
+        .ok_or_else(|| {
+            format!(
+                "Pool '{}' was written but can't be read back",
+                settings.name
+            )
+        })
+}
+
+/// Remove a pool, unmap its hosts and reload its master
+#[tauri::command]
+pub async fn delete_fpm_pool(version: String, name: String) -> Result<(), String> {
+    validate_name(&name)?;
+    let php = php_fpm::find(&version)?;
+    let pools = php_fpm::pools(&php);
+
+    let pool = pools
+        .iter()
+        .find(|p| p.name == name)
+        .ok_or_else(|| format!("Pool '{}' not found for PHP {}", name, php.version))?;
+    // A master without pools refuses to start
+    if pools.len() == 1 {
+        return Err(format!(
+            "'{}' is the only pool of PHP {}; PHP-FPM needs at least one",
+            name, php.version
+        ));
+    }
+
+    let file = Path::new(&pool.file);
+    let content = std::fs::read_to_string(file)
+        .map_err(|e| format!("Failed to read {}: {}", pool.file, e))?;
+    let mut lines: Vec<String> = content.lines().map(String::from).collect();
+
+    if pools.iter().filter(|p| p.file == pool.file).count() == 1 {
+        remove_file_privileged(file).await?;
+    } else if let Some((start, end)) = section_range(&lines, &name) {
+        lines.drain(start..end);
+        write_file_privileged(file, &(lines.join("\n") + "\n")).await?;
+    }
+
+    php_fpm::forget_pools();
+    let unmapped = rebind_hosts(&php.branch(), &name, None)?;
+
+    php_fpm::reload(&php)
+        .await
+        .map_err(|e| format!("Pool deleted, but PHP-FPM could not be reloaded: {}", e))?;
+    if unmapped {
+        apply_hosts().await.map_err(|e| {
+            format!(
+                "Pool deleted, but the sites using it could not be updated: {}",
+                e
+            )
+        })?;
+    }
+    Ok(())
+}
+
+/// Map a virtual host to a pool, or back to the shared pool with `None`.
+/// A host bound to a project profile leaves it; the profile's pool is its own.
+#[tauri::command]
+pub async fn assign_fpm_pool(
+    domain: String,
+    version: Option<String>,
+    pool: Option<String>,
+) -> Result<(), String> {
+    let target = match (version, pool) {
+        (Some(version), Some(name)) => {
+            let php = php_fpm::find(&version)?;
+            let pool = php_fpm::pools(&php)
+                .into_iter()
+                .find(|p| p.name == name)
+                .ok_or_else(|| format!("Pool '{}' not found for PHP {}", name, php.version))?;
+            Some(FpmPoolRef {
+                php_version: php.branch(),
+                name: pool.name,
+                listen: pool.listen,
+            })
+        }
+        _ => None,
+    };
+
+    let mut found = false;
+    let changed = update_hosts(|host_domain, host| {
+        if host_domain != domain {
+            return false;
+        }
+        found = true;
+        let before = host.clone();
+        match &target {
+            Some(pool) => {
+                host.remove("profile");
+                host.insert(
+                    "fpm_pool".to_string(),
+                    serde_json::to_value(pool).unwrap_or_default(),
+                );
+            }
+            None => {
+                host.remove("fpm_pool");
+            }
+        }
+        *host != before
+    })?;
+
+    if !found {
+        return Err(format!("Host '{}' not found", domain));
+    }
+    if changed {
+        apply_hosts().await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(listen: &str, pm: &str, max_children: u32) -> PoolSettings {
+        PoolSettings {
+            name: "www".to_string(),
+            listen: listen.to_string(),
+            pm: pm.to_string(),
+            max_children,
+            user: None,
+        }
+    }
+
+    fn pool(spare: Option<(u32, u32, u32)>) -> Pool {
+        Pool {
+            name: "www".to_string(),
+            php_version: "8.3".to_string(),
+            listen: "127.0.0.1:9000".to_string(),
+            file: String::new(),
+            ping_path: Some("/ping".to_string()),
+            ping_response: None,
+            status_path: Some("/status".to_string()),
+            pm: Some("dynamic".to_string()),
+            max_children: Some(10),
+            min_spare_servers: spare.map(|(min, _, _)| min),
+            start_servers: spare.map(|(_, start, _)| start),
+            max_spare_servers: spare.map(|(_, _, max)| max),
+            user: None,
+        }
+    }
+
+    fn keys(directives: &[(&'static str, String)]) -> Vec<&'static str> {
+        directives.iter().map(|(key, _)| *key).collect()
+    }
+
+    #[test]
+    fn spare_servers_are_kept_when_valid() {
+        let tuned = pool(Some((2, 4, 6)));
+        let written = directives(
+            &settings("127.0.0.1:9000", "dynamic", 10),
+            Some(&tuned),
+            None,
+        );
+        assert!(!keys(&written).contains(&"pm.start_servers"));
+
+        // Lowering max_children below max_spare_servers would stop FPM from starting
+        let written = directives(
+            &settings("127.0.0.1:9000", "dynamic", 4),
+            Some(&tuned),
+            None,
+        );
+        assert!(written.contains(&("pm.start_servers", "1".to_string())));
+        assert!(written.contains(&("pm.min_spare_servers", "1".to_string())));
+        assert!(written.contains(&("pm.max_spare_servers", "3".to_string())));
+    }
+
+    #[test]
+    fn spare_servers_are_filled_when_missing_or_invalid() {
+        for spare in [None, Some((0, 1, 3)), Some((3, 2, 4)), Some((1, 5, 4))] {
+            let written = directives(
+                &settings("127.0.0.1:9000", "dynamic", 10),
+                Some(&pool(spare)),
+                None,
+            );
+            assert!(
+                written.contains(&("pm.start_servers", "1".to_string())),
+                "{:?}",
+                spare
+            );
+        }
+
+        let written = directives(&settings("127.0.0.1:9000", "dynamic", 2), None, None);
+        assert!(written.contains(&("pm.max_spare_servers", "2".to_string())));
+
+        let written = directives(&settings("127.0.0.1:9000", "ondemand", 10), None, None);
+        assert!(!keys(&written).contains(&"pm.start_servers"));
+    }
+
+    #[test]
+    fn sockets_belong_to_the_web_server() {
+        let written = directives(
+            &settings("/run/php/www.sock", "static", 5),
+            None,
+            Some("root"),
+        );
+        assert!(written.contains(&("listen.owner", "root".to_string())));
+        assert!(written.contains(&("listen.group", "root".to_string())));
+        assert!(written.contains(&("listen.mode", "0660".to_string())));
+        assert!(!written.iter().any(|(_, value)| value == "0666"));
+
+        let written = directives(&settings("127.0.0.1:9000", "static", 5), None, Some("root"));
+        assert!(!keys(&written).contains(&"listen.owner"));
+        assert!(!keys(&written).contains(&"listen.mode"));
+    }
+
+    #[test]
+    fn directives_replace_lines_in_their_own_section() {
+        let content = "; Pool\n[www]\nlisten = /run/php/www.sock\nlisten.mode = 0666 ; old\n\n[other]\nlisten = 9001\n";
+        let updated = set_directives(
+            content,
+            "www",
+            &[
+                ("listen.mode", "0660".to_string()),
+                ("listen.owner", "www-data".to_string()),
+            ],
+        );
+        assert_eq!(
+            updated,
+            "; Pool\n[www]\nlisten = /run/php/www.sock\nlisten.mode = 0660\nlisten.owner = www-data\n\n[other]\nlisten = 9001\n"
+        );
+    }
+}
@@ -1,9 +1,23 @@
-use crate::types::{ServicesStatus, VirtualHost, VirtualHostAlias};
+use crate::hosts_watcher;
+use crate::jobs::Jobs;
+use crate::paths::{self, Layout};
+use crate::php_discovery;
+use crate::php_fpm;
+use crate::php_ini;
+use crate::php_overrides;
+use crate::php_switcher;
+use crate::services::{self, Service, ServiceAction};
+use crate::settings::{PhpConfigMode, Ports, Settings, WebServer, SETTINGS_CHANGED_EVENT};
+use crate::types::{PhpVersion, PortsStatus, ServicesStatus, VirtualHost, VirtualHostAlias};
+use crate::vhost_templates;
 use anyhow::Result;
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::net::{IpAddr, SocketAddr, TcpStream};
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::time::Duration;
+use tauri::Emitter;
 
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
@@ -16,7 +30,7 @@ const CREATE_NO_WINDOW: u32 = 0x08000000;
 // ============================================
 
 /// Execute a command and return stdout if successful
-fn run_command(cmd: &str, args: &[&str]) -> Option<String> {
+pub(crate) fn run_command(cmd: &str, args: &[&str]) -> Option<String> {
     let mut command = Command::new(cmd);
     command.args(args);
 
@@ -32,7 +46,10 @@ fn run_command(cmd: &str, args: &[&str]) -> Option<String> {
 
 /// Execute a command and return the full output (for Windows with hidden window)
 #[cfg(target_os = "windows")]
-fn run_windows_command(cmd: &str, args: &[&str]) -> std::io::Result<std::process::Output> {
+pub(crate) fn run_windows_command(
+    cmd: &str,
+    args: &[&str],
+) -> std::io::Result<std::process::Output> {
     Command::new(cmd)
         .args(args)
         .creation_flags(CREATE_NO_WINDOW)
@@ -40,7 +57,10 @@ fn run_windows_command(cmd: &str, args: &[&str]) -> std::io::Result<std::process
 }
 
 #[cfg(not(target_os = "windows"))]
-fn run_windows_command(cmd: &str, args: &[&str]) -> std::io::Result<std::process::Output> {
+pub(crate) fn run_windows_command(
+    cmd: &str,
+    args: &[&str],
+) -> std::io::Result<std::process::Output> {
     Command::new(cmd).args(args).output()
 }
 
@@ -55,7 +75,7 @@ fn find_and_execute(paths: &[&str], args: &[&str]) -> Option<String> {
 }
 
 /// Check if a command exists using 'which' (Unix) or 'where' (Windows)
-fn command_exists(cmd: &str) -> bool {
+pub(crate) fn command_exists(cmd: &str) -> bool {
     let check_cmd = if cfg!(target_os = "windows") {
         "where"
     } else {
@@ -64,34 +84,24 @@ fn command_exists(cmd: &str) -> bool {
     run_command(check_cmd, &[cmd]).is_some()
 }
 
-fn get_home_dir() -> String {
-    if cfg!(target_os = "windows") {
-        std::env::var("USERPROFILE").unwrap_or_else(|_| {
-            // Fallback: try to construct from HOMEDRIVE + HOMEPATH
-            let drive = std::env::var("HOMEDRIVE").unwrap_or_else(|_| String::from("C:"));
-            let path =
-                std::env::var("HOMEPATH").unwrap_or_else(|_| String::from("\\Users\\Default"));
-            format!("{}{}", drive, path)
-        })
-    } else {
-        std::env::var("HOME").unwrap_or_else(|_| {
-            // Fallback: try to get from user info
-            String::from("/tmp")
-        })
-    }
+pub(crate) fn get_hosts_file_path() -> PathBuf {
+    Layout::resolve().hosts_file
 }
 
-fn get_hosts_file_path() -> PathBuf {
-    let home = get_home_dir();
-    if cfg!(target_os = "windows") {
-        PathBuf::from(format!("{}\\localhost-manager\\conf\\hosts.json", home))
-    } else {
-        PathBuf::from(format!("{}/localhost-manager/conf/hosts.json", home))
-    }
+/// Write hosts.json from the app. The watcher learns the new content first,
+/// so it doesn't rebuild for a change the app made itself.
+pub(crate) fn write_hosts_file(hosts_file: &Path, content: &str) -> Result<(), String> {
+    hosts_watcher::record_write(content);
+    fs::write(hosts_file, content).map_err(|e| format!("Failed to write hosts file: {}", e))
 }
 
 #[tauri::command]
 pub async fn get_virtual_hosts() -> Result<HashMap<String, VirtualHost>, String> {
+    load_virtual_hosts()
+}
+
+/// hosts.json parsed into hosts, tolerating legacy alias formats
+pub(crate) fn load_virtual_hosts() -> Result<HashMap<String, VirtualHost>, String> {
     let hosts_file = get_hosts_file_path();
 
     if !hosts_file.exists() {
@@ -138,6 +148,57 @@ pub async fn get_virtual_hosts() -> Result<HashMap<String, VirtualHost>, String>
                 .unwrap_or("static")
                 .to_string();
 
+            let profile = host_obj
+                .get("profile")
+                .and_then(|v| v.as_str())
+                .filter(|v| !v.is_empty())
+                .map(String::from);
+
+            let fpm_pool = host_obj
+                .get("fpm_pool")
+                .and_then(|v| serde_json::from_value(v.clone()).ok());
+
+            let framework = host_obj
+                .get("framework")
+                .and_then(|v| v.as_str())
+                .filter(|v| !v.is_empty())
+                .map(String::from);
+
+            let template = host_obj
+                .get("template")
+                .and_then(|v| v.as_str())
+                .filter(|v| !v.is_empty())
+                .map(String::from);
+
+            let rewrites = host_obj
+                .get("rewrites")
+                .and_then(|v| v.as_array())
+                .map(|lines| {
+                    lines
+                        .iter()
+                        .filter_map(|l| l.as_str().map(String::from))
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            let wildcard = host_obj
+                .get("wildcard")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+
+            let subdomains = host_obj
+                .get("subdomains")
+                .and_then(|v| v.as_array())
+                .map(|labels| {
+                    labels
+                        .iter()
+                        .filter_map(|l| l.as_str())
+                        .map(|l| l.trim().to_string())
+                        .filter(|l| !l.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default();
+
             // Parse aliases
             let mut aliases = Vec::new();
 
@@ -200,6 +261,13 @@ pub async fn get_virtual_hosts() -> Result<HashMap<String, VirtualHost>, String>
                 active,
                 ssl,
                 host_type,
+                profile,
+                fpm_pool,
+                framework,
+                rewrites,
+                template,
+                wildcard,
+                subdomains,
             };
 
             hosts.insert(domain, host);
@@ -211,23 +279,70 @@ pub async fn get_virtual_hosts() -> Result<HashMap<String, VirtualHost>, String>
 
 #[tauri::command]
 pub async fn get_services_status() -> Result<ServicesStatus, String> {
-    let apache = check_process("httpd");
+    let settings = Settings::load().unwrap_or_default();
+    // Reported as `apache` whichever web server is in use
+    let apache = match settings.web_server {
+        WebServer::Apache => check_process("httpd") || check_process("apache2"),
+        WebServer::Nginx => check_process("nginx"),
+    };
     let mysql = check_process("mysqld");
     let php = check_process("php-fpm");
 
+    let ports = settings.ports;
+    let ports = tauri::async_runtime::spawn_blocking(move || check_ports(&ports))
+        .await
+        .map_err(|e| e.to_string())?;
+    let pools = php_fpm::check_all().await;
+
     Ok(ServicesStatus {
         apache,
         mysql,
         php,
         all_running: apache && mysql && php,
+        ports,
+        pools,
     })
 }
 
+/// Probe the configured ports so a running process that failed to bind
+/// (or a foreign service squatting the port) shows up in the status
+fn check_ports(ports: &Ports) -> PortsStatus {
+    let web_ip = ports.probe_ip();
+    let local_ip = IpAddr::from([127, 0, 0, 1]);
+
+    let php_fpm = match ports.php_fpm_socket.as_deref().filter(|s| !s.is_empty()) {
+        Some(socket) => std::path::Path::new(socket).exists(),
+        None => ports
+            .php_fpm_host
+            .parse()
+            .map(|ip| port_open(ip, ports.php_fpm))
+            .unwrap_or_else(|_| port_open(local_ip, ports.php_fpm)),
+    };
+
+    PortsStatus {
+        http: port_open(web_ip, ports.http),
+        https: port_open(web_ip, ports.https),
+        php_fpm,
+        mysql: port_open(local_ip, ports.mysql),
+    }
+}
+
+fn port_open(ip: IpAddr, port: u16) -> bool {
+    TcpStream::connect_timeout(&SocketAddr::new(ip, port), Duration::from_millis(300)).is_ok()
+}
+
+/// Browser URL for a host, including the port when it isn't the default
+#[tauri::command]
+pub async fn get_host_url(domain: String, ssl: bool) -> Result<String, String> {
+    Ok(Settings::load().unwrap_or_default().ports.url(&domain, ssl))
+}
+
 fn check_process(name: &str) -> bool {
     if cfg!(target_os = "windows") {
         // Windows: Use tasklist command to check for running processes
         let search_name = match name {
             "httpd" => "httpd.exe",
+            "nginx" => "nginx.exe",
             "mysqld" => "mysqld.exe",
             "php-fpm" => "php-cgi.exe", // On Windows, PHP typically runs as php-cgi
             _ => name,
@@ -299,8 +414,23 @@ fn parse_mysql_version(output: &str) -> Option<String> {
     })
 }
 
+/// Version the CLI shims select (for `directory` when given), falling back
+/// to whichever `php` is found first when no version has been selected
 #[tauri::command]
-pub async fn get_current_php_version() -> Result<String, String> {
+pub async fn get_current_php_version(directory: Option<String>) -> Result<String, String> {
+    let selected = tauri::async_runtime::spawn_blocking(move || {
+        php_switcher::active(
+            &php_discovery::installed_versions(),
+            directory.as_deref().map(Path::new),
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    if let Some(active) = selected {
+        return Ok(active.version);
+    }
+
     let php_paths: Vec<&str> = if cfg!(target_os = "windows") {
         vec![
             "C:\\xampp\\php\\php.exe",
@@ -382,8 +512,24 @@ pub async fn get_current_mysql_version() -> Result<String, String> {
         .ok_or_else(|| "MySQL not found".to_string())
 }
 
+/// Tenant labels end up in the hosts file and certificates, one name per label
+pub(crate) fn valid_subdomain(label: &str) -> bool {
+    !label.is_empty()
+        && !label.starts_with(['-', '.'])
+        && !label.ends_with(['-', '.'])
+        && label
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+}
+
 #[tauri::command]
 pub async fn save_virtual_hosts(hosts: HashMap<String, VirtualHost>) -> Result<(), String> {
+    for (domain, host) in &hosts {
+        if let Some(label) = host.subdomains.iter().find(|l| !valid_subdomain(l)) {
+            return Err(format!("Host '{}': invalid subdomain '{}'", domain, label));
+        }
+    }
+
     let hosts_file = get_hosts_file_path();
 
     // Ensure the directory exists
@@ -397,221 +543,107 @@ pub async fn save_virtual_hosts(hosts: HashMap<String, VirtualHost>) -> Result<(
         .map_err(|e| format!("Failed to serialize hosts: {}", e))?;
 
     // Write to file
-    fs::write(&hosts_file, json_content)
-        .map_err(|e| format!("Failed to write hosts file: {}", e))?;
+    write_hosts_file(&hosts_file, &json_content)?;
 
     Ok(())
 }
 
-#[tauri::command]
-pub async fn generate_configs() -> Result<String, String> {
-    let home = get_home_dir();
-
-    if cfg!(target_os = "windows") {
-        // Windows: Use PowerShell scripts
-        let scripts_dir = format!("{}\\localhost-manager\\scripts\\windows", home);
-
-        // Step 1: Generate configs
-        let generate_script = format!("{}\\generate-all.ps1", scripts_dir);
-        let mut cmd = Command::new("powershell");
-        cmd.args(["-ExecutionPolicy", "Bypass", "-File", &generate_script]);
-        #[cfg(target_os = "windows")]
-        cmd.creation_flags(CREATE_NO_WINDOW);
-        let output = cmd
-            .output()
-            .map_err(|e| format!("Failed to execute generate script: {}", e))?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(format!("Generate failed: {}", stderr));
-        }
+/// Append hosts to hosts.json, leaving existing entries untouched and
+/// skipping domains that are already taken. Returns how many were added.
+pub(crate) fn add_virtual_hosts(new_hosts: Vec<VirtualHost>) -> Result<usize, String> {
+    let hosts_file = get_hosts_file_path();
+    let mut hosts: HashMap<String, serde_json::Value> = match fs::read_to_string(&hosts_file) {
+        Ok(content) => serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse hosts file: {}", e))?,
+        Err(_) => HashMap::new(),
+    };
 
-        // Step 2: Apply configs (install.ps1) - requires admin
-        let install_script = format!("{}\\install.ps1", scripts_dir);
-        let mut install_cmd = Command::new("powershell");
-        install_cmd.args(["-ExecutionPolicy", "Bypass", "-File", &install_script]);
-        #[cfg(target_os = "windows")]
-        install_cmd.creation_flags(CREATE_NO_WINDOW);
-        let install_output = install_cmd
-            .output()
-            .map_err(|e| format!("Failed to execute install script: {}", e))?;
-
-        if install_output.status.success() {
-            let stdout = String::from_utf8_lossy(&install_output.stdout);
-            Ok(format!(
-                "Configuration generated and applied successfully.\n{}",
-                stdout
-            ))
-        } else {
-            let stderr = String::from_utf8_lossy(&install_output.stderr);
-            Err(format!("Install failed: {}", stderr))
-        }
-    } else {
-        // macOS/Linux: Use bash scripts
-        let home = get_home_dir();
-        let scripts_dir = format!("{}/localhost-manager/scripts", home);
-
-        // Step 1: Generate configs
-        let generate_script = format!("{}/generate-all.sh", scripts_dir);
-        let output = Command::new("bash")
-            .arg(&generate_script)
-            .output()
-            .map_err(|e| format!("Failed to execute generate script: {}", e))?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(format!("Generate failed: {}", stderr));
+    let mut added = 0;
+    for host in new_hosts {
+        if hosts.contains_key(&host.domain) {
+            continue;
         }
+        let value =
+            serde_json::to_value(&host).map_err(|e| format!("Failed to serialize host: {}", e))?;
+        hosts.insert(host.domain, value);
+        added += 1;
+    }
 
-        // Step 2: Apply configs (install.sh)
-        let install_script = format!("{}/install.sh", scripts_dir);
-        let install_output = Command::new("bash")
-            .arg(&install_script)
-            .output()
-            .map_err(|e| format!("Failed to execute install script: {}", e))?;
-
-        if install_output.status.success() {
-            let stdout = String::from_utf8_lossy(&install_output.stdout);
-            Ok(format!(
-                "Configuration generated and applied successfully.\n{}",
-                stdout
-            ))
-        } else {
-            let stderr = String::from_utf8_lossy(&install_output.stderr);
-            Err(format!("Install failed: {}", stderr))
-        }
+    if added > 0 {
+        let json_content = serde_json::to_string_pretty(&hosts)
+            .map_err(|e| format!("Failed to serialize hosts: {}", e))?;
+        write_hosts_file(&hosts_file, &json_content)?;
     }
+
+    Ok(added)
 }
 
 #[tauri::command]
-pub async fn apply_configs() -> Result<String, String> {
-    let home = get_home_dir();
+pub async fn generate_configs() -> Result<String, String> {
+    regenerate_and_apply()
+}
 
-    if cfg!(target_os = "windows") {
-        let script_path = format!("{}\\localhost-manager\\scripts\\windows\\install.ps1", home);
+/// Run the generate + install scripts for the current platform.
+/// Shared by the `generate_configs` command and the hosts.json watcher.
+pub(crate) fn regenerate_and_apply() -> Result<String, String> {
+    let layout = Layout::resolve();
 
-        let mut cmd = Command::new("powershell");
-        cmd.args(["-ExecutionPolicy", "Bypass", "-File", &script_path]);
-        #[cfg(target_os = "windows")]
-        cmd.creation_flags(CREATE_NO_WINDOW);
-        let output = cmd
-            .output()
-            .map_err(|e| format!("Failed to execute install script: {}", e))?;
+    // Each host's template goes to conf/sites, where the generated vhosts include it
+    vhost_templates::render_sites()?;
 
-        if output.status.success() {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            Ok(stdout.to_string())
-        } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            Err(format!("Install failed: {}", stderr))
-        }
-    } else {
-        let script_path = format!("{}/localhost-manager/scripts/install.sh", home);
+    // Step 1: Generate configs
+    let output = layout
+        .script_command("generate-all")
+        .output()
+        .map_err(|e| format!("Failed to execute generate script: {}", e))?;
 
-        let output = Command::new("bash")
-            .arg(&script_path)
-            .output()
-            .map_err(|e| format!("Failed to execute install script: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Generate failed: {}", stderr));
+    }
 
-        if output.status.success() {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            Ok(stdout.to_string())
-        } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            Err(format!("Install failed: {}", stderr))
-        }
+    // Step 2: Apply configs (install script) - requires admin
+    let install_output = layout
+        .script_command("install")
+        .output()
+        .map_err(|e| format!("Failed to execute install script: {}", e))?;
+
+    if install_output.status.success() {
+        let stdout = String::from_utf8_lossy(&install_output.stdout);
+        Ok(format!(
+            "Configuration generated and applied successfully.\n{}",
+            stdout
+        ))
+    } else {
+        let stderr = String::from_utf8_lossy(&install_output.stderr);
+        Err(format!("Install failed: {}", stderr))
     }
 }
 
 #[tauri::command]
-pub async fn control_service(action: String, service: String) -> Result<String, String> {
-    if cfg!(target_os = "windows") {
-        // Windows service control
-        // Map service names to Windows service names (varies by stack)
-        let windows_services: Vec<&str> = match service.as_str() {
-            "apache" => vec!["Apache2.4", "wampapache64", "Apache"],
-            "mysql" => vec!["MySQL", "wampmysqld64", "MySQL80"],
-            "php" => vec![], // PHP-FPM is not a Windows service typically
-            _ => return Err(format!("Unknown service: {}", service)),
-        };
-
-        if windows_services.is_empty() {
-            return Ok(format!(
-                "Service {} does not run as a Windows service",
-                service
-            ));
-        }
-
-        // Try each possible service name
-        for win_service in &windows_services {
-            let sc_action = match action.as_str() {
-                "start" => "start",
-                "stop" => "stop",
-                "restart" => "stop", // Will start after
-                _ => return Err(format!("Unknown action: {}", action)),
-            };
-
-            let output = run_windows_command("sc", &[sc_action, win_service]);
-
-            if let Ok(out) = output {
-                if out.status.success() {
-                    // If restart, also start
-                    if action == "restart" {
-                        std::thread::sleep(std::time::Duration::from_secs(1));
-                        let _ = run_windows_command("sc", &["start", win_service]);
-                    }
-                    return Ok(format!("Service {} {}ed successfully", service, action));
-                }
-            }
-        }
-
-        // Try net command as fallback
-        for win_service in &windows_services {
-            let net_action = match action.as_str() {
-                "start" => "start",
-                "stop" => "stop",
-                "restart" => "stop",
-                _ => return Err(format!("Unknown action: {}", action)),
-            };
-
-            let output = run_windows_command("net", &[net_action, win_service]);
-
-            if let Ok(out) = output {
-                if out.status.success() {
-                    if action == "restart" {
-                        std::thread::sleep(std::time::Duration::from_secs(1));
-                        let _ = run_windows_command("net", &["start", win_service]);
-                    }
-                    return Ok(format!("Service {} {}ed successfully", service, action));
-                }
-            }
-        }
+pub async fn apply_configs() -> Result<String, String> {
+    let output = Layout::resolve()
+        .script_command("install")
+        .output()
+        .map_err(|e| format!("Failed to execute install script: {}", e))?;
 
-        Err(format!(
-            "Could not {} {} - service may not be installed or requires admin rights",
-            action, service
-        ))
+    if output.status.success() {
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout.to_string())
     } else {
-        // macOS - use brew services
-        let brew_service = match service.as_str() {
-            "apache" => "httpd",
-            "mysql" => "mysql@8.4",
-            "php" => "php@8.3",
-            _ => return Err(format!("Unknown service: {}", service)),
-        };
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(format!("Install failed: {}", stderr))
+    }
+}
 
-        let output = Command::new("brew")
-            .args(["services", &action, brew_service])
-            .output()
-            .map_err(|e| format!("Failed to {} {}: {}", action, service, e))?;
+#[tauri::command]
+pub async fn control_service(action: String, service: String) -> Result<String, String> {
+    let service = Service::parse(&service)?;
+    let action = ServiceAction::parse(&action)?;
 
-        if output.status.success() {
-            Ok(format!("Service {} {}ed successfully", service, action))
-        } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            Err(format!("Failed to {} {}: {}", action, service, stderr))
-        }
-    }
+    tauri::async_runtime::spawn_blocking(move || services::control(service, action))
+        .await
+        .map_err(|e| e.to_string())?
 }
 
 #[tauri::command]
@@ -635,25 +667,19 @@ pub async fn delete_host(domain: String) -> Result<(), String> {
     let json_content = serde_json::to_string_pretty(&hosts)
         .map_err(|e| format!("Failed to serialize hosts: {}", e))?;
 
-    fs::write(&hosts_file, json_content)
-        .map_err(|e| format!("Failed to write hosts file: {}", e))?;
+    write_hosts_file(&hosts_file, &json_content)?;
 
     Ok(())
 }
 
 #[tauri::command]
 pub async fn get_home_directory() -> Result<String, String> {
-    Ok(get_home_dir())
+    Ok(paths::home_dir().to_string_lossy().to_string())
 }
 
 #[tauri::command]
 pub async fn get_scripts_path() -> Result<String, String> {
-    let home = get_home_dir();
-    if cfg!(target_os = "windows") {
-        Ok(format!("{}\\localhost-manager\\scripts\\windows", home))
-    } else {
-        Ok(format!("{}/localhost-manager/scripts", home))
-    }
+    Ok(Layout::resolve().scripts_dir.to_string_lossy().to_string())
 }
 
 // Setup Wizard Commands
@@ -748,9 +774,13 @@ pub async fn setup_directories(
     let conf_dir = std::path::Path::new(&config_path).join("conf");
     fs::create_dir_all(&conf_dir).map_err(|e| format!("Failed to create conf directory: {}", e))?;
 
+    // Give the new directory its own copy of the scripts so it can be moved
+    // or used from a terminal; Layout falls back to the bundled ones otherwise
     let scripts_dir = std::path::Path::new(&config_path).join("scripts");
-    fs::create_dir_all(&scripts_dir)
-        .map_err(|e| format!("Failed to create scripts directory: {}", e))?;
+    if let Some(bundled) = paths::bundled_scripts_dir().filter(|dir| *dir != scripts_dir) {
+        copy_dir(&bundled, &scripts_dir)
+            .map_err(|e| format!("Failed to copy scripts to {}: {}", scripts_dir.display(), e))?;
+    }
 
     // Create SSL directory
     fs::create_dir_all(&ssl_path).map_err(|e| format!("Failed to create SSL directory: {}", e))?;
@@ -762,6 +792,21 @@ pub async fn setup_directories(
     Ok(())
 }
 
+/// Copy a directory tree, keeping files that already exist in `to`
+fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else if !target.exists() {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
 #[derive(serde::Deserialize)]
 pub struct SetupConfig {
     stack: String,
@@ -774,7 +819,10 @@ pub struct SetupConfig {
 }
 
 #[tauri::command]
-pub async fn create_initial_config(config: SetupConfig) -> Result<(), String> {
+pub async fn create_initial_config(
+    app: tauri::AppHandle,
+    config: SetupConfig,
+) -> Result<(), String> {
     // Create initial hosts.json
     let hosts_file = std::path::Path::new(&config.config_path)
         .join("conf")
@@ -785,29 +833,23 @@ pub async fn create_initial_config(config: SetupConfig) -> Result<(), String> {
     let json_content = serde_json::to_string_pretty(&initial_hosts)
         .map_err(|e| format!("Failed to serialize config: {}", e))?;
 
-    fs::write(&hosts_file, json_content)
-        .map_err(|e| format!("Failed to write hosts file: {}", e))?;
-
-    // Create settings.json
-    let settings_file = std::path::Path::new(&config.config_path)
-        .join("conf")
-        .join("settings.json");
+    write_hosts_file(&hosts_file, &json_content)?;
 
-    let settings = serde_json::json!({
-        "stack": config.stack,
-        "projectsPath": config.projects_path,
-        "configPath": config.config_path,
-        "sslPath": config.ssl_path,
-        "setupCompleted": true,
-        "setupDate": chrono::Utc::now().to_rfc3339()
-    });
+    // Record the wizard choices in settings.json, where the layout resolver reads them back
+    let mut settings = Settings::load().map_err(|e| format!("Failed to load settings: {}", e))?;
+    settings.stack = config.stack;
+    settings.projects_path = Some(config.projects_path);
+    settings.config_path = Some(config.config_path);
+    settings.ssl_path = Some(config.ssl_path);
+    settings.setup_completed = true;
+    settings.setup_date = Some(chrono::Utc::now().to_rfc3339());
 
-    let settings_content = serde_json::to_string_pretty(&settings)
-        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
-
-    fs::write(&settings_file, settings_content)
+    settings
+        .save()
         .map_err(|e| format!("Failed to write settings file: {}", e))?;
 
+    let _ = app.emit(SETTINGS_CHANGED_EVENT, &settings);
+
     Ok(())
 }
 
@@ -895,351 +937,147 @@ pub async fn check_package_installed(package: String) -> Result<bool, String> {
     Ok(installed)
 }
 
-#[tauri::command]
-pub async fn install_package(package: String, version: String) -> Result<String, String> {
-    let output = if cfg!(target_os = "macos") {
-        match package.as_str() {
-            "apache" => Command::new("brew")
-                .args(["install", "httpd"])
-                .output()
-                .map_err(|e| e.to_string())?,
-            "mysql" => Command::new("brew")
-                .args(["install", "mysql"])
-                .output()
-                .map_err(|e| e.to_string())?,
+/// Build the platform package-manager command installing `package`
+fn install_package_command(package: &str, version: &str) -> Result<Command, String> {
+    let mut cmd;
+
+    if cfg!(target_os = "macos") {
+        cmd = Command::new("brew");
+        match package {
+            "apache" => cmd.args(["install", "httpd"]),
+            "mysql" => cmd.args(["install", "mysql"]),
             "php" => {
                 let php_package = if version.is_empty() || version == "8.3" {
-                    "php"
+                    "php".to_string()
                 } else {
-                    &format!("php@{}", version)
+                    format!("php@{}", version)
                 };
-                Command::new("brew")
-                    .args(["install", php_package])
-                    .output()
-                    .map_err(|e| e.to_string())?
+                cmd.args(["install", &php_package])
             }
             _ => return Err("Unknown package".to_string()),
-        }
+        };
     } else if cfg!(target_os = "windows") {
-        match package.as_str() {
-            "apache" => run_windows_command("choco", &["install", "apache-httpd", "-y"])
-                .map_err(|e| e.to_string())?,
-            "mysql" => run_windows_command("choco", &["install", "mysql", "-y"])
-                .map_err(|e| e.to_string())?,
+        cmd = Command::new("choco");
+        match package {
+            "apache" => cmd.args(["install", "apache-httpd", "-y"]),
+            "mysql" => cmd.args(["install", "mysql", "-y"]),
             "php" => {
-                let php_version = if version.is_empty() {
-                    "8.3".to_string()
-                } else {
-                    version.clone()
-                };
-                run_windows_command(
-                    "choco",
-                    &[
-                        "install",
-                        "php",
-                        &format!("--version={}", php_version),
-                        "-y",
-                    ],
-                )
-                .map_err(|e| e.to_string())?
+                let php_version = if version.is_empty() { "8.3" } else { version };
+                cmd.args([
+                    "install",
+                    "php",
+                    &format!("--version={}", php_version),
+                    "-y",
+                ])
             }
             _ => return Err("Unknown package".to_string()),
-        }
+        };
     } else {
-        match package.as_str() {
-            "apache" => Command::new("sudo")
-                .args(["apt", "install", "-y", "apache2"])
-                .output()
-                .map_err(|e| e.to_string())?,
-            "mysql" => Command::new("sudo")
-                .args(["apt", "install", "-y", "mysql-server"])
-                .output()
-                .map_err(|e| e.to_string())?,
+        cmd = Command::new("sudo");
+        match package {
+            "apache" => cmd.args(["apt", "install", "-y", "apache2"]),
+            "mysql" => cmd.args(["apt", "install", "-y", "mysql-server"]),
             "php" => {
-                let php_version = if version.is_empty() { "8.3" } else { &version };
-                Command::new("sudo")
-                    .args([
-                        "apt",
-                        "install",
-                        "-y",
-                        &format!("php{}", php_version),
-                        &format!("php{}-fpm", php_version),
-                        &format!("php{}-mysql", php_version),
-                        &format!("php{}-curl", php_version),
-                        &format!("php{}-gd", php_version),
-                        &format!("php{}-mbstring", php_version),
-                    ])
-                    .output()
-                    .map_err(|e| e.to_string())?
+                let php_version = if version.is_empty() { "8.3" } else { version };
+                cmd.args([
+                    "apt",
+                    "install",
+                    "-y",
+                    &format!("php{}", php_version),
+                    &format!("php{}-fpm", php_version),
+                    &format!("php{}-mysql", php_version),
+                    &format!("php{}-curl", php_version),
+                    &format!("php{}-gd", php_version),
+                    &format!("php{}-mbstring", php_version),
+                ])
             }
             _ => return Err("Unknown package".to_string()),
-        }
-    };
-
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
+        };
     }
+
+    Ok(cmd)
+}
+
+/// Start installing a package as a background job; returns the job ID.
+/// Output and the final status arrive as `job-output` events.
+#[tauri::command]
+pub async fn install_package(
+    window: tauri::Window,
+    jobs: tauri::State<'_, Jobs>,
+    package: String,
+    version: String,
+) -> Result<String, String> {
+    let cmd = install_package_command(&package, &version)?;
+    jobs.spawn(&window, cmd, &format!("Installing {}", package))
 }
 
 // ============================================
 // PHP Extensions Commands
 // ============================================
 
+/// Installed PHP branches (`8.3`, `8.2`, ...), newest first
 #[tauri::command]
 pub async fn get_installed_php_versions_list() -> Result<Vec<String>, String> {
-    let mut versions = Vec::new();
-
-    if cfg!(target_os = "macos") {
-        // Get PHP versions from Homebrew
-        let output = Command::new("brew")
-            .args(["list", "--versions"])
-            .output()
-            .map_err(|e| e.to_string())?;
-
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        for line in stdout.lines() {
-            if line.starts_with("php@") || line.starts_with("php ") {
-                if let Some(version) = line.split_whitespace().nth(1) {
-                    let major_minor: String =
-                        version.split('.').take(2).collect::<Vec<_>>().join(".");
-                    if !versions.contains(&major_minor) {
-                        versions.push(major_minor);
-                    }
-                }
-            }
-        }
-    } else if cfg!(target_os = "windows") {
-        // Check common PHP locations on Windows
-        let output = run_windows_command("php", &["-v"]);
-        if let Ok(o) = output {
-            if o.status.success() {
-                let stdout = String::from_utf8_lossy(&o.stdout);
-                if let Some(line) = stdout.lines().next() {
-                    if let Some(version) = line.split_whitespace().nth(1) {
-                        let major_minor: String =
-                            version.split('.').take(2).collect::<Vec<_>>().join(".");
-                        versions.push(major_minor);
-                    }
-                }
-            }
-        }
-    } else {
-        // Linux: check installed PHP versions
-        for v in ["7.4", "8.0", "8.1", "8.2", "8.3"] {
-            let path = format!("/usr/bin/php{}", v);
-            if std::path::Path::new(&path).exists() {
-                versions.push(v.to_string());
-            }
-        }
-    }
-
-    if versions.is_empty() {
-        // Fallback: try to get current PHP version
-        let output = run_windows_command("php", &["-v"]);
-        if let Ok(o) = output {
-            if o.status.success() {
-                let stdout = String::from_utf8_lossy(&o.stdout);
-                if let Some(line) = stdout.lines().next() {
-                    if let Some(version) = line.split_whitespace().nth(1) {
-                        let major_minor: String =
-                            version.split('.').take(2).collect::<Vec<_>>().join(".");
-                        versions.push(major_minor);
-                    }
-                }
-            }
+    let installed = tauri::async_runtime::spawn_blocking(php_discovery::installed_versions)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut versions: Vec<String> = Vec::new();
+    for php in installed {
+        let major_minor = php.branch();
+        if !versions.contains(&major_minor) {
+            versions.push(major_minor);
         }
     }
 
     Ok(versions)
 }
 
-#[derive(serde::Serialize)]
-pub struct ExtensionInfo {
-    name: String,
-    enabled: bool,
-    installed: bool,
-}
-
-#[tauri::command]
-pub async fn get_php_extensions(version: String) -> Result<Vec<ExtensionInfo>, String> {
-    let mut extensions = Vec::new();
-
-    // Common PHP extensions
-    let common_extensions = vec![
-        "bcmath",
-        "bz2",
-        "calendar",
-        "ctype",
-        "curl",
-        "dom",
-        "exif",
-        "fileinfo",
-        "filter",
-        "ftp",
-        "gd",
-        "gettext",
-        "gmp",
-        "iconv",
-        "imagick",
-        "imap",
-        "intl",
-        "json",
-        "ldap",
-        "mbstring",
-        "memcached",
-        "mongodb",
-        "mysqli",
-        "mysqlnd",
-        "opcache",
-        "openssl",
-        "pcntl",
-        "pdo",
-        "pdo_mysql",
-        "pdo_pgsql",
-        "pdo_sqlite",
-        "pgsql",
-        "phar",
-        "posix",
-        "readline",
-        "redis",
-        "session",
-        "shmop",
-        "simplexml",
-        "soap",
-        "sockets",
-        "sodium",
-        "sqlite3",
-        "ssh2",
-        "sysvmsg",
-        "sysvsem",
-        "sysvshm",
-        "tidy",
-        "tokenizer",
-        "xdebug",
-        "xml",
-        "xmlreader",
-        "xmlrpc",
-        "xmlwriter",
-        "xsl",
-        "zip",
-        "zlib",
-    ];
-
-    // Get loaded extensions
-    let output = if cfg!(target_os = "macos") {
-        let php_cmd = if version == "8.3" || version.is_empty() {
-            "php".to_string()
-        } else {
-            format!("/opt/homebrew/opt/php@{}/bin/php", version)
-        };
-        Command::new(&php_cmd)
-            .args(["-m"])
-            .output()
-            .map_err(|e| e.to_string())?
-    } else {
-        run_windows_command("php", &["-m"]).map_err(|e| e.to_string())?
-    };
-
-    let loaded: Vec<String> = String::from_utf8_lossy(&output.stdout)
-        .lines()
-        .map(|s| s.to_lowercase())
-        .collect();
-
-    for ext in common_extensions {
-        let enabled = loaded.contains(&ext.to_lowercase());
-        extensions.push(ExtensionInfo {
-            name: ext.to_string(),
-            enabled,
-            installed: true, // Simplified: assume all are available
-        });
-    }
-
-    Ok(extensions)
-}
-
 #[tauri::command]
 pub async fn toggle_php_extension(
     version: String,
     extension: String,
     enable: bool,
 ) -> Result<(), String> {
-    // Find php.ini path
-    let ini_path = if cfg!(target_os = "macos") {
-        if version == "8.3" || version.is_empty() {
-            "/opt/homebrew/etc/php/8.3/php.ini".to_string()
-        } else {
-            format!("/opt/homebrew/etc/php/{}/php.ini", version)
-        }
-    } else if cfg!(target_os = "windows") {
-        // Common Windows PHP locations
-        let possible_paths = vec![
-            format!("C:\\php\\php.ini"),
-            format!("C:\\xampp\\php\\php.ini"),
-            format!("C:\\wamp64\\bin\\php\\php{}\\php.ini", version),
-        ];
-        possible_paths
-            .into_iter()
-            .find(|p| std::path::Path::new(p).exists())
-            .unwrap_or_else(|| "C:\\php\\php.ini".to_string())
-    } else {
-        format!("/etc/php/{}/fpm/php.ini", version)
-    };
+    let php = php_discovery::find_installed(&version)
+        .ok_or_else(|| format!("PHP {} not found", version))?;
 
-    if !std::path::Path::new(&ini_path).exists() {
-        return Err(format!("php.ini not found at {}", ini_path));
+    match php_overrides::config_mode(&php) {
+        PhpConfigMode::ConfD => php_overrides::toggle_extension(&php, &extension, enable).await,
+        PhpConfigMode::PhpIni => php_ini::toggle_extension(&php, &extension, enable).await,
     }
-
-    let content = fs::read_to_string(&ini_path).map_err(|e| e.to_string())?;
-
-    let ext_line = format!("extension={}", extension);
-    let ext_line_commented = format!(";extension={}", extension);
-
-    let new_content = if enable {
-        // Enable: uncomment or add
-        if content.contains(&ext_line_commented) {
-            content.replace(&ext_line_commented, &ext_line)
-        } else if !content.contains(&ext_line) {
-            format!("{}\n{}", content, ext_line)
-        } else {
-            content
-        }
-    } else {
-        // Disable: comment out
-        if content.contains(&ext_line) && !content.contains(&ext_line_commented) {
-            content.replace(&ext_line, &ext_line_commented)
-        } else {
-            content
-        }
-    };
-
-    fs::write(&ini_path, new_content).map_err(|e| e.to_string())?;
-
-    Ok(())
 }
 
+/// Reload the PHP-FPM master of one version, or of every installed version
 #[tauri::command]
-pub async fn restart_php_fpm() -> Result<(), String> {
-    let output = if cfg!(target_os = "macos") {
-        Command::new("brew")
-            .args(["services", "restart", "php"])
-            .output()
-            .map_err(|e| e.to_string())?
-    } else if cfg!(target_os = "windows") {
+pub async fn restart_php_fpm(version: Option<String>) -> Result<(), String> {
+    if cfg!(target_os = "windows") {
         // Windows doesn't typically use php-fpm
         return Ok(());
-    } else {
-        Command::new("sudo")
-            .args(["systemctl", "restart", "php-fpm"])
-            .output()
-            .map_err(|e| e.to_string())?
+    }
+
+    let targets: Vec<PhpVersion> = match version {
+        Some(version) => vec![php_discovery::find_installed(&version)
+            .ok_or_else(|| format!("PHP {} not found", version))?],
+        None => tauri::async_runtime::spawn_blocking(php_discovery::installed_versions)
+            .await
+            .map_err(|e| e.to_string())?,
     };
 
-    if output.status.success() {
+    let mut errors = Vec::new();
+    for php in targets
+        .iter()
+        .filter(|php| php.sapis.iter().any(|s| s == "fpm"))
+    {
+        if let Err(e) = php_fpm::reload(php).await {
+            errors.push(format!("PHP {}: {}", php.version, e));
+        }
+    }
+
+    if errors.is_empty() {
         Ok(())
     } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
+        Err(errors.join("\n"))
     }
 }
 
@@ -1253,8 +1091,13 @@ where
     I: IntoIterator<Item = S>,
     S: AsRef<std::ffi::OsStr>,
 {
+    // Over TCP to the configured port; a bare `mysql` would use the default
+    // socket and ignore the port setting
+    let port = Settings::load().unwrap_or_default().ports.mysql;
     let mut cmd = Command::new("mysql");
-    cmd.args(args);
+    cmd.args(["--protocol=TCP", "-h", "127.0.0.1", "-P"])
+        .arg(port.to_string())
+        .args(args);
     #[cfg(target_os = "windows")]
     cmd.creation_flags(CREATE_NO_WINDOW);
     cmd.output()
@@ -0,0 +1,296 @@
+// ============================================
+// hosts.json Watcher
+// ============================================
+
+use crate::hosts_manager::{get_hosts_file_path, regenerate_and_apply, valid_subdomain};
+use crate::settings::{Settings, SETTINGS_CHANGED_EVENT};
+use notify_debouncer_mini::notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, Debouncer};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Listener, Manager, State};
+
+/// Event name used to report watcher progress to the UI
+pub const HOSTS_WATCHER_EVENT: &str = "hosts-watcher";
+
+/// Quiet period before a burst of writes (editor save, git checkout) is handled
+const DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(750);
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct HostsWatcherEvent {
+    stage: String,
+    message: String,
+}
+
+/// Managed state holding the active debouncer, if any.
+/// Dropping the debouncer stops the underlying watcher.
+#[derive(Default)]
+pub struct HostsWatcher {
+    debouncer: Mutex<Option<Debouncer<RecommendedWatcher>>>,
+}
+
+fn emit_progress(app: &AppHandle, stage: &str, message: impl Into<String>) {
+    let _ = app.emit(
+        HOSTS_WATCHER_EVENT,
+        HostsWatcherEvent {
+            stage: stage.to_string(),
+            message: message.into(),
+        },
+    );
+}
+
+/// Hash of the hosts.json content last accounted for: rebuilt by the
+/// watcher, or written by the app, which applies its own changes
+static LAST_HASH: Mutex<Option<u64>> = Mutex::new(None);
+
+fn content_hash(content: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn set_last_hash(hash: Option<u64>) {
+    if let Ok(mut last) = LAST_HASH.lock() {
+        *last = hash;
+    }
+}
+
+/// Note content the app is about to write to hosts.json, so the write
+/// doesn't look like an outside edit
+pub(crate) fn record_write(content: &str) {
+    set_last_hash(Some(content_hash(content)));
+}
+
+/// Check that hosts.json parses and every entry looks like a host before
+/// we hand it to the generator scripts. Returns the number of hosts.
+fn validate_hosts_content(content: &str) -> Result<usize, String> {
+    let hosts: HashMap<String, serde_json::Value> =
+        serde_json::from_str(content).map_err(|e| format!("Invalid JSON: {}", e))?;
+
+    for (domain, host) in &hosts {
+        if domain.trim().is_empty() || domain.contains(char::is_whitespace) {
+            return Err(format!("Invalid domain name '{}'", domain));
+        }
+
+        let obj = host
+            .as_object()
+            .ok_or_else(|| format!("Host '{}' must be an object", domain))?;
+
+        match obj.get("docroot").and_then(|v| v.as_str()) {
+            Some(docroot) if !docroot.trim().is_empty() => {}
+            _ => return Err(format!("Host '{}' has no docroot", domain)),
+        }
+
+        for flag in ["active", "ssl", "wildcard"] {
+            if let Some(value) = obj.get(flag) {
+                if !value.is_boolean() {
+                    return Err(format!(
+                        "Host '{}': '{}' must be true or false",
+                        domain, flag
+                    ));
+                }
+            }
+        }
+
+        if let Some(aliases) = obj.get("aliases") {
+            if !aliases.is_array() {
+                return Err(format!("Host '{}': 'aliases' must be an array", domain));
+            }
+        }
+
+        // Tenant labels end up in the hosts file, one name per label
+        if let Some(subdomains) = obj.get("subdomains") {
+            let labels = subdomains
+                .as_array()
+                .ok_or_else(|| format!("Host '{}': 'subdomains' must be an array", domain))?;
+            for label in labels {
+                if !label.as_str().is_some_and(valid_subdomain) {
+                    return Err(format!("Host '{}': invalid subdomain {}", domain, label));
+                }
+            }
+        }
+    }
+
+    Ok(hosts.len())
+}
+
+/// Validate, regenerate and reload after hosts.json changed on disk
+fn handle_hosts_change(app: &AppHandle, hosts_file: &Path) {
+    let content = match std::fs::read_to_string(hosts_file) {
+        Ok(content) => content,
+        Err(e) => {
+            emit_progress(app, "error", format!("Failed to read hosts file: {}", e));
+            return;
+        }
+    };
+
+    // Editors often write the same content twice (or only touch the file),
+    // and the app's own writes are already handled
+    let hash = content_hash(&content);
+    if LAST_HASH.lock().is_ok_and(|last| *last == Some(hash)) {
+        return;
+    }
+
+    emit_progress(app, "validating", "hosts.json changed, validating...");
+    let count = match validate_hosts_content(&content) {
+        Ok(count) => count,
+        Err(e) => {
+            emit_progress(app, "invalid", e);
+            return;
+        }
+    };
+
+    emit_progress(
+        app,
+        "generating",
+        format!("Regenerating configuration for {} hosts...", count),
+    );
+    match regenerate_and_apply() {
+        Ok(output) => {
+            set_last_hash(Some(hash));
+            emit_progress(app, "done", output);
+        }
+        Err(e) => emit_progress(app, "error", e),
+    }
+}
+
+impl HostsWatcher {
+    /// Start watching hosts.json; a no-op when already running
+    pub fn start(&self, app: &AppHandle) -> Result<(), String> {
+        let mut guard = self
+            .debouncer
+            .lock()
+            .map_err(|_| "Watcher state poisoned".to_string())?;
+
+        if guard.is_some() {
+            return Ok(());
+        }
+
+        let hosts_file = get_hosts_file_path();
+        // Watch the parent directory: editors and git replace the file atomically,
+        // which would orphan a watch placed on the file itself
+        let watch_dir = hosts_file
+            .parent()
+            .ok_or_else(|| "Invalid hosts file path".to_string())?
+            .to_path_buf();
+
+        if !watch_dir.exists() {
+            return Err(format!(
+                "Config directory not found: {}",
+                watch_dir.display()
+            ));
+        }
+
+        // Seed with the current content so starting the watcher doesn't trigger a rebuild
+        set_last_hash(
+            std::fs::read_to_string(&hosts_file)
+                .ok()
+                .map(|content| content_hash(&content)),
+        );
+
+        let handler_app = app.clone();
+        let handler_file = hosts_file.clone();
+        let mut debouncer =
+            new_debouncer(
+                DEBOUNCE_TIMEOUT,
+                move |result: DebounceEventResult| match result {
+                    Ok(events) => {
+                        if events.iter().any(|e| e.path == handler_file) {
+                            handle_hosts_change(&handler_app, &handler_file);
+                        }
+                    }
+                    Err(e) => emit_progress(&handler_app, "error", format!("Watcher error: {}", e)),
+                },
+            )
+            .map_err(|e| format!("Failed to create watcher: {}", e))?;
+
+        debouncer
+            .watcher()
+            .watch(&watch_dir, RecursiveMode::NonRecursive)
+            .map_err(|e| format!("Failed to watch {}: {}", watch_dir.display(), e))?;
+
+        *guard = Some(debouncer);
+        emit_progress(app, "started", format!("Watching {}", hosts_file.display()));
+
+        Ok(())
+    }
+
+    pub fn stop(&self, app: &AppHandle) -> Result<(), String> {
+        let mut guard = self
+            .debouncer
+            .lock()
+            .map_err(|_| "Watcher state poisoned".to_string())?;
+
+        if guard.take().is_some() {
+            emit_progress(app, "stopped", "Stopped watching hosts.json");
+        }
+
+        Ok(())
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.debouncer
+            .lock()
+            .map(|guard| guard.is_some())
+            .unwrap_or(false)
+    }
+}
+
+/// Start the watcher if the user enabled it, and follow later changes of
+/// the `watchHostsFile` preference without requiring a restart
+pub fn init(app: &AppHandle) {
+    if Settings::load()
+        .map(|s| s.ui.watch_hosts_file)
+        .unwrap_or(false)
+    {
+        if let Err(e) = app.state::<HostsWatcher>().start(app) {
+            emit_progress(app, "error", e);
+        }
+    }
+
+    let handle = app.clone();
+    app.listen(SETTINGS_CHANGED_EVENT, move |event| {
+        let Ok(settings) = serde_json::from_str::<Settings>(event.payload()) else {
+            return;
+        };
+
+        let watcher = handle.state::<HostsWatcher>();
+        let result = if settings.ui.watch_hosts_file {
+            watcher.start(&handle)
+        } else {
+            watcher.stop(&handle)
+        };
+
+        if let Err(e) = result {
+            emit_progress(&handle, "error", e);
+        }
+    });
+}
+
+/// Start watching hosts.json for changes
+#[tauri::command]
+pub async fn start_hosts_watcher(
+    app: AppHandle,
+    state: State<'_, HostsWatcher>,
+) -> Result<(), String> {
+    state.start(&app)
+}
+
+/// Stop watching hosts.json
+#[tauri::command]
+pub async fn stop_hosts_watcher(
+    app: AppHandle,
+    state: State<'_, HostsWatcher>,
+) -> Result<(), String> {
+    state.stop(&app)
+}
+
+/// Whether the hosts.json watcher is currently running
+#[tauri::command]
+pub async fn is_hosts_watcher_running(state: State<'_, HostsWatcher>) -> Result<bool, String> {
+    Ok(state.is_running())
+}
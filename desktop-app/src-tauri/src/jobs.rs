@@ -0,0 +1,392 @@
+// ============================================
+// Background Jobs
+// ============================================
+// Long-running commands (package installs, builds) run as spawned jobs instead
+// of blocking on `.output()`. Each job gets an ID, its stdout/stderr is
+// streamed line by line as `job-output` events, it can be cancelled, and it
+// ends with exactly one event carrying the final status.
+//
+// Jobs run in their own process group (a process tree on Windows), so
+// cancelling also stops what they started: apt's dpkg, brew's compilers.
+// Left running, those would keep the output pipes open and the job would
+// only report `cancelled` once they exited.
+
+use crate::system::is_privileged;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State, Window};
+
+#[cfg(unix)]
+use crate::system::execute_with_privileges;
+#[cfg(unix)]
+use std::os::unix::process::CommandExt as _;
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// Event name for job output and status updates
+pub const JOB_EVENT: &str = "job-output";
+
+/// How often the waiter checks whether the child exited
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Output lines kept per stream to explain a failure
+const TAIL_LINES: usize = 40;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase", tag = "state")]
+pub enum JobStatus {
+    Running,
+    Succeeded,
+    Failed { code: Option<i32> },
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JobEvent {
+    pub job_id: String,
+    /// `stdout`, `stderr` or `status`
+    pub stream: String,
+    pub line: String,
+    pub status: JobStatus,
+}
+
+struct JobHandle {
+    child: Arc<Mutex<Child>>,
+    cancelled: Arc<AtomicBool>,
+    /// Started through sudo, pkexec or the macOS administrator dialog, so
+    /// its processes belong to root
+    elevated: bool,
+}
+
+/// Managed state tracking running jobs by ID
+#[derive(Default)]
+pub struct Jobs {
+    running: Mutex<HashMap<String, JobHandle>>,
+}
+
+fn emit(window: &Window, job_id: &str, stream: &str, line: String, status: JobStatus) {
+    let _ = window.emit(
+        JOB_EVENT,
+        JobEvent {
+            job_id: job_id.to_string(),
+            stream: stream.to_string(),
+            line,
+            status,
+        },
+    );
+}
+
+/// Forward a pipe to the UI line by line, keeping the last few lines.
+/// Package managers don't always print UTF-8, so lines are decoded lossily.
+fn stream_lines<R: Read + Send + 'static>(
+    window: Window,
+    job_id: String,
+    stream: &'static str,
+    pipe: R,
+) -> std::thread::JoinHandle<VecDeque<String>> {
+    std::thread::spawn(move || {
+        let mut reader = BufReader::new(pipe);
+        let mut buf = Vec::new();
+        let mut tail = VecDeque::with_capacity(TAIL_LINES);
+
+        while reader.read_until(b'\n', &mut buf).unwrap_or(0) > 0 {
+            let line = String::from_utf8_lossy(&buf).trim_end().to_string();
+            buf.clear();
+            if line.is_empty() {
+                continue;
+            }
+            if tail.len() == TAIL_LINES {
+                tail.pop_front();
+            }
+            tail.push_back(line.clone());
+            emit(&window, &job_id, stream, line, JobStatus::Running);
+        }
+
+        tail
+    })
+}
+
+/// Why a job failed: the compiler or package-manager error lines near the
+/// end of its output, else its last line
+fn failure_summary(tails: &[VecDeque<String>]) -> Option<String> {
+    let errors: Vec<&str> = tails
+        .iter()
+        .flatten()
+        .map(String::as_str)
+        .filter(|line| {
+            let lower = line.to_lowercase();
+            lower.contains("error:") || lower.starts_with("e: ")
+        })
+        .collect();
+
+    if errors.is_empty() {
+        tails.iter().rev().find_map(|t| t.back()).cloned()
+    } else {
+        Some(errors.join("\n"))
+    }
+}
+
+impl Jobs {
+    /// Spawn `cmd` as a job and return its ID right away.
+    /// `label` describes the job in the final status line.
+    pub fn spawn(&self, window: &Window, cmd: Command, label: &str) -> Result<String, String> {
+        self.spawn_then(window, cmd, label, || Ok(()))
+    }
+
+    /// Like `spawn`, but once `cmd` succeeds run `then` (on the job's thread)
+    /// before reporting; its error fails the job.
+    pub fn spawn_then<F>(
+        &self,
+        window: &Window,
+        mut cmd: Command,
+        label: &str,
+        then: F,
+    ) -> Result<String, String>
+    where
+        F: FnOnce() -> Result<(), String> + Send + 'static,
+    {
+        cmd.stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        #[cfg(unix)]
+        cmd.process_group(0);
+        #[cfg(target_os = "windows")]
+        cmd.creation_flags(CREATE_NO_WINDOW);
+
+        let elevated = is_privileged(&cmd);
+        let mut child = cmd
+            .spawn()
+            .map_err(|e| format!("Failed to start {}: {}", label, e))?;
+
+        let job_id = uuid::Uuid::new_v4().to_string();
+        let stdout = child.stdout.take();
+        let stderr = child.stderr.take();
+
+        let child = Arc::new(Mutex::new(child));
+        let cancelled = Arc::new(AtomicBool::new(false));
+
+        self.running
+            .lock()
+            .map_err(|_| "Job state poisoned".to_string())?
+            .insert(
+                job_id.clone(),
+                JobHandle {
+                    child: child.clone(),
+                    cancelled: cancelled.clone(),
+                    elevated,
+                },
+            );
+
+        emit(
+            window,
+            &job_id,
+            "status",
+            format!("Started {}", label),
+            JobStatus::Running,
+        );
+
+        let readers: Vec<_> = [
+            stdout.map(|p| stream_lines(window.clone(), job_id.clone(), "stdout", p)),
+            stderr.map(|p| stream_lines(window.clone(), job_id.clone(), "stderr", p)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        let window = window.clone();
+        let label = label.to_string();
+        let id = job_id.clone();
+
+        std::thread::spawn(move || {
+            // Poll instead of wait() so cancel_job can lock the child in between
+            let exit = loop {
+                let polled = child.lock().map(|mut c| c.try_wait());
+                match polled {
+                    Ok(Ok(Some(status))) => break Some(status),
+                    Ok(Ok(None)) => std::thread::sleep(POLL_INTERVAL),
+                    _ => break None,
+                }
+            };
+
+            // Drain the pipes so every output line arrives before the final status
+            let tails: Vec<VecDeque<String>> =
+                readers.into_iter().filter_map(|r| r.join().ok()).collect();
+
+            let (status, line) = if cancelled.load(Ordering::SeqCst) {
+                (JobStatus::Cancelled, format!("{} cancelled", label))
+            } else {
+                match exit {
+                    Some(s) if s.success() => match then() {
+                        Ok(()) => (
+                            JobStatus::Succeeded,
+                            format!("{} finished successfully", label),
+                        ),
+                        Err(e) => (
+                            JobStatus::Failed { code: None },
+                            format!("{} failed: {}", label, e),
+                        ),
+                    },
+                    Some(s) => (
+                        JobStatus::Failed { code: s.code() },
+                        format!(
+                            "{} failed: {}",
+                            label,
+                            failure_summary(&tails).unwrap_or_else(|| s.to_string())
+                        ),
+                    ),
+                    None => (
+                        JobStatus::Failed { code: None },
+                        format!("{} failed: lost track of the process", label),
+                    ),
+                }
+            };
+
+            remove_job(window.app_handle(), &id);
+            emit(&window, &id, "status", line, status);
+        });
+
+        Ok(job_id)
+    }
+
+    /// Kill a running job and everything it started. The job still reports
+    /// `cancelled` through its final event.
+    pub async fn cancel(&self, job_id: &str) -> Result<(), String> {
+        let (child, elevated) = {
+            let running = self
+                .running
+                .lock()
+                .map_err(|_| "Job state poisoned".to_string())?;
+            let job = running
+                .get(job_id)
+                .ok_or_else(|| format!("No running job {}", job_id))?;
+
+            job.cancelled.store(true, Ordering::SeqCst);
+            (job.child.clone(), job.elevated)
+        };
+        let pid = child
+            .lock()
+            .map_err(|_| "Job state poisoned".to_string())?
+            .id();
+
+        let killed = kill_tree(pid, elevated).await;
+        // Nothing left to kill when the job ended in the meantime
+        let exited = child
+            .lock()
+            .is_ok_and(|mut c| matches!(c.try_wait(), Ok(Some(_))));
+        if exited {
+            Ok(())
+        } else {
+            killed
+        }
+    }
+
+    pub fn running_ids(&self) -> Vec<String> {
+        self.running
+            .lock()
+            .map(|running| running.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Terminate the process group led by `pid`
+#[cfg(unix)]
+async fn kill_tree(pid: u32, elevated: bool) -> Result<(), String> {
+    let args = vec!["-TERM".to_string(), "--".to_string(), format!("-{}", pid)];
+
+    // Root-owned members (what pkexec or the administrator dialog runs) only
+    // take a signal from root
+    if elevated {
+        return execute_with_privileges("kill".to_string(), args)
+            .await
+            .map(|_| ())
+            .map_err(|e| format!("Failed to cancel job: {}", e));
+    }
+
+    let output = Command::new("kill")
+        .args(&args)
+        .output()
+        .map_err(|e| format!("Failed to cancel job: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Failed to cancel job: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+/// Terminate `pid` and every process it started
+#[cfg(target_os = "windows")]
+async fn kill_tree(pid: u32, _elevated: bool) -> Result<(), String> {
+    let output = Command::new("taskkill")
+        .args(["/PID", &pid.to_string(), "/T", "/F"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .map_err(|e| format!("Failed to cancel job: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Failed to cancel job: {}",
+            String::from_utf8_lossy(&output.stdout).trim()
+        ))
+    }
+}
+
+fn remove_job(app: &AppHandle, job_id: &str) {
+    if let Ok(mut running) = app.state::<Jobs>().running.lock() {
+        running.remove(job_id);
+    }
+}
+
+/// Cancel a running job by ID
+#[tauri::command]
+pub async fn cancel_job(job_id: String, state: State<'_, Jobs>) -> Result<(), String> {
+    state.cancel(&job_id).await
+}
+
+/// IDs of the jobs that are still running
+#[tauri::command]
+pub async fn list_running_jobs(state: State<'_, Jobs>) -> Result<Vec<String>, String> {
+    Ok(state.running_ids())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn cancelling_kills_grandchildren() {
+        let mut cmd = Command::new("sh");
+        // The background sleep inherits stdout, like dpkg under apt
+        cmd.args(["-c", "sleep 30 & echo started; wait"])
+            .stdout(Stdio::piped())
+            .process_group(0);
+        let mut child = cmd.spawn().unwrap();
+
+        let mut reader = BufReader::new(child.stdout.take().unwrap());
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        assert_eq!(line, "started\n");
+
+        let started = std::time::Instant::now();
+        kill_tree(child.id(), false).await.unwrap();
+
+        // EOF only arrives once every process holding the pipe is gone
+        let mut rest = String::new();
+        reader.read_to_string(&mut rest).unwrap();
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert!(!child.wait().unwrap().success());
+    }
+}
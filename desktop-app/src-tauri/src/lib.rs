@@ -1,17 +1,60 @@
+mod composer;
 mod config;
+mod fastcgi;
+mod fpm_pools;
 mod hosts_manager;
+mod hosts_watcher;
+mod jobs;
+mod opcache;
+mod paths;
+mod php_catalog;
+mod php_discovery;
+mod php_extensions;
+mod php_fpm;
+mod php_ini;
 mod php_manager;
+mod php_overrides;
+mod php_standalone;
+mod php_switcher;
+mod project_discovery;
+mod project_inspector;
+mod project_scaffold;
+mod projects;
+mod services;
+mod settings;
 mod system;
 mod types;
+mod vhost_templates;
+mod xdebug;
 
+use composer::*;
+use fpm_pools::*;
 use hosts_manager::*;
+use hosts_watcher::*;
+use jobs::*;
+use opcache::*;
+use paths::*;
+use php_catalog::*;
+use php_extensions::*;
+use php_ini::*;
 use php_manager::*;
+use php_overrides::*;
+use php_standalone::*;
+use php_switcher::*;
+use project_discovery::*;
+use project_inspector::*;
+use project_scaffold::*;
+use projects::*;
+use services::*;
+use settings::*;
 use system::*;
 use tauri::{
     menu::{Menu, MenuItem, Submenu},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
     Manager,
 };
+use vhost_templates::*;
+use xdebug::*;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -21,6 +64,10 @@ pub fn run() {
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_notification::init())
+        .manage(HostsWatcher::default())
+        .manage(ProjectsPark::default())
+        .manage(Jobs::default())
         .setup(|app| {
             // Create tray menu
             let show_item = MenuItem::with_id(app, "show", "Show Window", true, None::<&str>)?;
@@ -32,8 +79,7 @@ pub fn run() {
             let separator2 = MenuItem::with_id(app, "sep2", "─────────────", false, None::<&str>)?;
 
             // Read hosts and create submenu
-            let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
-            let hosts_file = format!("{}/localhost-manager/conf/hosts.json", home);
+            let hosts_file = Layout::resolve().hosts_file;
 
             let mut host_items: Vec<MenuItem<tauri::Wry>> = Vec::new();
             if let Ok(content) = std::fs::read_to_string(&hosts_file) {
@@ -103,29 +149,16 @@ pub fn run() {
                         }
                     }
                     "start_all" => {
-                        // Detect and start all installed services
-                        std::process::Command::new("sh")
-                            .args(["-c", "brew services list | grep -E 'httpd|mysql|php' | awk '{print $1}' | xargs -I {} brew services start {}"])
-                            .spawn()
-                            .ok();
+                        control_all_with_notification(app, ServiceAction::Start);
                     }
                     "stop_all" => {
-                        // Detect and stop all installed services
-                        std::process::Command::new("sh")
-                            .args(["-c", "brew services list | grep -E 'httpd|mysql|php' | awk '{print $1}' | xargs -I {} brew services stop {}"])
-                            .spawn()
-                            .ok();
+                        control_all_with_notification(app, ServiceAction::Stop);
                     }
                     "restart_all" => {
-                        // Detect and restart all installed services
-                        std::process::Command::new("sh")
-                            .args(["-c", "brew services list | grep -E 'httpd|mysql|php' | awk '{print $1}' | xargs -I {} brew services restart {}"])
-                            .spawn()
-                            .ok();
+                        control_all_with_notification(app, ServiceAction::Restart);
                     }
                     "activate_hosts" => {
-                        let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
-                        let hosts_file = format!("{}/localhost-manager/conf/hosts.json", home);
+                        let hosts_file = Layout::resolve().hosts_file;
                         if let Ok(content) = std::fs::read_to_string(&hosts_file) {
                             if let Ok(mut hosts) = serde_json::from_str::<serde_json::Value>(&content) {
                                 if let Some(obj) = hosts.as_object_mut() {
@@ -142,8 +175,7 @@ pub fn run() {
                         }
                     }
                     "deactivate_hosts" => {
-                        let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
-                        let hosts_file = format!("{}/localhost-manager/conf/hosts.json", home);
+                        let hosts_file = Layout::resolve().hosts_file;
                         if let Ok(content) = std::fs::read_to_string(&hosts_file) {
                             if let Ok(mut hosts) = serde_json::from_str::<serde_json::Value>(&content) {
                                 if let Some(obj) = hosts.as_object_mut() {
@@ -160,20 +192,19 @@ pub fn run() {
                         }
                     }
                     "generate" => {
-                        let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
-                        let script = format!("{}/localhost-manager/scripts/generate-all.sh", home);
-                        std::process::Command::new("bash")
-                            .arg(&script)
-                            .spawn()
-                            .ok();
+                        // The generated vhosts include each host's rendered template
+                        std::thread::spawn(|| {
+                            if vhost_templates::render_sites().is_ok() {
+                                let _ = Layout::resolve().script_command("generate-all").output();
+                            }
+                        });
                     }
                     "quit" => {
                         app.exit(0);
                     }
                     id if id.starts_with("toggle_host_") => {
                         let host_name = id.strip_prefix("toggle_host_").unwrap();
-                        let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
-                        let hosts_file = format!("{}/localhost-manager/conf/hosts.json", home);
+                        let hosts_file = Layout::resolve().hosts_file;
                         if let Ok(content) = std::fs::read_to_string(&hosts_file) {
                             if let Ok(mut hosts) = serde_json::from_str::<serde_json::Value>(&content) {
                                 if let Some(obj) = hosts.as_object_mut() {
@@ -208,29 +239,75 @@ pub fn run() {
                 })
                 .build(app)?;
 
+            paths::init(app.handle());
+            hosts_watcher::init(app.handle());
+            project_discovery::init(app.handle());
+            php_switcher::init(app.handle());
+
             Ok(())
         })
         .on_window_event(|window, event| {
             // Hide window instead of closing when clicking the X button
             if let tauri::WindowEvent::CloseRequested { api, .. } = event {
-                window.hide().unwrap();
-                api.prevent_close();
+                let minimize_to_tray = Settings::load()
+                    .map(|s| s.ui.minimize_to_tray)
+                    .unwrap_or(true);
+                if minimize_to_tray {
+                    window.hide().unwrap();
+                    api.prevent_close();
+                }
             }
         })
         .invoke_handler(tauri::generate_handler![
             get_available_php_versions,
+            refresh_php_catalog,
             get_installed_php_versions,
             install_php_version,
             uninstall_php_version,
+            install_standalone_php,
+            remove_standalone_php,
+            use_php_version,
+            get_project_profiles,
+            save_project_profile,
+            delete_project_profile,
+            assign_project_profile,
             get_php_config,
             update_php_ini_setting,
+            get_php_ini,
+            update_php_ini_settings,
+            get_php_overrides,
+            update_php_overrides,
+            get_xdebug_status,
+            set_xdebug,
+            write_xdebug_launch_config,
+            get_php_cache_status,
+            reset_opcache,
+            get_fpm_pools,
+            save_fpm_pool,
+            delete_fpm_pool,
+            assign_fpm_pool,
+            get_composer_project,
+            run_composer,
+            inspect_project,
+            create_project,
+            scan_projects,
+            import_projects,
+            is_projects_park_running,
+            get_vhost_templates,
+            save_vhost_template,
+            reset_vhost_template,
+            preview_vhost_template,
             get_virtual_hosts,
             save_virtual_hosts,
             generate_configs,
             apply_configs,
             control_service,
+            control_all_services,
+            cancel_job,
+            list_running_jobs,
             delete_host,
             get_services_status,
+            get_host_url,
             get_current_php_version,
             get_current_apache_version,
             get_current_mysql_version,
@@ -238,11 +315,19 @@ pub fn run() {
             execute_with_privileges,
             get_home_directory,
             get_scripts_path,
+            get_layout,
+            // Settings
+            get_settings,
+            update_settings,
             detect_installed_stacks,
             setup_directories,
             create_initial_config,
             read_file,
             write_file,
+            // hosts.json Watcher
+            start_hosts_watcher,
+            stop_hosts_watcher,
+            is_hosts_watcher_running,
             // Stack Installer
             detect_platform,
             check_package_installed,
@@ -251,6 +336,7 @@ pub fn run() {
             get_installed_php_versions_list,
             get_php_extensions,
             toggle_php_extension,
+            install_php_extension,
             restart_php_fpm,
             // MySQL Configuration
             check_mysql_connection,
@@ -0,0 +1,268 @@
+// ============================================
+// OPcache and Realpath Cache
+// ============================================
+// The CLI has its own (usually disabled) OPcache, so the numbers that matter
+// live inside the FPM workers. A small status script is deployed next to the
+// FPM php.ini (outside every docroot) and run in each pool over FastCGI; it
+// reports `opcache_get_status()` and the worker's realpath cache, and can
+// call `opcache_reset()`.
+//
+// OPcache memory belongs to the FPM master, so pools of one version share it
+// and a reset through any pool clears it for all of them. The realpath cache
+// is per worker; the numbers are those of whichever worker answered.
+
+use crate::fastcgi::{self, Address};
+use crate::php_fpm::{self, Pool};
+use crate::system::write_file_privileged;
+use crate::types::PhpVersion;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const SCRIPT_NAME: &str = "localhost-manager-status.php";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Cached scripts reported, most hit first
+const MAX_SCRIPTS: usize = 100;
+
+const STATUS_SCRIPT: &str = r#"<?php
+// Deployed by Localhost Manager to read OPcache and realpath cache stats
+// over FastCGI. It isn't inside any docroot; the app runs it directly.
+header('Content-Type: application/json');
+
+$reset = null;
+if (($_SERVER['LHM_ACTION'] ?? '') === 'reset' && function_exists('opcache_reset')) {
+    $reset = opcache_reset();
+}
+
+$opcache = null;
+$status = function_exists('opcache_get_status') ? @opcache_get_status(true) : false;
+if (is_array($status)) {
+    $memory = $status['memory_usage'] ?? [];
+    $stats = $status['opcache_statistics'] ?? [];
+    $scripts = array_values(array_map(fn ($s) => [
+        'path' => $s['full_path'],
+        'hits' => $s['hits'],
+        'memory' => $s['memory_consumption'],
+    ], $status['scripts'] ?? []));
+    usort($scripts, fn ($a, $b) => $b['hits'] <=> $a['hits']);
+
+    $opcache = [
+        'enabled' => (bool) ($status['opcache_enabled'] ?? false),
+        'memory_used' => $memory['used_memory'] ?? 0,
+        'memory_free' => $memory['free_memory'] ?? 0,
+        'memory_wasted' => $memory['wasted_memory'] ?? 0,
+        'hits' => $stats['hits'] ?? 0,
+        'misses' => $stats['misses'] ?? 0,
+        'hit_rate' => $stats['opcache_hit_rate'] ?? 0,
+        'cached_scripts' => $stats['num_cached_scripts'] ?? 0,
+        'max_cached_keys' => $stats['max_cached_keys'] ?? 0,
+        'oom_restarts' => $stats['oom_restarts'] ?? 0,
+        'manual_restarts' => $stats['manual_restarts'] ?? 0,
+        'jit' => (bool) ($status['jit']['on'] ?? false),
+        'scripts' => array_slice($scripts, 0, (int) ($_SERVER['LHM_MAX_SCRIPTS'] ?? 100)),
+    ];
+}
+
+echo json_encode([
+    'php_version' => PHP_VERSION,
+    'pid' => getmypid(),
+    'opcache_loaded' => extension_loaded('Zend OPcache'),
+    'opcache' => $opcache,
+    'realpath' => [
+        'used' => realpath_cache_size(),
+        'limit' => ini_get('realpath_cache_size'),
+        'ttl' => (int) ini_get('realpath_cache_ttl'),
+        'entries' => count(realpath_cache_get()),
+    ],
+    'reset' => $reset,
+]);
+"#;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedScript {
+    pub path: String,
+    pub hits: u64,
+    pub memory: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpcacheStats {
+    pub enabled: bool,
+    pub memory_used: u64,
+    pub memory_free: u64,
+    pub memory_wasted: u64,
+    pub hits: u64,
+    pub misses: u64,
+    /// Percentage, 0-100
+    pub hit_rate: f64,
+    pub cached_scripts: u64,
+    pub max_cached_keys: u64,
+    pub oom_restarts: u64,
+    pub manual_restarts: u64,
+    pub jit: bool,
+    /// Most hit scripts first
+    pub scripts: Vec<CachedScript>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RealpathStats {
+    /// Bytes in use
+    pub used: u64,
+    /// `realpath_cache_size` as configured (e.g. `4096K`)
+    pub limit: String,
+    pub ttl: u64,
+    pub entries: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ScriptOutput {
+    php_version: String,
+    pid: u32,
+    opcache_loaded: bool,
+    opcache: Option<OpcacheStats>,
+    realpath: RealpathStats,
+    reset: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PoolCacheStatus {
+    pub pool: String,
+    pub listen: String,
+    /// Exact PHP version the worker runs
+    pub php_version: Option<String>,
+    /// Worker that answered
+    pub pid: Option<u32>,
+    pub opcache_loaded: bool,
+    /// None when OPcache isn't loaded in the pool
+    pub opcache: Option<OpcacheStats>,
+    pub realpath: Option<RealpathStats>,
+    /// Set by a reset: whether `opcache_reset()` succeeded
+    pub reset: Option<bool>,
+    /// Why the pool couldn't be inspected
+    pub error: Option<String>,
+}
+
+/// Where the status script goes: next to the FPM php.ini, readable by workers
+fn script_path(php: &PhpVersion) -> Result<PathBuf, String> {
+    php.ini_paths
+        .get("fpm")
+        .and_then(|ini| Path::new(ini).parent())
+        .map(|dir| dir.join(SCRIPT_NAME))
+        .ok_or_else(|| format!("PHP {} has no PHP-FPM configuration", php.version))
+}
+
+/// Install (or refresh) the status script for a version
+async fn deploy_script(php: &PhpVersion) -> Result<PathBuf, String> {
+    let path = script_path(php)?;
+    if std::fs::read_to_string(&path).ok().as_deref() != Some(STATUS_SCRIPT) {
+        write_file_privileged(&path, STATUS_SCRIPT).await?;
+    }
+    Ok(path)
+}
+
+async fn query(pool: &Pool, script: &Path, action: &str) -> PoolCacheStatus {
+    let mut status = PoolCacheStatus {
+        pool: pool.name.clone(),
+        listen: pool.listen.clone(),
+        php_version: None,
+        pid: None,
+        opcache_loaded: false,
+        opcache: None,
+        realpath: None,
+        reset: None,
+        error: None,
+    };
+
+    let script = script.to_string_lossy();
+    let max_scripts = MAX_SCRIPTS.to_string();
+    let mut params = fastcgi::cgi_params(&script, "/localhost-manager-status.php", "");
+    params.extend([
+        ("LHM_ACTION", action),
+        ("LHM_MAX_SCRIPTS", max_scripts.as_str()),
+    ]);
+
+    let response = match fastcgi::request(
+        &Address::parse(&pool.listen),
+        &params,
+        &[],
+        REQUEST_TIMEOUT,
+    )
+    .await
+    {
+        Ok(response) => response,
+        Err(e) => {
+            status.error = Some(e);
+            return status;
+        }
+    };
+
+    match serde_json::from_slice::<ScriptOutput>(&response.body) {
+        Ok(output) => {
+            status.php_version = Some(output.php_version);
+            status.pid = Some(output.pid);
+            status.opcache_loaded = output.opcache_loaded;
+            status.opcache = output.opcache;
+            status.realpath = Some(output.realpath);
+            status.reset = output.reset;
+        }
+        Err(_) => {
+            // "Primary script unknown" and friends arrive on stderr
+            let detail = if response.stderr.is_empty() {
+                String::from_utf8_lossy(&response.body).trim().to_string()
+            } else {
+                response.stderr
+            };
+            status.error = Some(format!(
+                "Pool answered {} instead of cache stats: {}",
+                response.status, detail
+            ));
+        }
+    }
+
+    status
+}
+
+/// OPcache and realpath cache stats of every FPM pool of a PHP version
+#[tauri::command]
+pub async fn get_php_cache_status(version: String) -> Result<Vec<PoolCacheStatus>, String> {
+    let php = php_fpm::find(&version)?;
+    let pools = php_fpm::pools(&php);
+    if pools.is_empty() {
+        return Err(format!("No PHP-FPM pools found for PHP {}", php.version));
+    }
+
+    let script = deploy_script(&php).await?;
+    let mut statuses = Vec::new();
+    for pool in &pools {
+        statuses.push(query(pool, &script, "status").await);
+    }
+    Ok(statuses)
+}
+
+/// Clear OPcache through one pool. Pools of the same version share the cache,
+/// so this resets it for all of them.
+#[tauri::command]
+pub async fn reset_opcache(version: String, pool: String) -> Result<PoolCacheStatus, String> {
+    let php = php_fpm::find(&version)?;
+    let target = php_fpm::pools(&php)
+        .into_iter()
+        .find(|p| p.name == pool)
+        .ok_or_else(|| format!("Pool '{}' not found for PHP {}", pool, php.version))?;
+
+    let script = deploy_script(&php).await?;
+    let status = query(&target, &script, "reset").await;
+
+    match (&status.error, status.reset) {
+        (Some(e), _) => Err(e.clone()),
+        (None, Some(false)) => Err(format!(
+            "opcache_reset() was refused in pool '{}' (opcache.restrict_api?)",
+            pool
+        )),
+        (None, None) if !status.opcache_loaded => {
+            Err(format!("OPcache isn't loaded in pool '{}'", pool))
+        }
+        _ => Ok(status),
+    }
+}
@@ -0,0 +1,262 @@
+// ============================================
+// Filesystem Layout
+// ============================================
+// Single source of truth for where the app keeps its files:
+//
+// - `config_dir`: per-user app config (settings.json, config.json), following
+//   XDG on Linux, Application Support on macOS and %APPDATA% on Windows.
+// - `base_dir`: the manager directory holding conf/, scripts/ and certs/.
+//   Resolved from $LOCALHOST_MANAGER_HOME, then `configPath` in settings.json
+//   (written by the setup wizard), then the legacy ~/localhost-manager, and
+//   finally the XDG data dir on Linux.
+// - `scripts_dir`: the bundled scripts. `<base_dir>/scripts` when it holds a
+//   copy (a git checkout, or one made by the setup wizard), otherwise the
+//   app's own resources, so a fresh `configPath` works without copying.
+
+use crate::config::AppConfig;
+use crate::settings::{Settings, WebServer};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+use tauri::{AppHandle, Manager};
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// Environment variable that overrides the manager directory
+pub const HOME_ENV: &str = "LOCALHOST_MANAGER_HOME";
+
+const APP_DIR_NAME: &str = "localhost-manager";
+
+/// Scripts shipped in the app bundle, set once the app has started
+static BUNDLED_SCRIPTS: OnceLock<PathBuf> = OnceLock::new();
+
+/// Record where the bundle keeps its scripts
+pub fn init(app: &AppHandle) {
+    if let Ok(dir) = app.path().resource_dir() {
+        let _ = BUNDLED_SCRIPTS.set(dir.join("scripts"));
+    }
+}
+
+/// Whether `dir` holds the scripts (the Windows ones live in `windows/`)
+fn has_scripts(dir: &Path) -> bool {
+    if cfg!(target_os = "windows") {
+        dir.join("windows").join("generate-all.ps1").is_file()
+    } else {
+        dir.join("generate-all.sh").is_file()
+    }
+}
+
+/// Scripts directory of the bundle, or of the source tree in debug builds
+pub fn bundled_scripts_dir() -> Option<PathBuf> {
+    let source_tree =
+        cfg!(debug_assertions).then(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("../../scripts"));
+    BUNDLED_SCRIPTS
+        .get()
+        .cloned()
+        .into_iter()
+        .chain(source_tree)
+        .find(|dir| has_scripts(dir))
+}
+
+/// Get the home directory in a cross-platform way
+pub fn home_dir() -> PathBuf {
+    #[cfg(target_os = "windows")]
+    {
+        std::env::var("USERPROFILE")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| {
+                // Fallback: try to construct from HOMEDRIVE + HOMEPATH
+                let drive = std::env::var("HOMEDRIVE").unwrap_or_else(|_| String::from("C:"));
+                let path =
+                    std::env::var("HOMEPATH").unwrap_or_else(|_| String::from("\\Users\\Default"));
+                PathBuf::from(format!("{}{}", drive, path))
+            })
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        std::env::var("HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from("/tmp"))
+    }
+}
+
+/// Get the app config directory in a cross-platform way
+pub fn config_dir() -> PathBuf {
+    #[cfg(target_os = "windows")]
+    {
+        let appdata = std::env::var("APPDATA")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| home_dir().join("AppData").join("Roaming"));
+        appdata.join(APP_DIR_NAME)
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        home_dir()
+            .join("Library")
+            .join("Application Support")
+            .join(APP_DIR_NAME)
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        xdg_dir("XDG_CONFIG_HOME", &[".config"])
+    }
+}
+
+/// Resolve an XDG base directory, ignoring empty or relative values as the spec requires
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn xdg_dir(var: &str, fallback: &[&str]) -> PathBuf {
+    std::env::var(var)
+        .ok()
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .unwrap_or_else(|| fallback.iter().fold(home_dir(), |acc, part| acc.join(part)))
+        .join(APP_DIR_NAME)
+}
+
+/// Path of the settings file written by the setup wizard
+pub fn settings_file() -> PathBuf {
+    config_dir().join("settings.json")
+}
+
+/// Older setup wizards wrote settings.json next to hosts.json instead
+pub fn legacy_settings_file() -> PathBuf {
+    home_dir()
+        .join(APP_DIR_NAME)
+        .join("conf")
+        .join("settings.json")
+}
+
+fn non_empty_path(value: Option<String>) -> Option<PathBuf> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Default manager directory when nothing is configured
+fn default_base_dir() -> PathBuf {
+    let legacy = home_dir().join(APP_DIR_NAME);
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        if legacy.exists() {
+            legacy
+        } else {
+            xdg_dir("XDG_DATA_HOME", &[".local", "share"])
+        }
+    }
+
+    #[cfg(any(target_os = "windows", target_os = "macos"))]
+    {
+        legacy
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Layout {
+    pub config_dir: PathBuf,
+    pub base_dir: PathBuf,
+    pub conf_dir: PathBuf,
+    pub hosts_file: PathBuf,
+    pub scripts_dir: PathBuf,
+    pub certs_dir: PathBuf,
+    pub projects_dir: Option<PathBuf>,
+    pub php_install_dir: PathBuf,
+    pub projects_config_dir: PathBuf,
+}
+
+impl Layout {
+    /// Resolve the layout from the environment, settings.json and config.json
+    pub fn resolve() -> Self {
+        let settings = Settings::load().unwrap_or_default();
+        let app_config = AppConfig::load().unwrap_or_default();
+
+        let base_dir = std::env::var(HOME_ENV)
+            .ok()
+            .and_then(|v| non_empty_path(Some(v)))
+            .or_else(|| non_empty_path(settings.config_path))
+            .unwrap_or_else(default_base_dir);
+
+        let conf_dir = base_dir.join("conf");
+
+        let own_scripts = base_dir.join("scripts");
+        let scripts_root = if has_scripts(&own_scripts) {
+            own_scripts
+        } else {
+            bundled_scripts_dir().unwrap_or(own_scripts)
+        };
+        let scripts_dir = if cfg!(target_os = "windows") {
+            scripts_root.join("windows")
+        } else {
+            scripts_root
+        };
+
+        let certs_dir = non_empty_path(settings.ssl_path).unwrap_or_else(|| base_dir.join("certs"));
+
+        Self {
+            config_dir: config_dir(),
+            hosts_file: conf_dir.join("hosts.json"),
+            conf_dir,
+            scripts_dir,
+            certs_dir,
+            projects_dir: non_empty_path(settings.projects_path),
+            php_install_dir: app_config.php_install_dir,
+            projects_config_dir: app_config.projects_config_dir,
+            base_dir,
+        }
+    }
+
+    /// Export the layout to a child process so the bundled scripts
+    /// read and write the same directories (and use the same ports) as the app
+    pub fn apply_env(&self, cmd: &mut Command) {
+        cmd.env(HOME_ENV, &self.base_dir)
+            .env("HOSTS_JSON", &self.hosts_file)
+            .env("CERT_DIR", &self.certs_dir)
+            .env("SITES_DIR", self.conf_dir.join("sites"));
+
+        let settings = Settings::load().unwrap_or_default();
+        cmd.env(
+            "WEB_SERVER",
+            match settings.web_server {
+                WebServer::Apache => "apache",
+                WebServer::Nginx => "nginx",
+            },
+        );
+        settings.ports.apply_env(cmd);
+    }
+
+    /// Build a command running one of the bundled scripts (`generate-all`,
+    /// `install`, ...): bash on macOS/Linux, PowerShell on Windows
+    pub fn script_command(&self, name: &str) -> Command {
+        let mut cmd = if cfg!(target_os = "windows") {
+            let script = self.scripts_dir.join(format!("{}.ps1", name));
+            let mut cmd = Command::new("powershell");
+            cmd.args(["-ExecutionPolicy", "Bypass", "-File"])
+                .arg(script);
+            cmd
+        } else {
+            let mut cmd = Command::new("bash");
+            cmd.arg(self.scripts_dir.join(format!("{}.sh", name)));
+            cmd
+        };
+
+        #[cfg(target_os = "windows")]
+        cmd.creation_flags(CREATE_NO_WINDOW);
+
+        self.apply_env(&mut cmd);
+        cmd
+    }
+}
+
+#[tauri::command]
+pub async fn get_layout() -> Result<Layout, String> {
+    Ok(Layout::resolve())
+}
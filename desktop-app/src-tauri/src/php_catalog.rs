@@ -0,0 +1,528 @@
+// ============================================
+// PHP Version Catalog
+// ============================================
+// Discovers the PHP versions the platform's package source can install:
+// apt (including the ondrej PPA), dnf module streams or Homebrew formulae.
+// Results are cached in the config dir so the list still works offline;
+// without a cache we fall back to a built-in list of supported branches.
+
+use crate::paths;
+use crate::php_discovery;
+use crate::types::PhpVersion;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// How long a cached catalog is used before querying the package source again
+const CACHE_TTL_SECS: i64 = 24 * 60 * 60;
+
+/// Last resort when there is neither a package source nor a cache
+const BUILTIN_VERSIONS: [&str; 6] = ["8.4.1", "8.3.14", "8.2.26", "8.1.30", "8.0.30", "7.4.33"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CatalogCache {
+    source: String,
+    /// Unix timestamp of the last successful query
+    updated_at: i64,
+    versions: Vec<PhpVersion>,
+}
+
+fn cache_file() -> PathBuf {
+    paths::config_dir().join("php-catalog.json")
+}
+
+fn read_cache() -> Option<CatalogCache> {
+    let content = std::fs::read_to_string(cache_file()).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn write_cache(cache: &CatalogCache) {
+    let file = cache_file();
+    if let Some(parent) = file.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    if let Ok(content) = serde_json::to_string_pretty(cache) {
+        let _ = std::fs::write(file, content);
+    }
+}
+
+/// Parse `8.3.14`, `8.3`, `1:8.3.14-1+ubuntu22.04.1+deb.sury.org+1` and similar
+/// package versions into major/minor/patch
+fn parse_version(raw: &str) -> Option<(u8, u8, u8)> {
+    // Drop the Debian epoch and anything after the upstream version
+    let upstream = raw.split_once(':').map(|(_, v)| v).unwrap_or(raw);
+    let upstream = upstream
+        .split(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .next()?;
+
+    let mut parts = upstream.split('.').map(|p| p.parse::<u8>().ok());
+    let major = parts.next()??;
+    let minor = parts.next()??;
+    let patch = parts.next().flatten().unwrap_or(0);
+
+    Some((major, minor, patch))
+}
+
+fn php_version(raw: &str, installed: bool, download_url: Option<String>) -> Option<PhpVersion> {
+    let (major, minor, patch) = parse_version(raw)?;
+    let version = raw
+        .split_once(':')
+        .map(|(_, v)| v)
+        .unwrap_or(raw)
+        .split(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .next()
+        .unwrap_or(raw)
+        .to_string();
+
+    Some(PhpVersion {
+        version,
+        major,
+        minor,
+        patch,
+        installed,
+        download_url,
+        ..Default::default()
+    })
+}
+
+fn command_stdout(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+// ============================================
+// apt (Debian/Ubuntu, ondrej PPA)
+// ============================================
+
+#[derive(Debug, Default, PartialEq)]
+struct AptPolicy {
+    installed: Option<String>,
+    candidate: Option<String>,
+    /// Repository base URL serving the candidate version
+    repo_url: Option<String>,
+}
+
+/// Parse `apt-cache policy <pkg>` output
+fn parse_apt_policy(output: &str) -> AptPolicy {
+    let mut policy = AptPolicy::default();
+    let mut in_candidate = false;
+
+    for line in output.lines() {
+        let trimmed = line.trim();
+
+        if let Some(v) = trimmed.strip_prefix("Installed:") {
+            policy.installed = Some(v.trim().to_string()).filter(|v| v != "(none)");
+            continue;
+        }
+        if let Some(v) = trimmed.strip_prefix("Candidate:") {
+            policy.candidate = Some(v.trim().to_string()).filter(|v| v != "(none)");
+            continue;
+        }
+
+        // Version table: "*** 8.3.14-1 500" (installed) or "8.3.14-1 500", each
+        // followed by priority lines such as "500 https://ppa... jammy/main amd64 Packages"
+        let mut parts = trimmed.trim_start_matches("*** ").split_whitespace();
+        let (Some(first), Some(second)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+
+        if second.contains("://") || second.starts_with('/') {
+            if in_candidate && policy.repo_url.is_none() && second.contains("://") {
+                policy.repo_url = Some(second.trim_end_matches('/').to_string());
+            }
+        } else if first.starts_with(|c: char| c.is_ascii_digit()) {
+            in_candidate = policy.candidate.as_deref() == Some(first);
+        }
+    }
+
+    policy
+}
+
+fn apt_catalog() -> Option<Vec<PhpVersion>> {
+    let names = command_stdout(
+        "apt-cache",
+        &["search", "--names-only", r"^php[0-9]+\.[0-9]+-cli$"],
+    )?;
+
+    let mut versions = Vec::new();
+    for name in names.lines().filter_map(|l| l.split_whitespace().next()) {
+        let Some(policy) =
+            command_stdout("apt-cache", &["policy", name]).map(|o| parse_apt_policy(&o))
+        else {
+            continue;
+        };
+
+        let Some(raw) = policy.candidate.as_ref().or(policy.installed.as_ref()) else {
+            continue;
+        };
+
+        // Full .deb URL: repository base + "Filename:" from the package record
+        let download_url = policy.repo_url.as_ref().and_then(|base| {
+            let show = command_stdout("apt-cache", &["show", &format!("{}={}", name, raw)])?;
+            show.lines()
+                .find_map(|l| l.strip_prefix("Filename:"))
+                .map(|file| format!("{}/{}", base, file.trim()))
+        });
+
+        if let Some(version) = php_version(raw, policy.installed.is_some(), download_url) {
+            versions.push(version);
+        }
+    }
+
+    Some(versions).filter(|v| !v.is_empty())
+}
+
+// ============================================
+// dnf module streams (Fedora/RHEL)
+// ============================================
+
+/// Parse `dnf module list php` into (stream, enabled) pairs
+fn parse_dnf_streams(output: &str) -> Vec<(String, bool)> {
+    output
+        .lines()
+        .filter(|line| line.starts_with("php "))
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace().skip(1);
+            let stream = parts.next()?.to_string();
+            let enabled = parts
+                .next()
+                .map(|flag| flag.contains("[e]"))
+                .unwrap_or(false);
+            parse_version(&stream).map(|_| (stream, enabled))
+        })
+        .collect()
+}
+
+fn dnf_catalog() -> Option<Vec<PhpVersion>> {
+    // Newer Fedora releases dropped modules; the stream list is empty there
+    let streams = parse_dnf_streams(
+        &command_stdout("dnf", &["module", "list", "php", "-q"]).unwrap_or_default(),
+    );
+
+    // Full version of the installed PHP, when any
+    let installed = command_stdout("rpm", &["-q", "--qf", "%{VERSION}", "php-cli"]);
+    let installed = installed.as_deref().and_then(parse_version);
+
+    // Only the enabled stream (or the non-modular package) can be resolved to an RPM
+    let available = command_stdout(
+        "dnf",
+        &[
+            "repoquery",
+            "-q",
+            "--latest-limit=1",
+            "--qf",
+            "%{version}",
+            "php-cli",
+        ],
+    );
+    let location = command_stdout(
+        "dnf",
+        &[
+            "repoquery",
+            "-q",
+            "--latest-limit=1",
+            "--location",
+            "php-cli",
+        ],
+    )
+    .and_then(|o| o.lines().next().map(|l| l.trim().to_string()))
+    .filter(|l| !l.is_empty());
+
+    let mut versions: Vec<PhpVersion> = streams
+        .into_iter()
+        .filter_map(|(stream, enabled)| {
+            let (major, minor, _) = parse_version(&stream)?;
+            let is_installed = installed.is_some_and(|(ma, mi, _)| ma == major && mi == minor);
+
+            let full = if enabled {
+                available.clone().filter(|v| parse_version(v).is_some())
+            } else {
+                None
+            };
+            let raw = full.unwrap_or(stream);
+            let url = if enabled { location.clone() } else { None };
+
+            php_version(&raw, is_installed, url)
+        })
+        .collect();
+
+    // Fedora ships a single non-modular PHP
+    if versions.is_empty() {
+        if let Some(raw) = available {
+            versions.extend(php_version(raw.trim(), installed.is_some(), location));
+        }
+    }
+
+    Some(versions).filter(|v| !v.is_empty())
+}
+
+// ============================================
+// Homebrew (macOS)
+// ============================================
+
+#[derive(Debug, Deserialize)]
+struct BrewInfo {
+    formulae: Vec<BrewFormula>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BrewFormula {
+    versions: BrewVersions,
+    #[serde(default)]
+    installed: Vec<serde_json::Value>,
+    urls: Option<BrewUrls>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BrewVersions {
+    stable: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BrewUrls {
+    stable: Option<BrewUrl>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BrewUrl {
+    url: String,
+}
+
+fn brew_catalog() -> Option<Vec<PhpVersion>> {
+    let names = command_stdout("brew", &["search", "--formula", r"/^php(@[0-9.]+)?$/"])?;
+    let names: Vec<&str> = names
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect();
+    if names.is_empty() {
+        return None;
+    }
+
+    let mut args = vec!["info", "--json=v2", "--formula"];
+    args.extend(names);
+    let info: BrewInfo = serde_json::from_str(&command_stdout("brew", &args)?).ok()?;
+
+    let versions: Vec<PhpVersion> = info
+        .formulae
+        .into_iter()
+        .filter_map(|formula| {
+            let stable = formula.versions.stable?;
+            let url = formula.urls.and_then(|u| u.stable).map(|u| u.url);
+            php_version(&stable, !formula.installed.is_empty(), url)
+        })
+        .collect();
+
+    Some(versions).filter(|v| !v.is_empty())
+}
+
+// ============================================
+// Catalog
+// ============================================
+
+fn builtin_catalog() -> Vec<PhpVersion> {
+    BUILTIN_VERSIONS
+        .iter()
+        .filter_map(|v| php_version(v, false, None))
+        .collect()
+}
+
+/// Query the platform package source. Returns the source name with the versions.
+fn discover() -> Option<(&'static str, Vec<PhpVersion>)> {
+    if cfg!(target_os = "macos") {
+        brew_catalog().map(|v| ("homebrew", v))
+    } else if cfg!(target_os = "linux") {
+        apt_catalog()
+            .map(|v| ("apt", v))
+            .or_else(|| dnf_catalog().map(|v| ("dnf", v)))
+    } else {
+        None
+    }
+}
+
+/// Keep one entry per major.minor (the newest), newest branch first
+fn normalize(versions: Vec<PhpVersion>) -> Vec<PhpVersion> {
+    let mut by_branch: BTreeMap<(u8, u8), PhpVersion> = BTreeMap::new();
+
+    for version in versions {
+        let key = (version.major, version.minor);
+        match by_branch.get(&key) {
+            Some(existing) if existing.patch >= version.patch && !version.installed => {}
+            _ => {
+                by_branch.insert(key, version);
+            }
+        }
+    }
+
+    by_branch.into_values().rev().collect()
+}
+
+/// Mark the branches that are installed right now. Cached flags go stale as
+/// soon as a version is installed or removed, by the app or by hand.
+fn overlay_installed(mut versions: Vec<PhpVersion>, installed: &[PhpVersion]) -> Vec<PhpVersion> {
+    for version in &mut versions {
+        version.installed = installed
+            .iter()
+            .any(|php| php.major == version.major && php.minor == version.minor);
+    }
+    versions
+}
+
+/// Installable PHP versions, from the cache when fresh unless `refresh` is set.
+/// `installed` reflects the PHP versions found on this machine at every call.
+pub fn available_versions(refresh: bool) -> Vec<PhpVersion> {
+    let installed = php_discovery::installed_versions();
+    let cache = read_cache();
+
+    if !refresh {
+        if let Some(cache) = &cache {
+            if Utc::now().timestamp() - cache.updated_at < CACHE_TTL_SECS {
+                return overlay_installed(cache.versions.clone(), &installed);
+            }
+        }
+    }
+
+    let versions = match discover() {
+        Some((source, versions)) => {
+            let versions = normalize(versions);
+            write_cache(&CatalogCache {
+                source: source.to_string(),
+                updated_at: Utc::now().timestamp(),
+                versions: versions.clone(),
+            });
+            versions
+        }
+        // Offline or no supported package manager: stale cache beats nothing
+        None => cache.map(|c| c.versions).unwrap_or_else(builtin_catalog),
+    };
+    overlay_installed(versions, &installed)
+}
+
+/// Re-query the package source, bypassing the cache
+#[tauri::command]
+pub async fn refresh_php_catalog() -> Result<Vec<PhpVersion>, String> {
+    tauri::async_runtime::spawn_blocking(|| available_versions(true))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_package_versions() {
+        let cases = [
+            ("8.3.14", Some((8, 3, 14))),
+            ("8.3", Some((8, 3, 0))),
+            ("1:8.3.14-1+ubuntu22.04.1+deb.sury.org+1", Some((8, 3, 14))),
+            ("8.2.26-1~deb12u1", Some((8, 2, 26))),
+            ("7.4.33_6", Some((7, 4, 33))),
+            ("8.4.0RC4", Some((8, 4, 0))),
+            ("8", None),
+            ("php", None),
+            ("", None),
+        ];
+        for (raw, expected) in cases {
+            assert_eq!(parse_version(raw), expected, "{}", raw);
+        }
+
+        let version = php_version("1:8.3.14-1+ubuntu22.04.1", true, None).unwrap();
+        assert_eq!(version.version, "8.3.14");
+        assert!(version.installed);
+    }
+
+    #[test]
+    fn parses_apt_policy_with_installed_version() {
+        let output = "\
+php8.3-cli:
+  Installed: 8.3.12-1+ubuntu22.04.1+deb.sury.org+1
+  Candidate: 8.3.14-1+ubuntu22.04.1+deb.sury.org+1
+  Version table:
+     8.3.14-1+ubuntu22.04.1+deb.sury.org+1 500
+        500 https://ppa.launchpadcontent.net/ondrej/php/ubuntu/ jammy/main amd64 Packages
+ *** 8.3.12-1+ubuntu22.04.1+deb.sury.org+1 100
+        100 /var/lib/dpkg/status
+";
+        assert_eq!(
+            parse_apt_policy(output),
+            AptPolicy {
+                installed: Some("8.3.12-1+ubuntu22.04.1+deb.sury.org+1".to_string()),
+                candidate: Some("8.3.14-1+ubuntu22.04.1+deb.sury.org+1".to_string()),
+                repo_url: Some("https://ppa.launchpadcontent.net/ondrej/php/ubuntu".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn parses_apt_policy_of_an_uninstalled_package() {
+        let output = "\
+php8.1-cli:
+  Installed: (none)
+  Candidate: 8.1.2-1ubuntu2.19
+  Version table:
+     8.1.2-1ubuntu2.19 500
+        500 http://archive.ubuntu.com/ubuntu jammy-updates/main amd64 Packages
+        500 http://security.ubuntu.com/ubuntu jammy-security/main amd64 Packages
+     8.1.2-1ubuntu2 500
+        500 http://mirror.example.org/ubuntu jammy/main amd64 Packages
+";
+        assert_eq!(
+            parse_apt_policy(output),
+            AptPolicy {
+                installed: None,
+                candidate: Some("8.1.2-1ubuntu2.19".to_string()),
+                repo_url: Some("http://archive.ubuntu.com/ubuntu".to_string()),
+            }
+        );
+
+        assert_eq!(
+            parse_apt_policy("php9.0-cli:\n  Installed: (none)\n  Candidate: (none)\n"),
+            AptPolicy::default()
+        );
+    }
+
+    #[test]
+    fn parses_dnf_module_streams() {
+        let output = "\
+Last metadata expiration check: 0:12:03 ago.
+Rocky Linux 9 - AppStream
+Name Stream   Profiles                      Summary
+php  8.1      common [d], devel, minimal    PHP scripting language
+php  8.2 [e]  common [d] [i], devel, minimal PHP scripting language
+php  8.3      common [d], devel, minimal    PHP scripting language
+phpunit 9     default                       Testing framework
+php  remi-8.4 common [d]                    PHP from Remi
+
+Hint: [d]efault, [e]nabled, [x]disabled, [i]nstalled
+";
+        assert_eq!(
+            parse_dnf_streams(output),
+            vec![
+                ("8.1".to_string(), false),
+                ("8.2".to_string(), true),
+                ("8.3".to_string(), false),
+            ]
+        );
+        assert!(parse_dnf_streams("").is_empty());
+    }
+
+    #[test]
+    fn installed_flags_follow_the_machine() {
+        let catalog = vec![
+            php_version("8.3.14", true, None).unwrap(),
+            php_version("8.2.26", false, None).unwrap(),
+        ];
+        let installed = vec![php_version("8.2.10", true, None).unwrap()];
+
+        let versions = overlay_installed(catalog, &installed);
+        assert!(!versions[0].installed);
+        assert!(versions[1].installed);
+    }
+}
@@ -0,0 +1,443 @@
+// ============================================
+// Installed PHP Discovery
+// ============================================
+// Finds PHP binaries wherever the common installers put them (Debian
+// alternatives, remi SCL, Homebrew, phpenv/phpbrew, our own standalone
+// installs, Windows stacks and PATH) and probes each one for its version,
+// SAPIs and ini files. Every installed-PHP listing goes through here.
+
+use crate::config::AppConfig;
+use crate::hosts_manager::run_command;
+use crate::paths;
+use crate::types::PhpVersion;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Prints version, loaded php.ini, scan dir and resolved binary as JSON
+const PROBE_SCRIPT: &str =
+    "echo json_encode([PHP_VERSION, php_ini_loaded_file(), PHP_CONFIG_FILE_SCAN_DIR, PHP_BINARY]);";
+
+#[derive(Debug, Deserialize)]
+struct Probe(String, serde_json::Value, String, String);
+
+struct Candidate {
+    binary: PathBuf,
+    source: &'static str,
+}
+
+fn subdirs(dir: &Path) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|e| e.path())
+                .filter(|p| p.is_dir())
+                .collect()
+        })
+        .unwrap_or_default();
+    dirs.sort();
+    dirs
+}
+
+/// `php8.3`, `php7.4` and the like
+fn is_versioned_php_name(name: &str) -> bool {
+    name.strip_prefix("php")
+        .and_then(|rest| rest.split_once('.'))
+        .is_some_and(|(major, minor)| {
+            !major.is_empty()
+                && !minor.is_empty()
+                && major.chars().all(|c| c.is_ascii_digit())
+                && minor.chars().all(|c| c.is_ascii_digit())
+        })
+}
+
+fn php_binary_name() -> &'static str {
+    if cfg!(target_os = "windows") {
+        "php.exe"
+    } else {
+        "php"
+    }
+}
+
+fn candidates() -> Vec<Candidate> {
+    let mut found = Vec::new();
+    let mut push = |binary: PathBuf, source: &'static str| {
+        if binary.is_file() {
+            found.push(Candidate { binary, source });
+        }
+    };
+
+    if cfg!(target_os = "windows") {
+        push(PathBuf::from("C:\\xampp\\php\\php.exe"), "xampp");
+        for dir in subdirs(Path::new("C:\\wamp64\\bin\\php")) {
+            push(dir.join("php.exe"), "wamp");
+        }
+        for dir in subdirs(Path::new("C:\\laragon\\bin\\php")) {
+            push(dir.join("php.exe"), "laragon");
+        }
+    } else {
+        // Debian/Ubuntu (including the ondrej PPA): /usr/bin/phpX.Y managed by alternatives
+        if let Ok(entries) = std::fs::read_dir("/usr/bin") {
+            for entry in entries.filter_map(Result::ok) {
+                if is_versioned_php_name(&entry.file_name().to_string_lossy()) {
+                    push(entry.path(), "debian");
+                }
+            }
+        }
+        if let Some(list) = run_command("update-alternatives", &["--list", "php"]) {
+            for line in list.lines() {
+                push(PathBuf::from(line.trim()), "debian");
+            }
+        }
+
+        // remi software collections: /opt/remi/php83/root/usr/bin/php
+        for dir in subdirs(Path::new("/opt/remi")) {
+            push(dir.join("root/usr/bin/php"), "remi");
+        }
+
+        // Homebrew keeps a stable opt/ symlink per formula
+        for prefix in ["/opt/homebrew/opt", "/usr/local/opt"] {
+            for dir in subdirs(Path::new(prefix)) {
+                let name = dir.file_name().unwrap_or_default().to_string_lossy();
+                if name == "php" || name.starts_with("php@") {
+                    push(dir.join("bin/php"), "homebrew");
+                }
+            }
+        }
+
+        let home = paths::home_dir();
+        for dir in subdirs(&home.join(".phpenv/versions")) {
+            push(dir.join("bin/php"), "phpenv");
+        }
+        for dir in subdirs(&home.join(".phpbrew/php")) {
+            push(dir.join("bin/php"), "phpbrew");
+        }
+    }
+
+    // Standalone versions installed by the app
+    let install_dir = AppConfig::load().unwrap_or_default().php_install_dir;
+    for dir in subdirs(&install_dir) {
+        push(dir.join("bin").join(php_binary_name()), "managed");
+        push(dir.join(php_binary_name()), "managed");
+    }
+
+    // Anything else reachable through PATH (custom prefixes, asdf/mise shims, ...)
+    if let Some(path_var) = std::env::var_os("PATH") {
+        for dir in std::env::split_paths(&path_var) {
+            push(dir.join(php_binary_name()), "path");
+            if let Ok(entries) = std::fs::read_dir(&dir) {
+                for entry in entries.filter_map(Result::ok) {
+                    if is_versioned_php_name(&entry.file_name().to_string_lossy()) {
+                        push(entry.path(), "path");
+                    }
+                }
+            }
+        }
+    }
+
+    found
+}
+
+fn probe(binary: &Path) -> Option<Probe> {
+    let output = run_command(&binary.to_string_lossy(), &["-r", PROBE_SCRIPT])?;
+    serde_json::from_str(output.trim()).ok()
+}
+
+/// Sibling binary for another SAPI or tool: `php8.3` → `php-fpm8.3` on
+/// Debian, `bin/php` → `sbin/php-fpm` for prefix installs
+pub(crate) fn sibling_binary(binary: &Path, sapi_name: &str) -> Option<PathBuf> {
+    let file_name = binary.file_name()?.to_string_lossy().to_string();
+    let dir = binary.parent()?;
+    let suffix = file_name
+        .trim_start_matches("php")
+        .trim_end_matches(".exe")
+        .to_string();
+    let exe = if file_name.ends_with(".exe") {
+        ".exe"
+    } else {
+        ""
+    };
+    let name = format!("{}{}{}", sapi_name, suffix, exe);
+
+    let mut dirs = vec![dir.to_path_buf()];
+    if let Some(prefix) = dir.parent() {
+        dirs.push(prefix.join("sbin"));
+    }
+    if dir == Path::new("/usr/bin") {
+        dirs.push(PathBuf::from("/usr/sbin"));
+    }
+
+    dirs.into_iter()
+        .map(|d| d.join(&name))
+        .find(|p| p.is_file())
+}
+
+fn detect_sapis(binary: &Path, major: u8, minor: u8) -> Vec<String> {
+    let mut sapis = vec!["cli".to_string()];
+
+    // Windows builds ship php-cgi.exe, which the stacks run as their FastCGI backend
+    for (sapi, name) in [("fpm", "php-fpm"), ("cgi", "php-cgi"), ("phpdbg", "phpdbg")] {
+        if sibling_binary(binary, name).is_some() {
+            sapis.push(sapi.to_string());
+        }
+    }
+
+    let apache_module = [
+        format!("/usr/lib/apache2/modules/libphp{}.{}.so", major, minor),
+        format!("/usr/lib/apache2/modules/libphp{}.so", major),
+    ];
+    let prefix_module = binary
+        .parent()
+        .and_then(Path::parent)
+        .map(|prefix| prefix.join("lib/httpd/modules/libphp.so"));
+
+    if apache_module.iter().any(|p| Path::new(p).exists())
+        || prefix_module.is_some_and(|p| p.exists())
+    {
+        sapis.push("apache2handler".to_string());
+    }
+
+    sapis
+}
+
+/// Where Debian keeps one configuration directory per PHP branch
+const DEBIAN_INI_ROOT: &str = "/etc/php";
+
+/// php.ini per SAPI. Debian keeps one per SAPI under `<debian_root>/X.Y`;
+/// everyone else shares the file the CLI loads.
+fn ini_paths(
+    debian_root: &Path,
+    sapis: &[String],
+    loaded: Option<&str>,
+    major: u8,
+    minor: u8,
+) -> BTreeMap<String, String> {
+    let debian_dir = debian_root.join(format!("{}.{}", major, minor));
+    let mut paths = BTreeMap::new();
+
+    for sapi in sapis {
+        let debian_sapi = if sapi == "apache2handler" {
+            "apache2"
+        } else {
+            sapi.as_str()
+        };
+        let debian_ini = debian_dir.join(debian_sapi).join("php.ini");
+
+        if debian_ini.exists() {
+            paths.insert(sapi.clone(), debian_ini.to_string_lossy().to_string());
+        } else if let Some(loaded) = loaded {
+            paths.insert(sapi.clone(), loaded.to_string());
+        }
+    }
+
+    paths
+}
+
+/// Install prefix: the directory above `bin/` for prefix layouts, else the binary's directory
+fn install_path(binary: &Path) -> PathBuf {
+    let dir = binary.parent().unwrap_or(binary);
+    match dir.file_name().and_then(|n| n.to_str()) {
+        Some("bin") | Some("sbin") => dir.parent().unwrap_or(dir).to_path_buf(),
+        _ => dir.to_path_buf(),
+    }
+}
+
+fn inspect(candidate: &Candidate) -> Option<PhpVersion> {
+    let Probe(version, loaded_ini, scan_dir, resolved) = probe(&candidate.binary)?;
+
+    let mut parts = version
+        .split(|c: char| !c.is_ascii_digit())
+        .map(|p| p.parse::<u8>().unwrap_or(0));
+    let major = parts.next()?;
+    let minor = parts.next().unwrap_or(0);
+    let patch = parts.next().unwrap_or(0);
+
+    let binary = if resolved.is_empty() {
+        candidate.binary.clone()
+    } else {
+        PathBuf::from(resolved)
+    };
+    let sapis = detect_sapis(&candidate.binary, major, minor);
+
+    Some(PhpVersion {
+        version,
+        major,
+        minor,
+        patch,
+        installed: true,
+        install_path: Some(install_path(&binary).to_string_lossy().to_string()),
+        download_url: None,
+        ini_paths: ini_paths(
+            Path::new(DEBIAN_INI_ROOT),
+            &sapis,
+            loaded_ini.as_str(),
+            major,
+            minor,
+        ),
+        ini_scan_dir: Some(scan_dir).filter(|d| !d.is_empty()),
+        binary: Some(candidate.binary.to_string_lossy().to_string()),
+        sapis,
+        source: Some(candidate.source.to_string()),
+    })
+}
+
+/// All distinct PHP installations, newest first.
+/// Symlinks (alternatives, Homebrew opt/, PATH entries) are collapsed onto
+/// the first candidate that resolves to the same binary.
+pub fn installed_versions() -> Vec<PhpVersion> {
+    let mut seen = HashSet::new();
+    let mut versions: Vec<PhpVersion> = candidates()
+        .into_iter()
+        .filter(|c| {
+            let key = std::fs::canonicalize(&c.binary).unwrap_or_else(|_| c.binary.clone());
+            seen.insert(key)
+        })
+        .filter_map(|c| inspect(&c))
+        .collect();
+
+    // Generic `php` entries (alternatives, PATH) may point at a build we already found
+    let mut seen_builds = HashSet::new();
+    versions.retain(|v| seen_builds.insert((v.version.clone(), v.install_path.clone())));
+
+    versions.sort_by_key(|v| std::cmp::Reverse((v.major, v.minor, v.patch)));
+    versions
+}
+
+/// Find an installation by full (`8.3.14`) or major.minor (`8.3`) version
+pub fn find_installed(version: &str) -> Option<PhpVersion> {
+    let versions = installed_versions();
+
+    versions
+        .iter()
+        .find(|v| v.version == version)
+        .or_else(|| versions.iter().find(|v| v.branch() == version))
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates `files` (empty) under a fresh directory
+    fn tree(files: &[&str]) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("discovery-test-{}", uuid::Uuid::new_v4()));
+        for file in files {
+            let path = root.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, "").unwrap();
+        }
+        root
+    }
+
+    #[test]
+    fn versioned_php_names() {
+        for (name, expected) in [
+            ("php8.3", true),
+            ("php7.4", true),
+            ("php10.12", true),
+            ("php", false),
+            ("php8", false),
+            ("php8.", false),
+            ("php.3", false),
+            ("php8.3.1", false),
+            ("php8.3-fpm", false),
+            ("php-fpm8.3", false),
+            ("phpize8.3", false),
+            ("python3.12", false),
+        ] {
+            assert_eq!(is_versioned_php_name(name), expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn install_path_skips_bin_directories() {
+        for (binary, expected) in [
+            ("/usr/bin/php8.3", "/usr"),
+            ("/usr/sbin/php-fpm8.3", "/usr"),
+            (
+                "/opt/homebrew/opt/php@8.2/bin/php",
+                "/opt/homebrew/opt/php@8.2",
+            ),
+            (
+                "/opt/remi/php83/root/usr/bin/php",
+                "/opt/remi/php83/root/usr",
+            ),
+            ("/opt/xampp/php/php.exe", "/opt/xampp/php"),
+        ] {
+            assert_eq!(
+                install_path(Path::new(binary)),
+                Path::new(expected),
+                "{}",
+                binary
+            );
+        }
+    }
+
+    #[test]
+    fn debian_has_an_ini_per_sapi_and_others_share_the_loaded_one() {
+        let root = tree(&["8.3/cli/php.ini", "8.3/fpm/php.ini", "8.3/apache2/php.ini"]);
+        let sapis: Vec<String> = ["cli", "fpm", "cgi", "apache2handler"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let ini = |sapi: &str| {
+            root.join(format!("8.3/{}/php.ini", sapi))
+                .to_string_lossy()
+                .to_string()
+        };
+
+        let debian = ini_paths(&root, &sapis, Some("/usr/lib/php.ini"), 8, 3);
+        let shared = ini_paths(&root, &sapis, Some("/usr/lib/php.ini"), 8, 2);
+        let unloaded = ini_paths(&root, &sapis, None, 8, 2);
+        let expected_debian = BTreeMap::from([
+            ("apache2handler".to_string(), ini("apache2")),
+            // No cgi/php.ini: it gets the file the CLI reported
+            ("cgi".to_string(), "/usr/lib/php.ini".to_string()),
+            ("cli".to_string(), ini("cli")),
+            ("fpm".to_string(), ini("fpm")),
+        ]);
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert_eq!(debian, expected_debian);
+        assert_eq!(shared.len(), 4);
+        assert!(shared.values().all(|path| path == "/usr/lib/php.ini"));
+        assert!(unloaded.is_empty());
+    }
+
+    #[test]
+    fn sibling_binaries_follow_debian_prefix_and_windows_layouts() {
+        let root = tree(&[
+            "usr/bin/php8.3",
+            "usr/sbin/php-fpm8.3",
+            "usr/bin/php-cgi8.3",
+            "opt/php/bin/php",
+            "opt/php/sbin/php-fpm",
+            "win/php.exe",
+            "win/php-cgi.exe",
+        ]);
+        let sibling = |binary: &str, sapi: &str| sibling_binary(&root.join(binary), sapi);
+
+        let found = [
+            sibling("usr/bin/php8.3", "php-fpm"),
+            sibling("usr/bin/php8.3", "php-cgi"),
+            sibling("usr/bin/php8.3", "phpdbg"),
+            sibling("opt/php/bin/php", "php-fpm"),
+            sibling("opt/php/bin/php", "php-cgi"),
+            sibling("win/php.exe", "php-cgi"),
+            sibling("win/php.exe", "php-fpm"),
+        ];
+        let expected = [
+            Some(root.join("usr/sbin/php-fpm8.3")),
+            Some(root.join("usr/bin/php-cgi8.3")),
+            None,
+            Some(root.join("opt/php/sbin/php-fpm")),
+            None,
+            Some(root.join("win/php-cgi.exe")),
+            None,
+        ];
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert_eq!(found, expected);
+    }
+}
@@ -0,0 +1,366 @@
+// ============================================
+// PHP Extension Inventory
+// ============================================
+// Lists what a PHP build really has: modules it loads (`php -m`, including
+// Zend extensions), shared extensions sitting disabled in `extension_dir`,
+// and each one's version. Loaded versions come from the build itself;
+// disabled ones are loaded for a moment with `-d extension=... --re`.
+//
+// Missing extensions are installed from the build's own channel: the distro
+// package on Debian/Ubuntu (apt) and Fedora/RHEL/Remi (dnf), PECL everywhere
+// else, then enabled like any other toggle.
+
+use crate::hosts_manager::{command_exists, run_command, toggle_php_extension};
+use crate::jobs::Jobs;
+use crate::php_discovery;
+use crate::php_ini::{extension_name, ZEND_EXTENSIONS};
+use crate::system::privileged_command;
+use crate::types::PhpVersion;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Prints extension_dir and the version of every loaded extension as JSON
+const VERSIONS_SCRIPT: &str = "$v = []; foreach (array_merge(get_loaded_extensions(), get_loaded_extensions(true)) as $e) { $v[$e] = phpversion($e) ?: null; } echo json_encode(['dir' => ini_get('extension_dir'), 'versions' => (object) $v]);";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExtensionInfo {
+    pub name: String,
+    pub enabled: bool,
+    /// Loaded, or available as a shared library in extension_dir
+    pub installed: bool,
+    /// Compiled into the binary; can't be toggled
+    pub builtin: bool,
+    /// Loaded with zend_extension= (OPcache, Xdebug)
+    pub zend: bool,
+    pub version: Option<String>,
+    /// Shared library, when it's one
+    pub path: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct VersionsOutput {
+    dir: String,
+    versions: BTreeMap<String, Option<String>>,
+}
+
+/// `php -m` names → ours: lowercase, and "Zend OPcache" is `opcache`
+fn module_name(name: &str) -> String {
+    let lower = name.trim().to_lowercase();
+    lower.strip_prefix("zend ").unwrap_or(&lower).to_string()
+}
+
+/// Loaded modules from `php -m`, split into regular and Zend extensions
+fn loaded_modules(binary: &str) -> (BTreeSet<String>, BTreeSet<String>) {
+    let mut modules = BTreeSet::new();
+    let mut zend = BTreeSet::new();
+    let mut in_zend = false;
+
+    for line in run_command(binary, &["-m"]).unwrap_or_default().lines() {
+        let line = line.trim();
+        match line {
+            "" => {}
+            "[PHP Modules]" => in_zend = false,
+            "[Zend Modules]" => in_zend = true,
+            _ if in_zend => {
+                zend.insert(module_name(line));
+            }
+            _ => {
+                modules.insert(module_name(line));
+            }
+        }
+    }
+
+    (modules, zend)
+}
+
+/// Shared libraries in extension_dir, by extension name
+fn shared_libraries(dir: &Path) -> BTreeMap<String, PathBuf> {
+    let suffix = if cfg!(target_os = "windows") {
+        ".dll"
+    } else {
+        ".so"
+    };
+
+    std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|e| e.path())
+                .filter(|p| p.to_string_lossy().ends_with(suffix))
+                .map(|p| {
+                    (
+                        extension_name(&p.file_name().unwrap_or_default().to_string_lossy()),
+                        p,
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Version of a disabled shared extension, from its `--re` reflection header:
+/// `Extension [ <persistent> extension #42 redis version 6.0.2 ] {`
+fn disabled_version(binary: &str, name: &str, library: &Path, zend: bool) -> Option<String> {
+    let directive = format!(
+        "{}={}",
+        if zend { "zend_extension" } else { "extension" },
+        library.display()
+    );
+    let output = run_command(binary, &["-d", &directive, "--re", name])?;
+
+    output
+        .lines()
+        .next()?
+        .split_once(" version ")
+        .map(|(_, rest)| rest.trim_end_matches(['{', ' ', ']']).trim().to_string())
+        .filter(|v| !v.is_empty() && v != "<no_version>")
+}
+
+/// Every extension a build loads or could load, sorted by name
+pub fn inventory(php: &PhpVersion) -> Result<Vec<ExtensionInfo>, String> {
+    let binary = php
+        .binary
+        .as_deref()
+        .ok_or_else(|| format!("PHP {} has no CLI binary", php.version))?;
+
+    let (modules, zend_modules) = loaded_modules(binary);
+    let probe: VersionsOutput = run_command(binary, &["-r", VERSIONS_SCRIPT])
+        .and_then(|output| serde_json::from_str(output.trim()).ok())
+        .ok_or_else(|| format!("Failed to inspect PHP {}", php.version))?;
+
+    let versions: BTreeMap<String, String> = probe
+        .versions
+        .into_iter()
+        .filter_map(|(name, version)| Some((module_name(&name), version?)))
+        .collect();
+    let libraries = shared_libraries(Path::new(&probe.dir));
+
+    let mut extensions: BTreeMap<String, ExtensionInfo> = BTreeMap::new();
+
+    for name in modules.iter().chain(zend_modules.iter()) {
+        let zend = zend_modules.contains(name);
+        extensions.insert(
+            name.clone(),
+            ExtensionInfo {
+                name: name.clone(),
+                enabled: true,
+                installed: true,
+                builtin: !libraries.contains_key(name),
+                zend,
+                version: versions.get(name).cloned(),
+                path: libraries.get(name).map(|p| p.to_string_lossy().to_string()),
+            },
+        );
+    }
+
+    for (name, library) in &libraries {
+        if extensions.contains_key(name) {
+            continue;
+        }
+        let zend = ZEND_EXTENSIONS.contains(&name.as_str());
+        extensions.insert(
+            name.clone(),
+            ExtensionInfo {
+                name: name.clone(),
+                enabled: false,
+                installed: true,
+                builtin: false,
+                zend,
+                version: disabled_version(binary, name, library, zend),
+                path: Some(library.to_string_lossy().to_string()),
+            },
+        );
+    }
+
+    Ok(extensions.into_values().collect())
+}
+
+/// Extensions of an installed PHP version, loaded or available
+#[tauri::command]
+pub async fn get_php_extensions(version: String) -> Result<Vec<ExtensionInfo>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let php = php_discovery::find_installed(&version)
+            .ok_or_else(|| format!("PHP {} not found", version))?;
+        inventory(&php)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// ============================================
+// Installing Extensions
+// ============================================
+
+/// Debian package suffix of an extension (`php8.3-<suffix>`)
+fn apt_package(name: &str) -> &str {
+    match name {
+        "mysqli" | "mysqlnd" | "pdo_mysql" => "mysql",
+        "pdo_pgsql" => "pgsql",
+        "pdo_sqlite" => "sqlite3",
+        "dom" | "simplexml" | "xmlreader" | "xmlwriter" | "xsl" => "xml",
+        _ => name,
+    }
+}
+
+/// RPM package suffix of an extension (`php-<suffix>`, `php83-php-<suffix>`)
+fn rpm_package(name: &str) -> &str {
+    match name {
+        "mysqli" | "pdo_mysql" => "mysqlnd",
+        "pdo_pgsql" => "pgsql",
+        "pdo_sqlite" | "sqlite3" => "pdo",
+        "dom" | "simplexml" | "xmlreader" | "xmlwriter" | "xsl" => "xml",
+        _ => name,
+    }
+}
+
+/// Command installing `name` for `php`, and what it installs
+fn install_command(php: &PhpVersion, name: &str) -> Result<(Command, String), String> {
+    if cfg!(target_os = "windows") {
+        return Err(format!(
+            "Installing extensions isn't supported on Windows; copy php_{}.dll from PECL into the ext directory",
+            name
+        ));
+    }
+
+    let binary = php
+        .binary
+        .as_deref()
+        .ok_or_else(|| format!("PHP {} has no CLI binary", php.version))?;
+    let branch = php.branch();
+    let source = php.source.as_deref().unwrap_or_default();
+
+    if cfg!(target_os = "linux") && matches!(source, "debian" | "path") && command_exists("apt-get")
+    {
+        let package = format!("php{}-{}", branch, apt_package(name));
+        if run_command("apt-cache", &["show", "--no-all-versions", &package]).is_some() {
+            return Ok((
+                privileged_command(&["apt-get", "install", "-y", &package]),
+                package,
+            ));
+        }
+    }
+
+    if cfg!(target_os = "linux") && matches!(source, "remi" | "path") && command_exists("dnf") {
+        let prefix = if source == "remi" {
+            format!("php{}{}-php", php.major, php.minor)
+        } else {
+            "php".to_string()
+        };
+        let candidates = [
+            format!("{}-pecl-{}", prefix, name),
+            format!("{}-{}", prefix, rpm_package(name)),
+        ];
+        if let Some(package) = candidates
+            .into_iter()
+            .find(|p| run_command("dnf", &["-q", "list", "--available", p]).is_some())
+        {
+            return Ok((
+                privileged_command(&["dnf", "install", "-y", &package]),
+                package,
+            ));
+        }
+    }
+
+    // PECL builds against the matching phpize/php-config, so the version's own
+    // dev tools have to be there
+    let binary = Path::new(binary);
+    if php_discovery::sibling_binary(binary, "phpize").is_none() {
+        return Err(format!(
+            "{} isn't packaged for PHP {} and phpize is missing; install the PHP {} development package to build it with PECL",
+            name, php.version, branch
+        ));
+    }
+    let pecl = php_discovery::sibling_binary(binary, "pecl")
+        .map(|p| p.to_string_lossy().to_string())
+        .or_else(|| command_exists("pecl").then(|| "pecl".to_string()))
+        .ok_or_else(|| format!("PECL not found for PHP {}", php.version))?;
+
+    // Debian names the tools phpize8.3/php-config8.3; PECL finds them by suffix
+    let suffix = binary
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .trim_start_matches("php")
+        .to_string();
+    let args = [
+        "-d".to_string(),
+        format!("php_suffix={}", suffix),
+        "-d".to_string(),
+        format!("php_bin={}", binary.display()),
+        "install".to_string(),
+        name.to_string(),
+    ];
+
+    // User-owned installs build in place; system ones write to a root-owned extension_dir
+    let cmd = if matches!(source, "homebrew" | "phpenv" | "phpbrew" | "managed") {
+        let mut cmd = Command::new(&pecl);
+        cmd.args(&args);
+        cmd
+    } else {
+        let mut elevated = vec![pecl.as_str()];
+        elevated.extend(args.iter().map(String::as_str));
+        privileged_command(&elevated)
+    };
+
+    Ok((cmd, format!("pecl/{}", name)))
+}
+
+/// Start installing a missing extension as a background job; returns the job ID.
+/// When the package is in place the extension is enabled for the version.
+#[tauri::command]
+pub async fn install_php_extension(
+    version: String,
+    extension: String,
+    window: tauri::Window,
+    jobs: tauri::State<'_, Jobs>,
+) -> Result<String, String> {
+    let php = php_discovery::find_installed(&version)
+        .ok_or_else(|| format!("PHP {} not found", version))?;
+    let name = extension_name(&extension);
+
+    let (php, inventory_before) = tauri::async_runtime::spawn_blocking(move || {
+        let extensions = inventory(&php);
+        (php, extensions)
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    if let Some(ext) = inventory_before?.iter().find(|e| e.name == name) {
+        return Err(format!(
+            "{} is already installed for PHP {}{}",
+            name,
+            php.version,
+            if ext.enabled {
+                ""
+            } else {
+                "; enable it instead"
+            }
+        ));
+    }
+
+    let (cmd, package) = install_command(&php, &name)?;
+
+    jobs.spawn_then(
+        &window,
+        cmd,
+        &format!("Installing {} for PHP {}", package, php.version),
+        move || {
+            let php = php_discovery::find_installed(&version)
+                .ok_or_else(|| format!("PHP {} not found", version))?;
+
+            // Distro packages usually enable themselves through conf.d
+            match inventory(&php)?.into_iter().find(|e| e.name == name) {
+                Some(ext) if ext.enabled => Ok(()),
+                Some(_) => {
+                    tauri::async_runtime::block_on(toggle_php_extension(version, name, true))
+                }
+                None => Err(format!(
+                    "{} was installed but PHP {} doesn't see it",
+                    package, php.version
+                )),
+            }
+        },
+    )
+}
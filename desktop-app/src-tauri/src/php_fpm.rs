@@ -0,0 +1,517 @@
+// ============================================
+// PHP-FPM Pools
+// ============================================
+// Locates the pool directory each PHP-FPM master includes (`pool.d` on
+// Debian, `php-fpm.d` everywhere else) and reloads the master that owns it:
+// USR2 to the PID in its `pid` file when there is one, so other versions keep
+// running, or its service otherwise.
+// Pools are health-checked over FastCGI: `ping.path` when the pool defines
+// one, a bare connection to its address otherwise, plus `pm.status_path` for
+// the numbers.
+
+use crate::fastcgi::{self, Address};
+use crate::php_discovery;
+use crate::system::execute_with_privileges;
+use crate::types::PhpVersion;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Status polling asks every few seconds; discovery runs each PHP binary
+const POOLS_CACHE_TTL: Duration = Duration::from_secs(30);
+
+const HEALTH_TIMEOUT: Duration = Duration::from_secs(1);
+
+static POOLS_CACHE: Mutex<Option<(Instant, Vec<Pool>)>> = Mutex::new(None);
+
+/// A pool defined in the pool directory
+#[derive(Debug, Clone, Serialize)]
+pub struct Pool {
+    pub name: String,
+    /// Branch of the master running it (`8.3`)
+    pub php_version: String,
+    /// `listen` as configured, with `$pool` expanded
+    pub listen: String,
+    pub file: String,
+    pub ping_path: Option<String>,
+    /// Expected ping answer, `pong` unless `ping.response` says otherwise
+    pub ping_response: Option<String>,
+    pub status_path: Option<String>,
+    /// `static`, `dynamic` or `ondemand`
+    pub pm: Option<String>,
+    pub max_children: Option<u32>,
+    pub start_servers: Option<u32>,
+    pub min_spare_servers: Option<u32>,
+    pub max_spare_servers: Option<u32>,
+    /// Workers run as this user when the master runs as root
+    pub user: Option<String>,
+}
+
+/// Numbers from `pm.status_path?json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PoolStats {
+    #[serde(alias = "process manager")]
+    pub process_manager: String,
+    #[serde(alias = "start since")]
+    pub start_since: u64,
+    #[serde(alias = "accepted conn")]
+    pub accepted_conn: u64,
+    #[serde(alias = "listen queue")]
+    pub listen_queue: u64,
+    #[serde(alias = "idle processes")]
+    pub idle_processes: u64,
+    #[serde(alias = "active processes")]
+    pub active_processes: u64,
+    #[serde(alias = "total processes")]
+    pub total_processes: u64,
+    #[serde(alias = "max children reached")]
+    pub max_children_reached: u64,
+    #[serde(alias = "slow requests", default)]
+    pub slow_requests: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PoolHealth {
+    pub pool: String,
+    pub php_version: String,
+    pub listen: String,
+    /// The pool accepts connections, and answered its ping.path when it has one
+    pub answering: bool,
+    /// The ping answer matched; None when the pool has no ping.path
+    pub ping: Option<bool>,
+    pub stats: Option<PoolStats>,
+    pub error: Option<String>,
+}
+
+/// An installed PHP version that has PHP-FPM, by full or major.minor version
+pub fn find(version: &str) -> Result<PhpVersion, String> {
+    let php = php_discovery::find_installed(version)
+        .ok_or_else(|| format!("PHP {} is not installed", version))?;
+    if !php.sapis.iter().any(|s| s == "fpm") {
+        return Err(format!("PHP {} has no PHP-FPM installed", php.version));
+    }
+    Ok(php)
+}
+
+/// Directory the FPM master of `php` includes pool files from.
+/// The pool directory sits next to the FPM (or shared) php.ini:
+///   /etc/php/8.3/fpm/php.ini          → /etc/php/8.3/fpm/pool.d
+///   /opt/homebrew/etc/php/8.3/php.ini → /opt/homebrew/etc/php/8.3/php-fpm.d
+///   /etc/opt/remi/php83/php.ini       → /etc/opt/remi/php83/php-fpm.d
+///   /etc/php.ini                      → /etc/php-fpm.d
+pub fn pool_dir(php: &PhpVersion) -> Option<PathBuf> {
+    let ini = php
+        .ini_paths
+        .get("fpm")
+        .or_else(|| php.ini_paths.get("cli"))?;
+    let ini_dir = Path::new(ini).parent()?;
+
+    ["pool.d", "php-fpm.d"]
+        .iter()
+        .map(|name| ini_dir.join(name))
+        .find(|dir| dir.is_dir())
+}
+
+/// Pools defined in the pool directory, by file name then order in the file
+pub fn pools(php: &PhpVersion) -> Vec<Pool> {
+    let Some(dir) = pool_dir(php) else {
+        return Vec::new();
+    };
+
+    let mut files: Vec<PathBuf> = std::fs::read_dir(&dir)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|ext| ext == "conf"))
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+
+    let mut pools = Vec::new();
+    for file in files {
+        let Ok(content) = std::fs::read_to_string(&file) else {
+            continue;
+        };
+        let mut current: Option<Pool> = None;
+        let mut finish = |current: Option<Pool>| {
+            if let Some(pool) = current.filter(|p| !p.listen.is_empty()) {
+                let expand = |v: &String| v.replace("$pool", &pool.name);
+                pools.push(Pool {
+                    listen: expand(&pool.listen),
+                    ping_path: pool.ping_path.as_ref().map(expand),
+                    status_path: pool.status_path.as_ref().map(expand),
+                    ..pool
+                });
+            }
+        };
+
+        for line in content.lines() {
+            let line = line.trim();
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                finish(current.take());
+                current = (name != "global").then(|| Pool {
+                    name: name.to_string(),
+                    php_version: php.branch(),
+                    listen: String::new(),
+                    file: file.to_string_lossy().to_string(),
+                    ping_path: None,
+                    ping_response: None,
+                    status_path: None,
+                    pm: None,
+                    max_children: None,
+                    start_servers: None,
+                    min_spare_servers: None,
+                    max_spare_servers: None,
+                    user: None,
+                });
+            } else if let (Some(pool), Some((key, value))) =
+                (current.as_mut(), line.split_once('='))
+            {
+                let value = value.split(';').next().unwrap_or_default().trim();
+                let value = value.trim_matches('"').to_string();
+                match key.trim() {
+                    "listen" => pool.listen = value,
+                    "ping.path" => pool.ping_path = Some(value),
+                    "ping.response" => pool.ping_response = Some(value),
+                    "pm.status_path" => pool.status_path = Some(value),
+                    "pm" => pool.pm = Some(value),
+                    "pm.max_children" => pool.max_children = value.parse().ok(),
+                    "pm.start_servers" => pool.start_servers = value.parse().ok(),
+                    "pm.min_spare_servers" => pool.min_spare_servers = value.parse().ok(),
+                    "pm.max_spare_servers" => pool.max_spare_servers = value.parse().ok(),
+                    "user" => pool.user = Some(value),
+                    _ => {}
+                }
+            }
+        }
+        finish(current);
+    }
+
+    pools
+}
+
+/// Master config of `php`, next to its pool directory
+fn master_config(php: &PhpVersion) -> Option<PathBuf> {
+    let ini = php
+        .ini_paths
+        .get("fpm")
+        .or_else(|| php.ini_paths.get("cli"))?;
+    Some(Path::new(ini).parent()?.join("php-fpm.conf")).filter(|p| p.is_file())
+}
+
+/// PID of the running master, from the `pid` file its [global] section names.
+/// Relative paths depend on the build prefix, so only absolute ones are used.
+fn master_pid(php: &PhpVersion) -> Option<u32> {
+    let config = std::fs::read_to_string(master_config(php)?).ok()?;
+    let pid_file = config
+        .lines()
+        .map(str::trim)
+        .take_while(|line| !line.starts_with('[') || *line == "[global]")
+        .filter_map(|line| line.split_once('='))
+        .find(|(key, _)| key.trim() == "pid")
+        .map(|(_, value)| {
+            value
+                .split(';')
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .trim_matches('"')
+                .to_string()
+        })
+        .filter(|path| path.starts_with('/'))?;
+
+    let pid: u32 = std::fs::read_to_string(pid_file)
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+
+    // A stale file can point at a PID the system has reused
+    let command = Command::new("ps")
+        .args(["-p", &pid.to_string(), "-o", "comm="])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&command.stdout)
+        .contains("php-fpm")
+        .then_some(pid)
+}
+
+/// Reload the FPM master serving `php` so pool changes take effect
+pub async fn reload(php: &PhpVersion) -> Result<(), String> {
+    if let Some(pid) = master_pid(php) {
+        // USR2 re-reads the config and replaces the workers gracefully
+        let pid = pid.to_string();
+        let signalled = Command::new("kill")
+            .args(["-USR2", &pid])
+            .status()
+            .is_ok_and(|status| status.success());
+        if signalled {
+            return Ok(());
+        }
+        // Masters started as root need root to be signalled
+        return execute_with_privileges("kill".to_string(), vec!["-USR2".to_string(), pid])
+            .await
+            .map(|_| ());
+    }
+
+    reload_service(php).await
+}
+
+/// Reload through the service manager when the master's PID isn't known
+async fn reload_service(php: &PhpVersion) -> Result<(), String> {
+    let branch = php.branch();
+
+    match php.source.as_deref() {
+        Some("homebrew") => {
+            // Discovery keeps the opt/ symlink: opt/php/bin/php or opt/php@8.3/bin/php
+            let formula = php
+                .binary
+                .as_deref()
+                .and_then(|b| Path::new(b).parent()?.parent()?.file_name())
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| format!("php@{}", branch));
+
+            let output = Command::new("brew")
+                .args(["services", "restart", &formula])
+                .output()
+                .map_err(|e| format!("Failed to execute brew: {}", e))?;
+
+            if output.status.success() {
+                Ok(())
+            } else {
+                Err(String::from_utf8_lossy(&output.stderr).to_string())
+            }
+        }
+        Some("debian") | Some("remi") | Some("path") if cfg!(target_os = "linux") => {
+            let unit = match php.source.as_deref() {
+                Some("debian") => format!("php{}-fpm", branch),
+                Some("remi") => format!("php{}{}-php-fpm", php.major, php.minor),
+                _ => "php-fpm".to_string(),
+            };
+
+            execute_with_privileges("systemctl".to_string(), vec!["reload".to_string(), unit])
+                .await
+                .map(|_| ())
+        }
+        _ => Err(format!(
+            "Don't know how to reload PHP-FPM for PHP {}; restart it manually",
+            php.version
+        )),
+    }
+}
+
+/// Drop cached pools after the pool files changed
+pub fn forget_pools() {
+    if let Ok(mut cache) = POOLS_CACHE.lock() {
+        *cache = None;
+    }
+}
+
+/// Pools of every installed PHP-FPM, cached for a short while
+pub fn all_pools() -> Vec<Pool> {
+    if let Ok(cache) = POOLS_CACHE.lock() {
+        if let Some((at, pools)) = cache.as_ref() {
+            if at.elapsed() < POOLS_CACHE_TTL {
+                return pools.clone();
+            }
+        }
+    }
+
+    let pools: Vec<Pool> = php_discovery::installed_versions()
+        .iter()
+        .filter(|php| php.sapis.iter().any(|s| s == "fpm"))
+        .flat_map(pools)
+        .collect();
+
+    if let Ok(mut cache) = POOLS_CACHE.lock() {
+        *cache = Some((Instant::now(), pools.clone()));
+    }
+    pools
+}
+
+/// Ask a pool whether it's serving: ping (or any answer), then its status page
+pub async fn check(pool: &Pool) -> PoolHealth {
+    let mut health = PoolHealth {
+        pool: pool.name.clone(),
+        php_version: pool.php_version.clone(),
+        listen: pool.listen.clone(),
+        answering: false,
+        ping: None,
+        stats: None,
+        error: None,
+    };
+    let address = Address::parse(&pool.listen);
+
+    // Without a ping.path any script we asked for would be missing, and the
+    // pool would log "Primary script unknown" on every poll
+    let answered = match pool.ping_path.as_deref() {
+        Some(ping_path) => {
+            let params = fastcgi::cgi_params(ping_path, ping_path, "");
+            fastcgi::request(&address, &params, &[], HEALTH_TIMEOUT)
+                .await
+                .map(|response| {
+                    let expected = pool.ping_response.as_deref().unwrap_or("pong");
+                    health.ping = Some(String::from_utf8_lossy(&response.body).trim() == expected);
+                })
+        }
+        None => fastcgi::connect(&address, HEALTH_TIMEOUT).await,
+    };
+    match answered {
+        Ok(()) => health.answering = true,
+        Err(e) => {
+            health.error = Some(e);
+            return health;
+        }
+    }
+
+    if let Some(status_path) = pool.status_path.as_deref() {
+        let params = fastcgi::cgi_params(status_path, status_path, "json");
+        match fastcgi::request(&address, &params, &[], HEALTH_TIMEOUT).await {
+            Ok(response) => match serde_json::from_slice(&response.body) {
+                Ok(stats) => health.stats = Some(stats),
+                Err(_) => {
+                    health.error = Some(format!(
+                        "{} answered {} instead of its status page",
+                        status_path, response.status
+                    ))
+                }
+            },
+            Err(e) => health.error = Some(e),
+        }
+    }
+
+    health
+}
+
+/// Health of every pool, checked concurrently
+pub async fn check_all() -> Vec<PoolHealth> {
+    let pools = tauri::async_runtime::spawn_blocking(all_pools)
+        .await
+        .unwrap_or_default();
+
+    let mut checks = tokio::task::JoinSet::new();
+    for (index, pool) in pools.into_iter().enumerate() {
+        checks.spawn(async move { (index, check(&pool).await) });
+    }
+
+    let mut results: Vec<(usize, PoolHealth)> = checks.join_all().await;
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, health)| health).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(listen: &str, ping_path: Option<&str>, status_path: Option<&str>) -> Pool {
+        Pool {
+            name: "test".to_string(),
+            php_version: String::new(),
+            listen: listen.to_string(),
+            file: String::new(),
+            ping_path: ping_path.map(String::from),
+            ping_response: None,
+            status_path: status_path.map(String::from),
+            pm: Some("static".to_string()),
+            max_children: Some(1),
+            start_servers: None,
+            min_spare_servers: None,
+            max_spare_servers: None,
+            user: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn pools_without_ping_path_are_checked_by_connecting() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let listen = listener.local_addr().unwrap().to_string();
+
+        // Anything sent would fail the test: a pool would log it as a missing script
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 64];
+            tokio::io::AsyncReadExt::read(&mut socket, &mut buf)
+                .await
+                .unwrap()
+        });
+
+        let health = check(&pool(&listen, None, None)).await;
+        assert!(health.answering, "{:?}", health.error);
+        assert_eq!(health.ping, None);
+        assert_eq!(health.error, None);
+        assert_eq!(server.await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn unreachable_pools_report_the_error() {
+        // Bind then drop, so nothing listens on the port
+        let listen = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .to_string();
+
+        let health = check(&pool(&listen, None, Some("/status"))).await;
+        assert!(!health.answering);
+        assert!(health.error.unwrap().starts_with("Failed to connect"));
+        assert!(health.stats.is_none());
+    }
+
+    /// Runs a throwaway pool with the php-fpm binary named by `PHP_FPM`
+    /// (default `php-fpm`): `PHP_FPM=php-fpm8.3 cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "needs a php-fpm binary"]
+    async fn checks_a_real_pool() {
+        let dir = std::env::temp_dir().join(format!("php-fpm-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let socket = dir.join("fpm.sock");
+        let config = dir.join("php-fpm.conf");
+        std::fs::write(
+            &config,
+            format!(
+                "[global]\nerror_log = {log}\ndaemonize = no\n\n\
+                 [test]\nlisten = {socket}\npm = static\npm.max_children = 1\n\
+                 ping.path = /ping\npm.status_path = /status\n",
+                log = dir.join("fpm.log").display(),
+                socket = socket.display(),
+            ),
+        )
+        .unwrap();
+
+        let binary = std::env::var("PHP_FPM").unwrap_or_else(|_| "php-fpm".to_string());
+        // -R lets the pool run as root inside containers
+        let mut fpm = Command::new(binary)
+            .args(["-n", "-R", "-y"])
+            .arg(&config)
+            .spawn()
+            .expect("failed to start php-fpm");
+
+        let started = Instant::now();
+        while !socket.exists() && started.elapsed() < Duration::from_secs(10) {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        let listen = socket.to_string_lossy().to_string();
+
+        let health = check(&pool(&listen, Some("/ping"), Some("/status"))).await;
+        let bare = check(&pool(&listen, None, None)).await;
+
+        let _ = fpm.kill();
+        let _ = fpm.wait();
+        let log = std::fs::read_to_string(dir.join("fpm.log")).unwrap_or_default();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert!(health.answering, "{:?}\n{}", health.error, log);
+        assert_eq!(health.ping, Some(true));
+        let stats = health.stats.expect("status page");
+        assert_eq!(stats.process_manager, "static");
+        assert_eq!(stats.total_processes, 1);
+
+        assert!(bare.answering, "{:?}", bare.error);
+        assert_eq!(bare.ping, None);
+        assert!(!log.contains("Primary script unknown"), "{}", log);
+    }
+}
@@ -0,0 +1,955 @@
+// ============================================
+// php.ini Editor
+// ============================================
+// Parses php.ini into lines that round-trip byte for byte, so edits only
+// touch the directives they change. Comments, sections and ordering are
+// kept, commented defaults (`;date.timezone =`) are recognized, and the
+// scoped `[PATH=...]` / `[HOST=...]` sections are kept apart from the global
+// directives. Every batch of edits is checked with the version's own PHP
+// binary before the file is replaced.
+
+use crate::hosts_manager::run_command;
+use crate::php_discovery;
+use crate::system::write_file_privileged;
+use crate::types::PhpVersion;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Extensions loaded with `zend_extension=` rather than `extension=`
+pub const ZEND_EXTENSIONS: [&str; 3] = ["opcache", "xdebug", "ioncube_loader"];
+
+#[derive(Debug, Clone)]
+enum Line {
+    /// Blank lines, prose comments and anything we don't understand, kept verbatim
+    Other(String),
+    Section {
+        raw: String,
+        name: String,
+    },
+    Entry {
+        raw: String,
+        key: String,
+        value: String,
+        /// Inline `; comment` after the value
+        comment: Option<String>,
+        /// A commented-out default such as `;memory_limit = 128M`
+        commented: bool,
+    },
+}
+
+impl Line {
+    fn raw(&self) -> &str {
+        match self {
+            Line::Other(raw) | Line::Section { raw, .. } | Line::Entry { raw, .. } => raw,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IniEntry {
+    pub key: String,
+    pub value: String,
+    /// Scoped section (`PATH=/var/www`, `HOST=example.test`); None for global directives
+    pub section: Option<String>,
+    /// 1-based line number
+    pub line: usize,
+    pub commented: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PhpIniFile {
+    pub path: String,
+    pub sections: Vec<String>,
+    pub entries: Vec<IniEntry>,
+}
+
+/// One edit in a batch; `value: None` comments the directive out
+#[derive(Debug, Clone, Deserialize)]
+pub struct IniChange {
+    pub key: String,
+    pub value: Option<String>,
+    #[serde(default)]
+    pub section: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IniValidation {
+    pub path: String,
+    /// Value PHP reports for each changed directive, None when PHP doesn't know it
+    pub effective: BTreeMap<String, Option<String>>,
+    pub warnings: Vec<String>,
+}
+
+fn is_key_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "_.-[]".contains(c)
+}
+
+fn valid_key(key: &str) -> bool {
+    !key.is_empty() && key.chars().all(is_key_char)
+}
+
+/// `[PATH=/srv]` and `[HOST=example.test]` apply only to matching requests;
+/// every other section is just a label inside the global scope
+fn is_scoped(section: &str) -> bool {
+    let upper = section.to_ascii_uppercase();
+    upper.starts_with("PATH=") || upper.starts_with("HOST=")
+}
+
+/// Split `value ; comment`, ignoring semicolons inside double quotes
+fn split_comment(rest: &str) -> (String, Option<String>) {
+    let mut in_quotes = false;
+    for (i, c) in rest.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ';' if !in_quotes => {
+                return (
+                    rest[..i].trim().to_string(),
+                    Some(rest[i..].trim_end().to_string()),
+                )
+            }
+            _ => {}
+        }
+    }
+    (rest.trim().to_string(), None)
+}
+
+fn parse_line(raw: &str) -> Line {
+    let trimmed = raw.trim();
+
+    if let Some(name) = trimmed
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+    {
+        return Line::Section {
+            raw: raw.to_string(),
+            name: name.trim().to_string(),
+        };
+    }
+
+    // Commented defaults start right at the key (`;extension=bz2`); prose
+    // comments have a space after the semicolon
+    let (body, commented) = match trimmed.strip_prefix(';') {
+        Some(rest) if rest.starts_with(is_key_char) => (rest, true),
+        Some(_) => return Line::Other(raw.to_string()),
+        None => (trimmed, false),
+    };
+
+    match body.split_once('=') {
+        Some((key, rest)) if valid_key(key.trim()) => {
+            let (value, comment) = split_comment(rest);
+            Line::Entry {
+                raw: raw.to_string(),
+                key: key.trim().to_string(),
+                value,
+                comment,
+                commented,
+            }
+        }
+        _ => Line::Other(raw.to_string()),
+    }
+}
+
+/// `"php_redis.dll"`, `/usr/lib/php/20230831/redis.so` and `redis` all name `redis`
+pub fn extension_name(value: &str) -> String {
+    let value = value.trim().trim_matches('"');
+    let file = value.rsplit(['/', '\\']).next().unwrap_or(value);
+    file.trim_start_matches("php_")
+        .trim_end_matches(".so")
+        .trim_end_matches(".dll")
+        .to_lowercase()
+}
+
+fn entry_line(key: &str, value: &str, comment: Option<&str>, commented: bool) -> Line {
+    let raw = format!(
+        "{}{} = {}{}",
+        if commented { ";" } else { "" },
+        key,
+        value,
+        comment.map(|c| format!(" {}", c)).unwrap_or_default()
+    );
+    Line::Entry {
+        raw,
+        key: key.to_string(),
+        value: value.to_string(),
+        comment: comment.map(String::from),
+        commented,
+    }
+}
+
+pub struct IniDocument {
+    lines: Vec<Line>,
+    newline: &'static str,
+    trailing_newline: bool,
+}
+
+impl IniDocument {
+    pub fn parse(content: &str) -> Self {
+        Self {
+            lines: content.lines().map(parse_line).collect(),
+            newline: if content.contains("\r\n") {
+                "\r\n"
+            } else {
+                "\n"
+            },
+            trailing_newline: content.is_empty() || content.ends_with('\n'),
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = self
+            .lines
+            .iter()
+            .map(Line::raw)
+            .collect::<Vec<_>>()
+            .join(self.newline);
+        if self.trailing_newline && !self.lines.is_empty() {
+            out.push_str(self.newline);
+        }
+        out
+    }
+
+    /// Scoped section each line belongs to
+    fn scopes(&self) -> Vec<Option<String>> {
+        let mut current = None;
+        self.lines
+            .iter()
+            .map(|line| {
+                if let Line::Section { name, .. } = line {
+                    current = is_scoped(name).then(|| name.clone());
+                }
+                current.clone()
+            })
+            .collect()
+    }
+
+    pub fn entries(&self) -> Vec<IniEntry> {
+        self.lines
+            .iter()
+            .zip(self.scopes())
+            .enumerate()
+            .filter_map(|(i, (line, section))| match line {
+                Line::Entry {
+                    key,
+                    value,
+                    commented,
+                    ..
+                } => Some(IniEntry {
+                    key: key.clone(),
+                    value: value.clone(),
+                    section,
+                    line: i + 1,
+                    commented: *commented,
+                }),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn sections(&self) -> Vec<String> {
+        self.lines
+            .iter()
+            .filter_map(|line| match line {
+                Line::Section { name, .. } => Some(name.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Active global directives; later occurrences win, as in PHP
+    pub fn values(&self) -> BTreeMap<String, String> {
+        self.entries()
+            .into_iter()
+            .filter(|e| !e.commented && e.section.is_none())
+            .map(|e| (e.key, e.value))
+            .collect()
+    }
+
+    /// Index of the last matching entry in `scope`
+    fn find(&self, key: &str, scope: Option<&str>, commented: bool) -> Option<usize> {
+        let scopes = self.scopes();
+        self.lines.iter().enumerate().rposition(|(i, line)| {
+            matches!(line, Line::Entry { key: k, commented: c, .. } if k == key && *c == commented)
+                && scopes[i].as_deref() == scope
+        })
+    }
+
+    fn section_index(&self, name: &str) -> Option<usize> {
+        self.lines.iter().position(
+            |line| matches!(line, Line::Section { name: n, .. } if n.eq_ignore_ascii_case(name)),
+        )
+    }
+
+    /// Where a section's content ends, before the blank lines that separate it from the next one
+    fn section_end(&self, start: usize) -> usize {
+        let next = self.lines[start + 1..]
+            .iter()
+            .position(|line| matches!(line, Line::Section { .. }))
+            .map(|offset| start + 1 + offset)
+            .unwrap_or(self.lines.len());
+
+        let mut end = next;
+        while end > start + 1 && self.lines[end - 1].raw().trim().is_empty() {
+            end -= 1;
+        }
+        end
+    }
+
+    /// Insertion point for a new global directive: its extension's section
+    /// (`date.timezone` → `[Date]`), else `[PHP]`, else before the first scoped section
+    fn global_insert_index(&self, key: &str) -> usize {
+        let prefix = key.split('.').next().unwrap_or(key);
+        let section = (key.contains('.'))
+            .then(|| self.section_index(prefix))
+            .flatten()
+            .or_else(|| self.section_index("PHP"));
+
+        match section {
+            Some(start) => self.section_end(start),
+            None => self
+                .lines
+                .iter()
+                .position(|line| matches!(line, Line::Section { name, .. } if is_scoped(name)))
+                .unwrap_or(self.lines.len()),
+        }
+    }
+
+    pub fn set(&mut self, key: &str, value: &str, scope: Option<&str>) {
+        if let Some(i) = self.find(key, scope, false) {
+            if let Line::Entry { comment, .. } = &self.lines[i] {
+                self.lines[i] = entry_line(key, value, comment.as_deref(), false);
+            }
+            return;
+        }
+
+        // Keep the documented default and put the active value right below it
+        let index = match (self.find(key, scope, true), scope) {
+            (Some(i), _) => i + 1,
+            (None, None) => self.global_insert_index(key),
+            (None, Some(name)) => match self.section_index(name) {
+                Some(start) => self.section_end(start),
+                None => {
+                    self.lines.push(Line::Other(String::new()));
+                    self.lines.push(Line::Section {
+                        raw: format!("[{}]", name),
+                        name: name.to_string(),
+                    });
+                    self.lines.len()
+                }
+            },
+        };
+
+        self.lines
+            .insert(index, entry_line(key, value, None, false));
+    }
+
+    /// Comment a line out or back in, leaving the rest of it untouched
+    fn set_commented(&mut self, i: usize, commented: bool) {
+        if let Line::Entry {
+            raw,
+            commented: current,
+            ..
+        } = &mut self.lines[i]
+        {
+            if *current != commented {
+                *raw = if commented {
+                    format!(";{}", raw.trim_start())
+                } else {
+                    raw.trim_start().trim_start_matches(';').to_string()
+                };
+                *current = commented;
+            }
+        }
+    }
+
+    /// Comment out every active occurrence of `key` in `scope`
+    pub fn unset(&mut self, key: &str, scope: Option<&str>) {
+        while let Some(i) = self.find(key, scope, false) {
+            self.set_commented(i, true);
+        }
+    }
+
+    /// Global `extension=`/`zend_extension=` lines loading `name`
+    fn extension_lines(&self, name: &str) -> Vec<(usize, bool)> {
+        let scopes = self.scopes();
+        self.lines
+            .iter()
+            .enumerate()
+            .filter_map(|(i, line)| match line {
+                Line::Entry {
+                    key,
+                    value,
+                    commented,
+                    ..
+                } if (key == "extension" || key == "zend_extension")
+                    && scopes[i].is_none()
+                    && extension_name(value) == name =>
+                {
+                    Some((i, *commented))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Extensions loaded by active global lines
+    pub fn extensions(&self) -> Vec<String> {
+        self.entries()
+            .into_iter()
+            .filter(|e| {
+                !e.commented
+                    && e.section.is_none()
+                    && (e.key == "extension" || e.key == "zend_extension")
+            })
+            .map(|e| extension_name(&e.value))
+            .collect()
+    }
+
+    /// Uncomment the documented `;extension=name` line, or add one next to
+    /// the other extension lines
+    pub fn enable_extension(&mut self, name: &str, zend: bool) {
+        let name = extension_name(name);
+        let lines = self.extension_lines(&name);
+
+        if lines.iter().any(|(_, commented)| !commented) {
+            return;
+        }
+        if let Some((i, _)) = lines.last() {
+            self.set_commented(*i, false);
+            return;
+        }
+
+        let key = if zend { "zend_extension" } else { "extension" };
+        let scopes = self.scopes();
+        let index = self
+            .lines
+            .iter()
+            .enumerate()
+            .rposition(|(i, line)| {
+                matches!(line, Line::Entry { key, .. } if key == "extension" || key == "zend_extension")
+                    && scopes[i].is_none()
+            })
+            .map(|i| i + 1)
+            .unwrap_or_else(|| self.global_insert_index(key));
+
+        self.lines.insert(
+            index,
+            Line::Entry {
+                raw: format!("{}={}", key, name),
+                key: key.to_string(),
+                value: name,
+                comment: None,
+                commented: false,
+            },
+        );
+    }
+
+    pub fn disable_extension(&mut self, name: &str) {
+        let name = extension_name(name);
+        for (i, commented) in self.extension_lines(&name) {
+            if !commented {
+                self.set_commented(i, true);
+            }
+        }
+    }
+
+    pub fn apply(&mut self, change: &IniChange) -> Result<(), String> {
+        let key = change.key.trim();
+        if !valid_key(key) {
+            return Err(format!("Invalid ini setting name '{}'", change.key));
+        }
+        if key == "extension" || key == "zend_extension" {
+            return Err("Use toggle_php_extension to enable or disable extensions".to_string());
+        }
+
+        let scope = change
+            .section
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty());
+        if let Some(scope) = scope {
+            if !is_scoped(scope) {
+                return Err(format!(
+                    "Section '{}' must be a [PATH=...] or [HOST=...] section",
+                    scope
+                ));
+            }
+        }
+
+        match change.value.as_deref() {
+            Some(value) if value.contains(['\n', '\r']) => Err(format!(
+                "Invalid value for {}: newlines are not allowed",
+                key
+            )),
+            Some(value) => {
+                self.set(key, value.trim(), scope);
+                Ok(())
+            }
+            None => {
+                self.unset(key, scope);
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Installed version and the php.ini its `sapi` (default `cli`) loads
+pub fn resolve_ini(version: &str, sapi: Option<&str>) -> Result<(PhpVersion, PathBuf), String> {
+    let php = php_discovery::find_installed(version)
+        .ok_or_else(|| format!("PHP {} not found", version))?;
+    let sapi = sapi.unwrap_or("cli");
+
+    let path = php
+        .ini_paths
+        .get(sapi)
+        .map(PathBuf::from)
+        .ok_or_else(|| format!("PHP {} has no php.ini for the {} SAPI", version, sapi))?;
+
+    Ok((php, path))
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|e| format!("Failed to read php.ini: {}", e))
+}
+
+/// php.ini the CLI actually loads, according to `php --ini`
+fn loaded_ini(php: &PhpVersion) -> Option<String> {
+    run_command(php.binary.as_deref()?, &["--ini"])?
+        .lines()
+        .find_map(|line| line.strip_prefix("Loaded Configuration File:"))
+        .map(|path| path.trim().to_string())
+        .filter(|path| path != "(none)")
+}
+
+/// What PHP makes of a configuration
+pub struct ConfigProbe {
+    /// Effective value of each requested directive, None when PHP doesn't know it
+    pub values: BTreeMap<String, Option<String>>,
+    /// Loaded extensions and Zend extensions, lowercased
+    pub extensions: Vec<String>,
+    /// Startup warnings and deprecations
+    pub warnings: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct ProbeOutput {
+    values: BTreeMap<String, Option<String>>,
+    extensions: Vec<String>,
+}
+
+/// Start the version's CLI binary with `ini` (and `scan_dir` instead of the
+/// compiled-in scan directory) and read back `keys`. Fails with PHP's message
+/// when the configuration doesn't parse.
+pub fn probe_config(
+    php: &PhpVersion,
+    ini: &Path,
+    scan_dir: Option<&Path>,
+    keys: &[&str],
+) -> Result<ConfigProbe, String> {
+    let binary = php
+        .binary
+        .as_deref()
+        .ok_or_else(|| format!("PHP {} has no CLI binary to check with", php.version))?;
+
+    let keys = serde_json::to_string(keys).unwrap_or_else(|_| "[]".to_string());
+    let script = format!(
+        "echo json_encode(['values' => (object) array_map(function ($k) {{ $v = ini_get($k); return $v === false ? null : $v; }}, array_combine({keys}, {keys})), 'extensions' => array_map('strtolower', array_merge(get_loaded_extensions(), get_loaded_extensions(true)))]);",
+        keys = keys
+    );
+
+    let mut cmd = Command::new(binary);
+    cmd.args(["-d", "display_startup_errors=1", "-c"])
+        .arg(ini)
+        .args(["-r", &script]);
+    if let Some(dir) = scan_dir {
+        cmd.env("PHP_INI_SCAN_DIR", dir);
+    }
+
+    let output = cmd
+        .output()
+        .map_err(|e| format!("Failed to run PHP {}: {}", php.version, e))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let diagnostics = format!("{}{}", stderr, stdout);
+
+    if let Some(error) = diagnostics
+        .lines()
+        .find(|l| l.contains("syntax error") || l.contains("Parse error"))
+    {
+        return Err(error.trim().to_string());
+    }
+
+    // The JSON is the last line; startup warnings may precede it
+    let parsed: ProbeOutput = stdout
+        .lines()
+        .last()
+        .and_then(|line| serde_json::from_str(line).ok())
+        .ok_or_else(|| {
+            format!(
+                "PHP {} failed to start: {}",
+                php.version,
+                diagnostics.trim()
+            )
+        })?;
+
+    Ok(ConfigProbe {
+        values: parsed.values,
+        extensions: parsed.extensions,
+        warnings: diagnostics
+            .lines()
+            .filter(|l| l.contains("Warning") || l.contains("Deprecated"))
+            .map(String::from)
+            .collect(),
+    })
+}
+
+/// Warnings for changes PHP doesn't recognize or that something loaded later overrides
+pub fn change_warnings(
+    php: &PhpVersion,
+    changes: &[IniChange],
+    values: &BTreeMap<String, Option<String>>,
+) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    for change in changes.iter().filter(|c| c.section.is_none()) {
+        let key = change.key.trim();
+        match (values.get(key), change.value.as_deref()) {
+            (Some(None), _) | (None, _) => warnings.push(format!(
+                "PHP {} doesn't recognize {} (is its extension loaded?)",
+                php.version, key
+            )),
+            (Some(Some(actual)), Some(wanted)) if !same_value(actual, wanted.trim()) => warnings
+                .push(format!(
+                    "{} is {} after loading the scan directory, not {}",
+                    key, actual, wanted
+                )),
+            _ => {}
+        }
+    }
+
+    warnings
+}
+
+/// Global keys a batch touches
+pub fn changed_keys(changes: &[IniChange]) -> Vec<&str> {
+    changes
+        .iter()
+        .filter(|c| c.section.is_none())
+        .map(|c| c.key.trim())
+        .collect()
+}
+
+/// Load a candidate php.ini with the version's own binary and report what PHP
+/// makes of the changed directives. Syntax errors fail the batch; unknown
+/// directives and values overridden by the scan dir are only warnings.
+fn validate(
+    php: &PhpVersion,
+    candidate: &Path,
+    target: &Path,
+    changes: &[IniChange],
+) -> Result<IniValidation, String> {
+    let probe = probe_config(php, candidate, None, &changed_keys(changes)).map_err(|error| {
+        format!(
+            "PHP rejected the new php.ini: {}",
+            error.replace(
+                &candidate.to_string_lossy().to_string(),
+                &target.to_string_lossy()
+            )
+        )
+    })?;
+
+    let mut warnings: Vec<String> = Vec::new();
+
+    // Editing a file the CLI doesn't load has no effect on it
+    if php.ini_paths.get("cli").map(Path::new) == Some(target) {
+        if let Some(loaded) = loaded_ini(php).filter(|l| Path::new(l) != target) {
+            warnings.push(format!(
+                "PHP {} loads {}, not {}",
+                php.version,
+                loaded,
+                target.display()
+            ));
+        }
+    }
+
+    warnings.extend(probe.warnings);
+    warnings.extend(change_warnings(php, changes, &probe.values));
+
+    Ok(IniValidation {
+        path: target.to_string_lossy().to_string(),
+        effective: probe.values,
+        warnings,
+    })
+}
+
+/// ini_get() reports booleans as "1"/"" and strips quotes
+fn same_value(actual: &str, wanted: &str) -> bool {
+    let wanted = wanted.trim_matches('"');
+    let normalize = |v: &str| match v.to_ascii_lowercase().as_str() {
+        "on" | "true" | "yes" | "1" => "1".to_string(),
+        "off" | "false" | "no" | "none" | "0" | "" => String::new(),
+        other => other.to_string(),
+    };
+    actual == wanted || normalize(actual) == normalize(wanted)
+}
+
+/// Apply a batch of changes to a php.ini file after validating them.
+/// The previous file is kept next to it as `php.ini.bak`.
+pub async fn update(
+    php: &PhpVersion,
+    path: &Path,
+    changes: &[IniChange],
+) -> Result<IniValidation, String> {
+    let original = read(path)?;
+    let mut document = IniDocument::parse(&original);
+    for change in changes {
+        document.apply(change)?;
+    }
+    let content = document.render();
+
+    let candidate =
+        std::env::temp_dir().join(format!("php-{}-{}.ini", php.version, uuid::Uuid::new_v4()));
+    std::fs::write(&candidate, &content)
+        .map_err(|e| format!("Failed to write {}: {}", candidate.display(), e))?;
+    let validation = validate(php, &candidate, path, changes);
+    let _ = std::fs::remove_file(&candidate);
+    let validation = validation?;
+
+    if content != original {
+        write_file_privileged(&path.with_extension("ini.bak"), &original).await?;
+        write_file_privileged(path, &content).await?;
+    }
+
+    Ok(validation)
+}
+
+/// Enable or disable an extension in the php.ini of every SAPI (CLI and FPM)
+pub async fn toggle_extension(php: &PhpVersion, name: &str, enable: bool) -> Result<(), String> {
+    let name = extension_name(name);
+    let zend = ZEND_EXTENSIONS.contains(&name.as_str());
+
+    let mut paths: Vec<&String> = ["cli", "fpm"]
+        .iter()
+        .filter_map(|sapi| php.ini_paths.get(*sapi))
+        .collect();
+    paths.dedup();
+    if paths.is_empty() {
+        return Err(format!("No php.ini found for PHP {}", php.version));
+    }
+
+    for path in paths {
+        let path = Path::new(path);
+        let original = read(path)?;
+        let mut document = IniDocument::parse(&original);
+        if enable {
+            document.enable_extension(&name, zend);
+        } else {
+            document.disable_extension(&name);
+        }
+
+        let content = document.render();
+        if content != original {
+            write_file_privileged(&path.with_extension("ini.bak"), &original).await?;
+            write_file_privileged(path, &content).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Parsed php.ini of a version (CLI unless `sapi` is given)
+#[tauri::command]
+pub async fn get_php_ini(version: String, sapi: Option<String>) -> Result<PhpIniFile, String> {
+    let (_, path) = resolve_ini(&version, sapi.as_deref())?;
+    let document = IniDocument::parse(&read(&path)?);
+
+    Ok(PhpIniFile {
+        path: path.to_string_lossy().to_string(),
+        sections: document.sections(),
+        entries: document.entries(),
+    })
+}
+
+/// Apply several php.ini edits at once, validated with `php -c` before saving
+#[tauri::command]
+pub async fn update_php_ini_settings(
+    version: String,
+    sapi: Option<String>,
+    changes: Vec<IniChange>,
+) -> Result<IniValidation, String> {
+    let (php, path) = resolve_ini(&version, sapi.as_deref())?;
+    update(&php, &path, &changes).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "[PHP]\n\
+; Maximum amount of memory a script may consume\n\
+memory_limit = 128M\n\
+memory_limit_x = 1\n\
+;max_execution_time = 30\n\
+\n\
+[Date]\n\
+;date.timezone =\n\
+\n\
+[PATH=/srv/shop]\n\
+memory_limit = 512M\n\
+\n\
+[HOST=shop.test]\n\
+display_errors = On\n";
+
+    fn change(key: &str, value: Option<&str>, section: Option<&str>) -> IniChange {
+        IniChange {
+            key: key.to_string(),
+            value: value.map(String::from),
+            section: section.map(String::from),
+        }
+    }
+
+    #[test]
+    fn parse_and_render_round_trip() {
+        assert_eq!(IniDocument::parse(SAMPLE).render(), SAMPLE);
+
+        let crlf = SAMPLE.replace('\n', "\r\n");
+        assert_eq!(IniDocument::parse(&crlf).render(), crlf);
+
+        let unterminated = SAMPLE.trim_end();
+        assert_eq!(IniDocument::parse(unterminated).render(), unterminated);
+    }
+
+    #[test]
+    fn set_replaces_the_exact_key_only() {
+        let mut doc = IniDocument::parse(SAMPLE);
+        doc.set("memory_limit", "256M", None);
+
+        let out = doc.render();
+        assert!(out.contains("[PHP]\n; Maximum amount of memory a script may consume\nmemory_limit = 256M\nmemory_limit_x = 1\n"));
+        // The scoped override is a different directive
+        assert!(out.contains("[PATH=/srv/shop]\nmemory_limit = 512M\n"));
+        assert!(out.ends_with('\n'));
+        assert_eq!(doc.values()["memory_limit"], "256M");
+        assert_eq!(doc.values()["memory_limit_x"], "1");
+    }
+
+    #[test]
+    fn set_keeps_inline_comments() {
+        let mut doc = IniDocument::parse("[PHP]\npost_max_size = 8M ; uploads\n");
+        doc.set("post_max_size", "64M", None);
+        assert_eq!(doc.render(), "[PHP]\npost_max_size = 64M ; uploads\n");
+    }
+
+    #[test]
+    fn set_goes_below_the_commented_default() {
+        let mut doc = IniDocument::parse(SAMPLE);
+        doc.set("max_execution_time", "120", None);
+        doc.set("date.timezone", "Europe/Madrid", None);
+
+        let out = doc.render();
+        assert!(out.contains(";max_execution_time = 30\nmax_execution_time = 120\n"));
+        assert!(out.contains("[Date]\n;date.timezone =\ndate.timezone = Europe/Madrid\n\n[PATH="));
+    }
+
+    #[test]
+    fn set_new_global_key_stays_out_of_scoped_sections() {
+        let mut doc = IniDocument::parse(SAMPLE);
+        doc.set("upload_max_filesize", "32M", None);
+
+        let out = doc.render();
+        assert!(out.contains(";max_execution_time = 30\nupload_max_filesize = 32M\n\n[Date]"));
+        assert_eq!(
+            doc.entries()
+                .iter()
+                .find(|e| e.key == "upload_max_filesize")
+                .and_then(|e| e.section.clone()),
+            None
+        );
+    }
+
+    #[test]
+    fn set_in_scoped_sections() {
+        let mut doc = IniDocument::parse(SAMPLE);
+        doc.set("memory_limit", "1G", Some("PATH=/srv/shop"));
+        doc.set("display_errors", "Off", Some("HOST=shop.test"));
+        doc.set("error_reporting", "E_ALL", Some("HOST=shop.test"));
+        doc.set("memory_limit", "64M", Some("HOST=blog.test"));
+
+        let out = doc.render();
+        assert!(out.contains(
+            "[PHP]\n; Maximum amount of memory a script may consume\nmemory_limit = 128M\n"
+        ));
+        assert!(out.contains("[PATH=/srv/shop]\nmemory_limit = 1G\n"));
+        assert!(out.contains(
+            "[HOST=shop.test]\ndisplay_errors = Off\nerror_reporting = E_ALL\n\n[HOST=blog.test]\nmemory_limit = 64M\n"
+        ));
+        assert!(out.ends_with("memory_limit = 64M\n"));
+    }
+
+    #[test]
+    fn unset_comments_out_every_occurrence_in_scope() {
+        let mut doc = IniDocument::parse("[PHP]\nmemory_limit = 128M\nmemory_limit_x = 1\nmemory_limit = 256M\n\n[PATH=/srv]\nmemory_limit = 1G\n");
+        doc.unset("memory_limit", None);
+
+        assert_eq!(
+            doc.render(),
+            "[PHP]\n;memory_limit = 128M\nmemory_limit_x = 1\n;memory_limit = 256M\n\n[PATH=/srv]\nmemory_limit = 1G\n"
+        );
+        assert!(!doc.values().contains_key("memory_limit"));
+
+        // Setting it again reuses the last commented line's position
+        doc.set("memory_limit", "512M", None);
+        assert!(doc
+            .render()
+            .contains(";memory_limit = 256M\nmemory_limit = 512M\n"));
+    }
+
+    #[test]
+    fn unset_scoped_leaves_global() {
+        let mut doc = IniDocument::parse(SAMPLE);
+        doc.unset("memory_limit", Some("PATH=/srv/shop"));
+
+        let out = doc.render();
+        assert!(out.contains("[PATH=/srv/shop]\n;memory_limit = 512M\n"));
+        assert_eq!(doc.values()["memory_limit"], "128M");
+    }
+
+    #[test]
+    fn apply_validates_and_dispatches() {
+        let mut doc = IniDocument::parse(SAMPLE);
+
+        doc.apply(&change(" memory_limit ", Some(" 256M "), None))
+            .unwrap();
+        doc.apply(&change("display_errors", None, Some(" HOST=shop.test ")))
+            .unwrap();
+        doc.apply(&change("memory_limit", Some("2G"), Some("")))
+            .unwrap();
+
+        let out = doc.render();
+        assert!(out.contains(
+            "[PHP]\n; Maximum amount of memory a script may consume\nmemory_limit = 2G\n"
+        ));
+        assert!(out.contains("[HOST=shop.test]\n;display_errors = On\n"));
+
+        assert!(doc.apply(&change("bad key", Some("1"), None)).is_err());
+        assert!(doc
+            .apply(&change("extension", Some("redis"), None))
+            .is_err());
+        assert!(doc
+            .apply(&change("memory_limit", Some("1G"), Some("Date")))
+            .is_err());
+        assert!(doc
+            .apply(&change("memory_limit", Some("1G\nextension=evil"), None))
+            .is_err());
+        assert_eq!(doc.render(), out);
+    }
+
+    #[test]
+    fn empty_document_gets_a_trailing_newline() {
+        let mut doc = IniDocument::parse("");
+        doc.set("memory_limit", "256M", None);
+        assert_eq!(doc.render(), "memory_limit = 256M\n");
+    }
+}
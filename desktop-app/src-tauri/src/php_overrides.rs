@@ -0,0 +1,319 @@
+// ============================================
+// Scan-dir Overrides
+// ============================================
+// In `conf-d` mode the app leaves the distro-owned php.ini alone and writes
+// its settings and extension toggles to `99-localhost-manager.ini` in the
+// scan directory of each SAPI (CLI and FPM). The `99-` prefix sorts it after
+// the packaged extension files so its values win, and package upgrades never
+// touch it. After every write the file is loaded back with the version's own
+// binary, and a change PHP rejects is rolled back.
+
+use crate::hosts_manager::run_command;
+use crate::php_discovery;
+use crate::php_fpm;
+use crate::php_ini::{self, IniChange, IniDocument, IniValidation, ZEND_EXTENSIONS};
+use crate::settings::{PhpConfigMode, Settings};
+use crate::system::{remove_file_privileged, write_file_privileged};
+use crate::types::PhpVersion;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+pub const OVERRIDE_FILE: &str = "99-localhost-manager.ini";
+
+const HEADER: &str = "; Managed by Localhost Manager. Settings and extensions changed in the app\n\
+                      ; are written here so package upgrades never overwrite them.\n";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PhpOverrides {
+    pub mode: PhpConfigMode,
+    /// Override file per SAPI; SAPIs sharing a scan directory share the file
+    pub files: BTreeMap<String, String>,
+    pub settings: BTreeMap<String, String>,
+    pub extensions: Vec<String>,
+    /// Effective value of each overridden directive, per SAPI
+    pub effective: BTreeMap<String, BTreeMap<String, Option<String>>>,
+}
+
+/// Scan directory the CLI reads, as reported by `php --ini`
+fn cli_scan_dir(php: &PhpVersion) -> Option<PathBuf> {
+    run_command(php.binary.as_deref()?, &["--ini"])
+        .and_then(|output| {
+            output.lines().find_map(|line| {
+                line.strip_prefix("Scan for additional .ini files in:")
+                    .map(|dir| dir.trim().to_string())
+            })
+        })
+        .or_else(|| php.ini_scan_dir.clone())
+        .filter(|dir| !dir.is_empty() && dir != "(none)")
+        // PHP_INI_SCAN_DIR may list several; the last one is read last
+        .and_then(|dirs| std::env::split_paths(&dirs).last())
+}
+
+/// Scan directory per SAPI. Debian gives each SAPI a conf.d next to its
+/// php.ini; the other layouts share the CLI's.
+pub fn scan_dirs(php: &PhpVersion) -> BTreeMap<String, PathBuf> {
+    let cli = cli_scan_dir(php);
+    let mut dirs = BTreeMap::new();
+
+    for sapi in ["cli", "fpm"] {
+        if !php.sapis.iter().any(|s| s == sapi) {
+            continue;
+        }
+
+        let own = (sapi != "cli")
+            .then(|| php.ini_paths.get(sapi))
+            .flatten()
+            .and_then(|ini| Path::new(ini).parent())
+            .map(|dir| dir.join("conf.d"))
+            .filter(|dir| dir.is_dir());
+
+        if let Some(dir) = own.or_else(|| cli.clone()) {
+            dirs.insert(sapi.to_string(), dir);
+        }
+    }
+
+    dirs
+}
+
+/// Mode used for a version: the configured one, except that builds without a
+/// scan directory (Windows, some static builds) only read their php.ini
+pub fn config_mode(php: &PhpVersion) -> PhpConfigMode {
+    match Settings::load().unwrap_or_default().php_config_mode {
+        PhpConfigMode::ConfD if scan_dirs(php).is_empty() => PhpConfigMode::PhpIni,
+        mode => mode,
+    }
+}
+
+fn override_files(php: &PhpVersion) -> Result<BTreeMap<String, PathBuf>, String> {
+    let files: BTreeMap<String, PathBuf> = scan_dirs(php)
+        .into_iter()
+        .map(|(sapi, dir)| (sapi, dir.join(OVERRIDE_FILE)))
+        .collect();
+
+    if files.is_empty() {
+        return Err(format!(
+            "PHP {} has no scan directory; switch PHP configuration to php.ini mode",
+            php.version
+        ));
+    }
+
+    Ok(files)
+}
+
+/// Current overrides, from the first SAPI's file (they're kept identical)
+fn read_document(files: &BTreeMap<String, PathBuf>) -> IniDocument {
+    let content = files
+        .values()
+        .find_map(|path| std::fs::read_to_string(path).ok())
+        .unwrap_or_else(|| HEADER.to_string());
+    IniDocument::parse(&content)
+}
+
+/// Settings the override files set, without the extension lines
+fn document_settings(document: &IniDocument) -> BTreeMap<String, String> {
+    document
+        .values()
+        .into_iter()
+        .filter(|(key, _)| key != "extension" && key != "zend_extension")
+        .collect()
+}
+
+/// Settings a version's override files set; empty when it has none
+pub fn settings(php: &PhpVersion) -> BTreeMap<String, String> {
+    override_files(php)
+        .map(|files| document_settings(&read_document(&files)))
+        .unwrap_or_default()
+}
+
+/// What each SAPI ends up with once the overrides are loaded
+fn probe_all(
+    php: &PhpVersion,
+    files: &BTreeMap<String, PathBuf>,
+    keys: &[&str],
+) -> Result<BTreeMap<String, php_ini::ConfigProbe>, String> {
+    let mut probes = BTreeMap::new();
+
+    for (sapi, file) in files {
+        let Some(ini) = php.ini_paths.get(sapi) else {
+            continue;
+        };
+        let probe =
+            php_ini::probe_config(php, Path::new(ini), file.parent(), keys).map_err(|e| {
+                format!(
+                    "PHP {} ({}) rejected {}: {}",
+                    php.version,
+                    sapi,
+                    file.display(),
+                    e
+                )
+            })?;
+        probes.insert(sapi.clone(), probe);
+    }
+
+    Ok(probes)
+}
+
+/// Write `content` to every SAPI's override file and check it loads; on
+/// failure (or when `check` rejects the result) restore the previous files.
+async fn write_checked<F>(
+    php: &PhpVersion,
+    content: &str,
+    keys: &[&str],
+    check: F,
+) -> Result<BTreeMap<String, php_ini::ConfigProbe>, String>
+where
+    F: Fn(&BTreeMap<String, php_ini::ConfigProbe>) -> Result<(), String>,
+{
+    let files = override_files(php)?;
+
+    let mut previous: BTreeMap<PathBuf, Option<String>> = BTreeMap::new();
+    for file in files.values() {
+        if previous.contains_key(file) {
+            continue;
+        }
+        previous.insert(file.clone(), std::fs::read_to_string(file).ok());
+        write_file_privileged(file, content).await?;
+    }
+
+    let result = probe_all(php, &files, keys).and_then(|probes| {
+        check(&probes)?;
+        Ok(probes)
+    });
+
+    match result {
+        Ok(probes) => {
+            if files.contains_key("fpm") && previous.values().any(|p| p.as_deref() != Some(content))
+            {
+                // Best effort: the values are saved either way and apply on the next FPM restart
+                let _ = php_fpm::reload(php).await;
+            }
+            Ok(probes)
+        }
+        Err(e) => {
+            for (file, content) in previous {
+                let _ = match content {
+                    Some(content) => write_file_privileged(&file, &content).await,
+                    None => remove_file_privileged(&file).await,
+                };
+            }
+            Err(e)
+        }
+    }
+}
+
+/// Apply setting changes to the override files
+pub async fn update_overrides(
+    php: &PhpVersion,
+    changes: &[IniChange],
+) -> Result<IniValidation, String> {
+    let files = override_files(php)?;
+    let mut document = read_document(&files);
+    for change in changes {
+        document.apply(change)?;
+    }
+
+    let keys = php_ini::changed_keys(changes);
+    let probes = write_checked(php, &document.render(), &keys, |_| Ok(())).await?;
+
+    let mut warnings = Vec::new();
+    let mut effective = BTreeMap::new();
+    for (sapi, probe) in probes {
+        warnings.extend(
+            php_ini::change_warnings(php, changes, &probe.values)
+                .into_iter()
+                .map(|w| format!("{}: {}", sapi, w)),
+        );
+        warnings.extend(probe.warnings);
+        if effective.is_empty() {
+            effective = probe.values;
+        }
+    }
+    warnings.dedup();
+
+    Ok(IniValidation {
+        path: files
+            .values()
+            .next()
+            .map(|f| f.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        effective,
+        warnings,
+    })
+}
+
+/// Enable or disable an extension through the override files. An extension
+/// another ini file enables can't be unloaded from here, which is reported.
+pub async fn toggle_extension(php: &PhpVersion, name: &str, enable: bool) -> Result<(), String> {
+    let name = php_ini::extension_name(name);
+    let files = override_files(php)?;
+    let mut document = read_document(&files);
+
+    if enable {
+        document.enable_extension(&name, ZEND_EXTENSIONS.contains(&name.as_str()));
+    } else {
+        document.disable_extension(&name);
+    }
+
+    // `php -m` lists OPcache as "Zend OPcache"
+    let loaded = |probe: &php_ini::ConfigProbe| {
+        probe
+            .extensions
+            .iter()
+            .any(|e| *e == name || *e == format!("zend {}", name))
+    };
+
+    write_checked(php, &document.render(), &[], |probes| {
+        match probes.iter().find(|(_, probe)| loaded(probe) != enable) {
+            Some((sapi, _)) if enable => Err(format!(
+                "{} could not be loaded by PHP {} ({}); is it installed?",
+                name, php.version, sapi
+            )),
+            Some((sapi, _)) => Err(format!(
+                "{} is enabled by another ini file for PHP {} ({}); disable it there or switch to php.ini mode",
+                name, php.version, sapi
+            )),
+            None => Ok(()),
+        }
+    })
+    .await
+    .map(|_| ())
+}
+
+/// Current overrides of a version and the values each SAPI ends up with
+#[tauri::command]
+pub async fn get_php_overrides(version: String) -> Result<PhpOverrides, String> {
+    let php = php_discovery::find_installed(&version)
+        .ok_or_else(|| format!("PHP {} not found", version))?;
+    let files = override_files(&php)?;
+    let document = read_document(&files);
+
+    let settings = document_settings(&document);
+    let keys: Vec<&str> = settings.keys().map(String::as_str).collect();
+    let effective = probe_all(&php, &files, &keys)?
+        .into_iter()
+        .map(|(sapi, probe)| (sapi, probe.values))
+        .collect();
+
+    Ok(PhpOverrides {
+        mode: config_mode(&php),
+        files: files
+            .into_iter()
+            .map(|(sapi, file)| (sapi, file.to_string_lossy().to_string()))
+            .collect(),
+        extensions: document.extensions(),
+        settings,
+        effective,
+    })
+}
+
+/// Apply several setting changes to the override files
+#[tauri::command]
+pub async fn update_php_overrides(
+    version: String,
+    changes: Vec<IniChange>,
+) -> Result<IniValidation, String> {
+    let php = php_discovery::find_installed(&version)
+        .ok_or_else(|| format!("PHP {} not found", version))?;
+    update_overrides(&php, &changes).await
+}
@@ -0,0 +1,595 @@
+// ============================================
+// Standalone PHP Builds
+// ============================================
+// Downloads prebuilt static PHP binaries from the configured mirror into
+// `php_install_dir/<version>`, so any version can be installed without root
+// or a package manager. Archives are verified against the mirror's SHA-256
+// before anything is extracted.
+//
+// Mirror layout (static-php-cli compatible):
+//   <mirror>/php-<version>-cli-<os>-<arch>.tar.gz   (+ .sha256)
+//   <mirror>/php-<version>-fpm-<os>-<arch>.tar.gz   (optional, + .sha256)
+//   <mirror>/php-<version>-cli-win.zip              (Windows, + .sha256)
+// A `sha256sums.txt` at the mirror root is used when per-file checksums are missing.
+
+use crate::config::AppConfig;
+use crate::settings::Settings;
+use crate::types::DownloadProgress;
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+/// Event carrying `DownloadProgress` updates
+pub const PHP_DOWNLOAD_EVENT: &str = "php-download-progress";
+
+/// Minimum interval between progress events while downloading
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+struct Artifact {
+    file_name: String,
+    /// Directory inside the install prefix the archive is unpacked into
+    target_dir: &'static str,
+    required: bool,
+}
+
+fn platform() -> (&'static str, &'static str) {
+    let os = if cfg!(target_os = "macos") {
+        "macos"
+    } else if cfg!(target_os = "windows") {
+        "win"
+    } else {
+        "linux"
+    };
+    let arch = if cfg!(target_arch = "aarch64") {
+        "aarch64"
+    } else {
+        "x86_64"
+    };
+    (os, arch)
+}
+
+fn artifacts(version: &str) -> Vec<Artifact> {
+    let (os, arch) = platform();
+
+    if os == "win" {
+        // The Windows build ships php.exe and php-cgi.exe side by side
+        return vec![Artifact {
+            file_name: format!("php-{}-cli-win.zip", version),
+            target_dir: "",
+            required: true,
+        }];
+    }
+
+    vec![
+        Artifact {
+            file_name: format!("php-{}-cli-{}-{}.tar.gz", version, os, arch),
+            target_dir: "bin",
+            required: true,
+        },
+        Artifact {
+            file_name: format!("php-{}-fpm-{}-{}.tar.gz", version, os, arch),
+            target_dir: "sbin",
+            required: false,
+        },
+    ]
+}
+
+/// Only full `X.Y.Z` versions map to an archive, and this keeps the
+/// version safe to use as a directory name
+fn validate_version(version: &str) -> Result<(), String> {
+    let parts: Vec<&str> = version.split('.').collect();
+    if parts.len() == 3
+        && parts
+            .iter()
+            .all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()))
+    {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid PHP version '{}', expected a full version such as 8.3.14",
+            version
+        ))
+    }
+}
+
+fn format_speed(bytes_per_sec: f64) -> String {
+    if bytes_per_sec >= 1024.0 * 1024.0 {
+        format!("{:.1} MB/s", bytes_per_sec / (1024.0 * 1024.0))
+    } else {
+        format!("{:.0} KB/s", bytes_per_sec / 1024.0)
+    }
+}
+
+fn stage_progress(version: &str, stage: &str) -> DownloadProgress {
+    DownloadProgress {
+        version: version.to_string(),
+        stage: stage.to_string(),
+        downloaded: 0,
+        total: 0,
+        percentage: if stage == "done" { 100.0 } else { 0.0 },
+        speed: String::new(),
+    }
+}
+
+/// Expected SHA-256 for an archive: `<url>.sha256`, else `sha256sums.txt`
+async fn fetch_checksum(
+    client: &reqwest::Client,
+    mirror: &str,
+    file_name: &str,
+) -> Result<String, String> {
+    let parse_hex = |token: &str| {
+        let token = token.trim().to_lowercase();
+        (token.len() == 64 && token.chars().all(|c| c.is_ascii_hexdigit())).then_some(token)
+    };
+
+    let per_file = format!("{}/{}.sha256", mirror, file_name);
+    if let Ok(response) = client.get(&per_file).send().await {
+        if response.status().is_success() {
+            let body = response.text().await.unwrap_or_default();
+            if let Some(hash) = body.split_whitespace().next().and_then(parse_hex) {
+                return Ok(hash);
+            }
+        }
+    }
+
+    let sums_url = format!("{}/sha256sums.txt", mirror);
+    if let Ok(response) = client.get(&sums_url).send().await {
+        if response.status().is_success() {
+            let body = response.text().await.unwrap_or_default();
+            let hash = body.lines().find_map(|line| {
+                let mut parts = line.split_whitespace();
+                let hash = parts.next()?;
+                let name = parts.next()?.trim_start_matches('*');
+                (name == file_name).then(|| parse_hex(hash)).flatten()
+            });
+            if let Some(hash) = hash {
+                return Ok(hash);
+            }
+        }
+    }
+
+    Err(format!("No SHA-256 checksum published for {}", file_name))
+}
+
+/// Stream an archive to `dest`, hashing as we go. Returns the hex digest.
+/// Chunks are written and hashed on a blocking thread so disk I/O never
+/// stalls the async runtime.
+async fn download(
+    report: &(dyn Fn(DownloadProgress) + Sync),
+    client: &reqwest::Client,
+    url: &str,
+    dest: &Path,
+    version: &str,
+) -> Result<String, String> {
+    let mut response = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Download failed: {}", e))?;
+
+    let (chunks, received) = std::sync::mpsc::channel::<Vec<u8>>();
+    let target = dest.to_path_buf();
+    let writer = tauri::async_runtime::spawn_blocking(move || {
+        let mut file = std::fs::File::create(&target)
+            .map_err(|e| format!("Failed to create {}: {}", target.display(), e))?;
+        let mut hasher = Sha256::new();
+        for chunk in received {
+            file.write_all(&chunk)
+                .map_err(|e| format!("Failed to write download: {}", e))?;
+            hasher.update(&chunk);
+        }
+        file.flush()
+            .map_err(|e| format!("Failed to write download: {}", e))?;
+        Ok::<_, String>(format!("{:x}", hasher.finalize()))
+    });
+
+    let total = response.content_length().unwrap_or(0);
+    let mut downloaded: u64 = 0;
+    let started = Instant::now();
+    let mut last_emit = Instant::now() - PROGRESS_INTERVAL;
+
+    let streamed = loop {
+        let chunk = match response.chunk().await {
+            Ok(Some(chunk)) => chunk,
+            Ok(None) => break Ok(()),
+            Err(e) => break Err(format!("Download interrupted: {}", e)),
+        };
+        downloaded += chunk.len() as u64;
+        // The writer only hangs up after a write error, which it reports below
+        if chunks.send(chunk.into()).is_err() {
+            break Ok(());
+        }
+
+        if last_emit.elapsed() >= PROGRESS_INTERVAL || downloaded == total {
+            last_emit = Instant::now();
+            let elapsed = started.elapsed().as_secs_f64().max(0.001);
+            report(DownloadProgress {
+                version: version.to_string(),
+                stage: "downloading".to_string(),
+                downloaded,
+                total,
+                percentage: if total > 0 {
+                    downloaded as f64 / total as f64 * 100.0
+                } else {
+                    0.0
+                },
+                speed: format_speed(downloaded as f64 / elapsed),
+            });
+        }
+    };
+
+    drop(chunks);
+    let digest = writer.await.map_err(|e| e.to_string())?;
+    streamed?;
+    digest
+}
+
+fn extract(archive: &Path, target: &Path) -> Result<(), String> {
+    std::fs::create_dir_all(target)
+        .map_err(|e| format!("Failed to create {}: {}", target.display(), e))?;
+
+    let file = std::fs::File::open(archive)
+        .map_err(|e| format!("Failed to open {}: {}", archive.display(), e))?;
+
+    if archive.extension().and_then(|e| e.to_str()) == Some("zip") {
+        zip::ZipArchive::new(file)
+            .and_then(|mut zip| zip.extract(target))
+            .map_err(|e| format!("Failed to extract {}: {}", archive.display(), e))
+    } else {
+        // tar's unpack refuses entries escaping the target directory
+        tar::Archive::new(flate2::read::GzDecoder::new(file))
+            .unpack(target)
+            .map_err(|e| format!("Failed to extract {}: {}", archive.display(), e))
+    }
+}
+
+#[cfg(unix)]
+fn make_executable(dir: &Path) {
+    use std::os::unix::fs::PermissionsExt;
+
+    if let Ok(entries) = std::fs::read_dir(dir) {
+        for entry in entries.filter_map(Result::ok) {
+            let _ = std::fs::set_permissions(entry.path(), std::fs::Permissions::from_mode(0o755));
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn make_executable(_dir: &Path) {}
+
+/// Download, verify and unpack every artifact into `staging`
+async fn stage(
+    report: &(dyn Fn(DownloadProgress) + Sync),
+    mirror: &str,
+    downloads_dir: &Path,
+    staging: &Path,
+    version: &str,
+) -> Result<(), String> {
+    let client = reqwest::Client::new();
+
+    for artifact in artifacts(version) {
+        let url = format!("{}/{}", mirror, artifact.file_name);
+
+        // Optional SAPIs (FPM) aren't built for every version/platform
+        let expected = match fetch_checksum(&client, mirror, &artifact.file_name).await {
+            Ok(hash) => hash,
+            Err(_) if !artifact.required => continue,
+            Err(e) => return Err(e),
+        };
+
+        let archive = downloads_dir.join(&artifact.file_name);
+        report(stage_progress(version, "downloading"));
+        let downloaded = download(report, &client, &url, &archive, version).await;
+        let actual = match downloaded {
+            Ok(actual) => actual,
+            Err(e) => {
+                let _ = std::fs::remove_file(&archive);
+                return Err(e);
+            }
+        };
+
+        report(stage_progress(version, "verifying"));
+        if actual != expected {
+            let _ = std::fs::remove_file(&archive);
+            return Err(format!(
+                "Checksum mismatch for {}: expected {}, got {}",
+                artifact.file_name, expected, actual
+            ));
+        }
+
+        report(stage_progress(version, "extracting"));
+        let target = staging.join(artifact.target_dir);
+        tauri::async_runtime::spawn_blocking(move || {
+            let extracted = extract(&archive, &target);
+            let _ = std::fs::remove_file(&archive);
+            extracted?;
+            make_executable(&target);
+            Ok::<_, String>(())
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+    }
+
+    Ok(())
+}
+
+/// Install `version` from `mirror` into `install_dir/<version>`
+async fn install(
+    report: &(dyn Fn(DownloadProgress) + Sync),
+    mirror: &str,
+    install_dir: &Path,
+    version: &str,
+) -> Result<PathBuf, String> {
+    validate_version(version)?;
+
+    let mirror = mirror.trim_end_matches('/');
+    let downloads_dir = install_dir.join(".downloads");
+    let staging = install_dir.join(format!(".{}.partial", version));
+    let prefix = install_dir.join(version);
+
+    let prepare = (downloads_dir.clone(), staging.clone());
+    tauri::async_runtime::spawn_blocking(move || {
+        let (downloads_dir, staging) = prepare;
+        let _ = std::fs::remove_dir_all(&staging);
+        std::fs::create_dir_all(&downloads_dir)
+            .map_err(|e| format!("Failed to create {}: {}", downloads_dir.display(), e))
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    let staged = stage(report, mirror, &downloads_dir, &staging, version).await;
+
+    // Swap the finished install in so a failed download never leaves half a version behind
+    let target = prefix.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let swapped = staged.and_then(|()| {
+            if target.exists() {
+                std::fs::remove_dir_all(&target)
+                    .map_err(|e| format!("Failed to replace {}: {}", target.display(), e))?;
+            }
+            std::fs::rename(&staging, &target)
+                .map_err(|e| format!("Failed to install into {}: {}", target.display(), e))
+        });
+        if swapped.is_err() {
+            let _ = std::fs::remove_dir_all(&staging);
+        }
+        swapped
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    Ok(prefix)
+}
+
+/// Download a standalone PHP build into `php_install_dir/<version>`
+#[tauri::command]
+pub async fn install_standalone_php(app: AppHandle, version: String) -> Result<String, String> {
+    let mirror = Settings::load().unwrap_or_default().php_mirror_url;
+    let install_dir = AppConfig::load().unwrap_or_default().php_install_dir;
+    let report = |progress: DownloadProgress| {
+        let _ = app.emit(PHP_DOWNLOAD_EVENT, progress);
+    };
+
+    match install(&report, &mirror, &install_dir, &version).await {
+        Ok(prefix) => {
+            report(stage_progress(&version, "done"));
+            Ok(format!("PHP {} installed to {}", version, prefix.display()))
+        }
+        Err(e) => {
+            report(stage_progress(&version, "error"));
+            Err(e)
+        }
+    }
+}
+
+/// Remove a standalone PHP build
+#[tauri::command]
+pub async fn remove_standalone_php(version: String) -> Result<String, String> {
+    validate_version(&version)?;
+
+    let prefix = AppConfig::load()
+        .unwrap_or_default()
+        .php_install_dir
+        .join(&version);
+
+    if !prefix.exists() {
+        return Err(format!(
+            "PHP {} is not installed as a standalone build",
+            version
+        ));
+    }
+
+    std::fs::remove_dir_all(&prefix)
+        .map_err(|e| format!("Failed to remove {}: {}", prefix.display(), e))?;
+
+    Ok(format!("PHP {} removed", version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    const VERSION: &str = "8.3.14";
+
+    fn cli_archive_name() -> String {
+        artifacts(VERSION).remove(0).file_name
+    }
+
+    /// A tar.gz holding a single `php` file
+    fn archive() -> Vec<u8> {
+        let content = b"#!/bin/sh\necho 8.3.14\n";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+            Vec::new(),
+            flate2::Compression::default(),
+        ));
+        builder
+            .append_data(&mut header, "php", &content[..])
+            .unwrap();
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    fn sha256_hex(bytes: &[u8]) -> String {
+        format!("{:x}", Sha256::digest(bytes))
+    }
+
+    /// Serve `files` by path over plain HTTP, 404 for anything else
+    async fn serve(files: HashMap<String, Vec<u8>>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else {
+                    return;
+                };
+                let files = files.clone();
+                tokio::spawn(async move {
+                    let mut request = Vec::new();
+                    let mut buf = [0u8; 1024];
+                    while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                        match socket.read(&mut buf).await {
+                            Ok(0) | Err(_) => return,
+                            Ok(n) => request.extend_from_slice(&buf[..n]),
+                        }
+                    }
+                    let request = String::from_utf8_lossy(&request);
+                    let path = request.split_whitespace().nth(1).unwrap_or("/");
+
+                    let (status, body) = match files.get(path) {
+                        Some(body) => ("200 OK", body.clone()),
+                        None => ("404 Not Found", b"not found".to_vec()),
+                    };
+                    let head = format!(
+                        "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                        status,
+                        body.len()
+                    );
+                    let _ = socket.write_all(head.as_bytes()).await;
+                    let _ = socket.write_all(&body).await;
+                });
+            }
+        });
+
+        format!("http://{}/mirror/", addr)
+    }
+
+    fn install_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("php-standalone-test-{}", uuid::Uuid::new_v4()))
+    }
+
+    fn stages(events: &std::sync::Mutex<Vec<DownloadProgress>>) -> Vec<String> {
+        let mut stages: Vec<String> = events
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|e| e.stage.clone())
+            .collect();
+        stages.dedup();
+        stages
+    }
+
+    #[tokio::test]
+    async fn installs_a_verified_archive() {
+        let archive = archive();
+        let name = cli_archive_name();
+        let mirror = serve(HashMap::from([
+            (
+                format!("/mirror/{}.sha256", name),
+                format!("{}  {}\n", sha256_hex(&archive), name).into_bytes(),
+            ),
+            (format!("/mirror/{}", name), archive),
+        ]))
+        .await;
+
+        let dir = install_dir();
+        let events = std::sync::Mutex::new(Vec::new());
+        let report = |progress: DownloadProgress| events.lock().unwrap().push(progress);
+
+        let prefix = install(&report, &mirror, &dir, VERSION).await.unwrap();
+
+        assert_eq!(prefix, dir.join(VERSION));
+        let php = prefix.join("bin").join("php");
+        assert!(php.is_file());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&php).unwrap().permissions().mode();
+            assert_eq!(mode & 0o111, 0o111);
+        }
+        // The FPM archive isn't published, which is fine: it's optional
+        assert!(!prefix.join("sbin").exists());
+        assert!(!dir.join(format!(".{}.partial", VERSION)).exists());
+        assert!(!dir.join(".downloads").join(&name).exists());
+        assert_eq!(stages(&events), ["downloading", "verifying", "extracting"]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn rejects_a_checksum_mismatch() {
+        let archive = archive();
+        let name = cli_archive_name();
+        let mirror = serve(HashMap::from([
+            (
+                "/mirror/sha256sums.txt".to_string(),
+                format!("{}  {}\n", sha256_hex(b"something else"), name).into_bytes(),
+            ),
+            (format!("/mirror/{}", name), archive),
+        ]))
+        .await;
+
+        let dir = install_dir();
+        let err = install(&|_| {}, &mirror, &dir, VERSION).await.unwrap_err();
+
+        assert!(err.starts_with("Checksum mismatch"), "{}", err);
+        assert!(!dir.join(format!(".{}.partial", VERSION)).exists());
+        assert!(!dir.join(VERSION).exists());
+        assert!(!dir.join(".downloads").join(&name).exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn fails_when_the_archive_is_missing() {
+        let name = cli_archive_name();
+        let mirror = serve(HashMap::from([(
+            format!("/mirror/{}.sha256", name),
+            sha256_hex(b"anything").into_bytes(),
+        )]))
+        .await;
+
+        let dir = install_dir();
+        let err = install(&|_| {}, &mirror, &dir, VERSION).await.unwrap_err();
+
+        assert!(err.starts_with("Download failed"), "{}", err);
+        assert!(err.contains("404"), "{}", err);
+        assert!(!dir.join(format!(".{}.partial", VERSION)).exists());
+        assert!(!dir.join(VERSION).exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn fails_without_a_published_checksum() {
+        let mirror = serve(HashMap::new()).await;
+
+        let dir = install_dir();
+        let err = install(&|_| {}, &mirror, &dir, VERSION).await.unwrap_err();
+
+        assert!(err.starts_with("No SHA-256 checksum"), "{}", err);
+        assert!(!dir.join(VERSION).exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
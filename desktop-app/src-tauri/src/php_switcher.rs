@@ -0,0 +1,497 @@
+// ============================================
+// PHP CLI Version Switcher
+// ============================================
+// Maintains a shim directory under the config dir with `php`, `phpize`,
+// `php-config` and `composer` wrappers. Like phpenv, each shim picks the
+// version from the nearest `.php-version` file above the working directory
+// and falls back to the global version (`defaultPhpVersion` in settings).
+//
+// Installed branches are linked under `shims/versions/<major.minor>/` and the
+// global branch is written to `shims/version`, so the shims never need to
+// run discovery or parse settings.json themselves.
+
+use crate::paths;
+use crate::php_discovery;
+use crate::settings::{Settings, SETTINGS_CHANGED_EVENT};
+use crate::types::PhpVersion;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Listener};
+
+/// Per-directory version file, compatible with phpenv and friends
+pub const VERSION_FILE: &str = ".php-version";
+
+/// Event name used to report background shim refreshes to the UI
+pub const PHP_SHIMS_EVENT: &str = "php-shims";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PhpShimsEvent {
+    stage: String,
+    message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ActivePhp {
+    /// Full version of the selected build
+    pub version: String,
+    /// `local` when pinned by a `.php-version` file, otherwise `global`
+    pub scope: String,
+    pub version_file: Option<String>,
+    pub binary: String,
+    pub shims_dir: String,
+    /// Whether the shims directory is on this process's PATH
+    pub shims_on_path: bool,
+}
+
+pub fn shims_dir() -> PathBuf {
+    paths::config_dir().join("shims")
+}
+
+/// `8.3.14`, `8.3` or `php-8.3` → `8.3`
+fn branch(version: &str) -> String {
+    version
+        .trim()
+        .trim_start_matches("php")
+        .trim_start_matches('-')
+        .split('.')
+        .take(2)
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Version pinned by the nearest `.php-version` at or above `dir`
+pub fn local_version(dir: &Path) -> Option<(String, PathBuf)> {
+    dir.ancestors().find_map(|d| {
+        let file = d.join(VERSION_FILE);
+        let content = std::fs::read_to_string(&file).ok()?;
+        let version = content.lines().next()?.trim().to_string();
+        (!version.is_empty()).then_some((version, file))
+    })
+}
+
+/// Pick the installed build for a requested version: an exact match first,
+/// then the newest build of the same branch
+fn select<'a>(installed: &'a [PhpVersion], requested: &str) -> Option<&'a PhpVersion> {
+    let requested = requested.trim();
+    installed
+        .iter()
+        .find(|v| v.version == requested)
+        .or_else(|| {
+            let wanted = branch(requested);
+            installed.iter().find(|v| v.branch() == wanted)
+        })
+}
+
+fn shims_on_path() -> bool {
+    let dir = shims_dir();
+    std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).any(|p| p == dir))
+        .unwrap_or(false)
+}
+
+/// The version the shims would run in `dir` (or globally when `dir` is None)
+pub fn active(installed: &[PhpVersion], dir: Option<&Path>) -> Option<ActivePhp> {
+    let (requested, version_file) = match dir.and_then(local_version) {
+        Some((version, file)) => (version, Some(file)),
+        None => (Settings::load().ok()?.default_php_version?, None),
+    };
+    let php = select(installed, &requested)?;
+
+    Some(ActivePhp {
+        version: php.version.clone(),
+        scope: if version_file.is_some() {
+            "local"
+        } else {
+            "global"
+        }
+        .to_string(),
+        version_file: version_file.map(|f| f.to_string_lossy().to_string()),
+        binary: php.binary.clone().unwrap_or_default(),
+        shims_dir: shims_dir().to_string_lossy().to_string(),
+        shims_on_path: shims_on_path(),
+    })
+}
+
+/// Real composer on PATH, skipping our own shim
+pub(crate) fn find_composer() -> Option<PathBuf> {
+    let shims = shims_dir();
+    let names: &[&str] = if cfg!(target_os = "windows") {
+        &["composer.phar"]
+    } else {
+        &["composer", "composer.phar"]
+    };
+
+    let path_var = std::env::var_os("PATH")?;
+    std::env::split_paths(&path_var)
+        .filter(|dir| *dir != shims)
+        .flat_map(|dir| names.iter().map(move |name| dir.join(name)))
+        .find(|p| p.is_file())
+}
+
+#[cfg(unix)]
+fn sh_quote(value: &Path) -> String {
+    format!("'{}'", value.to_string_lossy().replace('\'', "'\\''"))
+}
+
+#[cfg(unix)]
+fn shim_script(shims: &Path, tool: &str, composer: Option<&Path>) -> String {
+    let run = match (tool, composer) {
+        ("composer", Some(composer)) => format!("exec \"$bin/php\" {} \"$@\"", sh_quote(composer)),
+        ("composer", None) => {
+            "echo \"localhost-manager: composer was not found on PATH\" >&2\nexit 127".to_string()
+        }
+        _ => format!(
+            "if [ ! -x \"$bin/{tool}\" ]; then\n    echo \"localhost-manager: {tool} is not available for PHP $branch\" >&2\n    exit 127\nfi\nexec \"$bin/{tool}\" \"$@\"",
+            tool = tool
+        ),
+    };
+
+    format!(
+        r#"#!/bin/sh
+# Generated by Localhost Manager; rewritten on every version switch.
+shims={shims}
+
+version=
+dir="$PWD"
+while :; do
+    if [ -f "$dir/{version_file}" ]; then
+        version=$(head -n 1 "$dir/{version_file}" | tr -d ' \r')
+        break
+    fi
+    [ "$dir" = "/" ] && break
+    dir=$(dirname "$dir")
+done
+[ -n "$version" ] || version=$(cat "$shims/version" 2>/dev/null)
+
+branch=$(echo "$version" | sed 's/^php-*//' | cut -d. -f1,2)
+bin="$shims/versions/$branch"
+if [ -z "$branch" ] || [ ! -d "$bin" ]; then
+    echo "localhost-manager: PHP ${{version:-(none)}} is not installed" >&2
+    exit 127
+fi
+{run}
+"#,
+        shims = sh_quote(shims),
+        version_file = VERSION_FILE,
+        run = run
+    )
+}
+
+#[cfg(target_os = "windows")]
+fn shim_script(shims: &Path, tool: &str, composer: Option<&Path>) -> String {
+    let run = match (tool, composer) {
+        ("composer", Some(composer)) => format!(
+            "call \"%shims%\\versions\\%branch%\\php.cmd\" \"{}\" %*",
+            composer.display()
+        ),
+        ("composer", None) => {
+            "echo localhost-manager: composer.phar was not found on PATH 1>&2\r\nexit /b 127"
+                .to_string()
+        }
+        _ => "call \"%shims%\\versions\\%branch%\\php.cmd\" %*".to_string(),
+    };
+
+    format!(
+        "@echo off\r\n\
+         rem Generated by Localhost Manager; rewritten on every version switch.\r\n\
+         setlocal\r\n\
+         set \"shims={shims}\"\r\n\
+         set \"version=\"\r\n\
+         set \"dir=%CD%\"\r\n\
+         :search\r\n\
+         if exist \"%dir%\\{version_file}\" (\r\n\
+         \x20   set /p version=<\"%dir%\\{version_file}\"\r\n\
+         \x20   goto resolved\r\n\
+         )\r\n\
+         for %%I in (\"%dir%\\..\") do set \"parent=%%~fI\"\r\n\
+         if /i \"%parent%\"==\"%dir%\" goto resolved\r\n\
+         set \"dir=%parent%\"\r\n\
+         goto search\r\n\
+         :resolved\r\n\
+         if not defined version set /p version=<\"%shims%\\version\"\r\n\
+         for /f \"tokens=1,2 delims=.\" %%a in (\"%version%\") do set \"branch=%%a.%%b\"\r\n\
+         if not exist \"%shims%\\versions\\%branch%\\php.cmd\" (\r\n\
+         \x20   echo localhost-manager: PHP %version% is not installed 1>&2\r\n\
+         \x20   exit /b 127\r\n\
+         )\r\n\
+         {run}\r\n\
+         exit /b %errorlevel%\r\n",
+        shims = shims.display(),
+        version_file = VERSION_FILE,
+        run = run
+    )
+}
+
+/// Link one installed branch into `versions/<major.minor>/`
+#[cfg(unix)]
+fn link_version(target: &Path, php: &PhpVersion) -> std::io::Result<()> {
+    let Some(binary) = php.binary.as_deref().map(PathBuf::from) else {
+        return Ok(());
+    };
+    std::fs::create_dir_all(target)?;
+    std::os::unix::fs::symlink(&binary, target.join("php"))?;
+
+    for tool in ["phpize", "php-config"] {
+        if let Some(path) = php_discovery::sibling_binary(&binary, tool) {
+            std::os::unix::fs::symlink(path, target.join(tool))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Symlinks need elevated rights on Windows, so branches get a forwarding script
+#[cfg(target_os = "windows")]
+fn link_version(target: &Path, php: &PhpVersion) -> std::io::Result<()> {
+    let Some(binary) = php.binary.as_deref() else {
+        return Ok(());
+    };
+    std::fs::create_dir_all(target)?;
+    std::fs::write(target.join("php.cmd"), format!("@\"{}\" %*\r\n", binary))
+}
+
+fn write_shim(path: &Path, content: &str) -> std::io::Result<()> {
+    std::fs::write(path, content)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))?;
+    }
+
+    Ok(())
+}
+
+/// Recreate the shim directory for the currently installed versions
+pub fn rebuild_shims(installed: &[PhpVersion], global: Option<&str>) -> Result<(), String> {
+    let shims = shims_dir();
+    let versions_dir = shims.join("versions");
+
+    // Link into a staging directory and swap it in, so running shims never
+    // see `versions/` missing or half-built
+    let staging = shims.join("versions.new");
+    let retired = shims.join("versions.old");
+    let _ = std::fs::remove_dir_all(&staging);
+    let _ = std::fs::remove_dir_all(&retired);
+    std::fs::create_dir_all(&staging)
+        .map_err(|e| format!("Failed to create {}: {}", staging.display(), e))?;
+
+    // `installed` is newest first, so each branch links its newest build
+    for php in installed {
+        let target = staging.join(php.branch());
+        if target.exists() {
+            continue;
+        }
+        if let Err(e) = link_version(&target, php) {
+            let _ = std::fs::remove_dir_all(&staging);
+            return Err(format!("Failed to link PHP {}: {}", php.version, e));
+        }
+    }
+
+    // rename() won't replace a non-empty directory, so the old one steps aside first
+    if versions_dir.exists() {
+        std::fs::rename(&versions_dir, &retired)
+            .map_err(|e| format!("Failed to replace {}: {}", versions_dir.display(), e))?;
+    }
+    if let Err(e) = std::fs::rename(&staging, &versions_dir) {
+        let _ = std::fs::rename(&retired, &versions_dir);
+        return Err(format!(
+            "Failed to replace {}: {}",
+            versions_dir.display(),
+            e
+        ));
+    }
+    let _ = std::fs::remove_dir_all(&retired);
+
+    let composer = find_composer();
+    let tools: &[&str] = if cfg!(target_os = "windows") {
+        &["php", "composer"]
+    } else {
+        &["php", "phpize", "php-config", "composer"]
+    };
+
+    for tool in tools {
+        let file_name = if cfg!(target_os = "windows") {
+            format!("{}.cmd", tool)
+        } else {
+            tool.to_string()
+        };
+        let path = shims.join(file_name);
+        write_shim(&path, &shim_script(&shims, tool, composer.as_deref()))
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    }
+
+    let global = global.map(branch).unwrap_or_default();
+    std::fs::write(shims.join("version"), format!("{}\n", global))
+        .map_err(|e| format!("Failed to write global version: {}", e))?;
+
+    Ok(())
+}
+
+/// Global branch the shims were last built with
+fn built_global() -> String {
+    std::fs::read_to_string(shims_dir().join("version"))
+        .map(|v| v.trim().to_string())
+        .unwrap_or_default()
+}
+
+/// Rebuild the shims in the background, reporting failures to the UI
+fn refresh(app: &AppHandle, global: Option<String>) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        if let Err(e) = rebuild_shims(&php_discovery::installed_versions(), global.as_deref()) {
+            let _ = app.emit(
+                PHP_SHIMS_EVENT,
+                PhpShimsEvent {
+                    stage: "error".to_string(),
+                    message: format!("Failed to refresh PHP shims: {}", e),
+                },
+            );
+        }
+    });
+}
+
+/// Refresh existing shims on startup so they follow installs and removals,
+/// and again whenever `defaultPhpVersion` changes in settings
+pub fn init(app: &AppHandle) {
+    if shims_dir().exists() {
+        refresh(
+            app,
+            Settings::load().ok().and_then(|s| s.default_php_version),
+        );
+    }
+
+    let handle = app.clone();
+    app.listen(SETTINGS_CHANGED_EVENT, move |event| {
+        let Ok(settings) = serde_json::from_str::<Settings>(event.payload()) else {
+            return;
+        };
+
+        let global = settings
+            .default_php_version
+            .as_deref()
+            .map(branch)
+            .unwrap_or_default();
+        // use_php_version already rebuilt them; so did an earlier event
+        if !shims_dir().exists() || built_global() == global {
+            return;
+        }
+
+        refresh(&handle, settings.default_php_version);
+    });
+}
+
+/// Select the CLI PHP version, globally or for `directory` via `.php-version`
+#[tauri::command]
+pub async fn use_php_version(
+    app: AppHandle,
+    version: String,
+    directory: Option<String>,
+) -> Result<ActivePhp, String> {
+    let installed = tauri::async_runtime::spawn_blocking(php_discovery::installed_versions)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if select(&installed, &version).is_none() {
+        return Err(format!("PHP {} is not installed", version));
+    }
+
+    let mut settings = Settings::load().map_err(|e| format!("Failed to load settings: {}", e))?;
+
+    match directory.as_deref() {
+        Some(dir) => {
+            let dir = Path::new(dir);
+            if !dir.is_dir() {
+                return Err(format!("Directory not found: {}", dir.display()));
+            }
+            let file = dir.join(VERSION_FILE);
+            std::fs::write(&file, format!("{}\n", version.trim()))
+                .map_err(|e| format!("Failed to write {}: {}", file.display(), e))?;
+        }
+        None => {
+            settings.default_php_version = Some(version.trim().to_string());
+            settings
+                .save()
+                .map_err(|e| format!("Failed to save settings: {}", e))?;
+            let _ = app.emit(SETTINGS_CHANGED_EVENT, &settings);
+        }
+    }
+
+    rebuild_shims(&installed, settings.default_php_version.as_deref())?;
+
+    active(&installed, directory.as_deref().map(Path::new))
+        .ok_or_else(|| format!("PHP {} is not installed", version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn php(version: &str) -> PhpVersion {
+        let parts: Vec<u8> = version.split('.').map(|p| p.parse().unwrap()).collect();
+        PhpVersion {
+            version: version.to_string(),
+            major: parts[0],
+            minor: parts[1],
+            patch: parts[2],
+            installed: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn branch_strips_prefixes_and_patch() {
+        for (raw, expected) in [
+            ("8.3.14", "8.3"),
+            ("8.3", "8.3"),
+            ("php-8.2", "8.2"),
+            ("php8.1.0", "8.1"),
+            (" 7.4.33\n", "7.4"),
+            ("8", "8"),
+            ("", ""),
+        ] {
+            assert_eq!(branch(raw), expected, "{:?}", raw);
+        }
+    }
+
+    #[test]
+    fn select_prefers_an_exact_build_then_the_newest_of_the_branch() {
+        // Discovery lists newest first
+        let installed = [php("8.3.14"), php("8.3.2"), php("8.2.20")];
+
+        assert_eq!(select(&installed, "8.3.2").unwrap().version, "8.3.2");
+        assert_eq!(select(&installed, "8.3").unwrap().version, "8.3.14");
+        assert_eq!(select(&installed, "8.3.99").unwrap().version, "8.3.14");
+        assert_eq!(select(&installed, " php-8.2 ").unwrap().version, "8.2.20");
+        assert!(select(&installed, "7.4").is_none());
+        assert!(select(&[], "8.3").is_none());
+    }
+
+    #[test]
+    fn local_version_uses_the_nearest_file_above_the_directory() {
+        let root = std::env::temp_dir().join(format!("php-switcher-test-{}", uuid::Uuid::new_v4()));
+        let nested = root.join("project").join("src").join("deep");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        std::fs::write(root.join(VERSION_FILE), "8.1\n").unwrap();
+        let found = local_version(&nested);
+
+        std::fs::write(
+            root.join("project").join(VERSION_FILE),
+            "  8.3.4  \n# comment\n",
+        )
+        .unwrap();
+        let nearer = local_version(&nested);
+
+        // An empty file pins nothing, so the search goes on upwards
+        std::fs::write(nested.join(VERSION_FILE), "\n").unwrap();
+        let empty = local_version(&nested);
+
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert_eq!(found, Some(("8.1".to_string(), root.join(VERSION_FILE))));
+        let project_file = root.join("project").join(VERSION_FILE);
+        assert_eq!(nearer, Some(("8.3.4".to_string(), project_file.clone())));
+        assert_eq!(empty, Some(("8.3.4".to_string(), project_file)));
+    }
+}
@@ -0,0 +1,580 @@
+// ============================================
+// Project Discovery
+// ============================================
+// Scans the projects directory for folders that can be served and proposes
+// a `<folder>.test` host for each, using the project inspector for the
+// docroot, type and template. Projects are looked for one level down, and
+// two levels down inside plain folders (`~/Sites/client-a/shop`), which then
+// become the host's group.
+//
+// "Park" keeps watching the projects directory and registers folders that
+// appear after it started; folders that were already there (imported or
+// not) are left to the scan, so removing a host doesn't bring it back.
+// Folders a scaffold is still creating are skipped: it registers them itself.
+
+use crate::hosts_manager::{add_virtual_hosts, load_virtual_hosts, regenerate_and_apply};
+use crate::paths::Layout;
+use crate::php_discovery;
+use crate::project_inspector;
+use crate::project_scaffold;
+use crate::settings::{Settings, SETTINGS_CHANGED_EVENT};
+use crate::types::{PhpVersion, VirtualHost};
+use notify_debouncer_mini::notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, Debouncer};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Listener, Manager, State};
+
+/// Event name used to report parked registrations to the UI
+pub const PROJECTS_PARK_EVENT: &str = "projects-park";
+
+/// Top-level domain for discovered hosts
+const DISCOVERED_TLD: &str = "test";
+
+/// Folders that hold dependencies or tooling, never projects
+const SKIPPED_DIRS: &[&str] = &["node_modules", "vendor"];
+
+/// Wait for a clone or an unpack to settle before inspecting the new folder
+const DEBOUNCE_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscoveredHost {
+    /// `<folder>.test`, or `<folder>-<group>.test` when the name is taken
+    pub domain: String,
+    /// Project root
+    pub directory: String,
+    pub docroot: String,
+    /// Parent folder name
+    pub group: String,
+    pub host_type: String,
+    pub framework: Option<String>,
+    pub template: String,
+    pub php_version: Option<String>,
+    /// A host already serves this docroot or uses this domain
+    pub registered: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectsParkEvent {
+    stage: String,
+    message: String,
+}
+
+/// Managed state holding the park watcher, if any, and the directory it watches
+#[derive(Default)]
+pub struct ProjectsPark {
+    debouncer: Mutex<Option<(PathBuf, Debouncer<RecommendedWatcher>)>>,
+}
+
+fn emit_progress(app: &AppHandle, stage: &str, message: impl Into<String>) {
+    let _ = app.emit(
+        PROJECTS_PARK_EVENT,
+        ProjectsParkEvent {
+            stage: stage.to_string(),
+            message: message.into(),
+        },
+    );
+}
+
+fn projects_dir() -> Result<PathBuf, String> {
+    Layout::resolve()
+        .projects_dir
+        .filter(|dir| dir.is_dir())
+        .ok_or_else(|| "No projects directory configured".to_string())
+}
+
+fn subdirectories(dir: &Path) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| path.is_dir())
+                .filter(|path| {
+                    let name = path
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_default();
+                    !name.starts_with('.') && !SKIPPED_DIRS.contains(&name.as_str())
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    dirs.sort();
+    dirs
+}
+
+fn folder_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Hostname label for a folder name: `My_App` becomes `my-app`
+fn slug(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_matches('-').to_string()
+}
+
+/// Every servable project under `root`, with the name of its parent folder.
+/// Installed PHP versions are discovered once by the caller: inspecting
+/// each folder on its own would run every PHP binary once per project.
+fn find_projects(
+    root: &Path,
+    installed: &[PhpVersion],
+) -> Vec<(project_inspector::ProjectInspection, String)> {
+    let inspect = |dir: &Path| {
+        project_inspector::inspect_with(dir, installed)
+            .ok()
+            .filter(|inspection| inspection.framework.is_some())
+    };
+
+    let mut found = Vec::new();
+    for dir in subdirectories(root) {
+        if project_scaffold::is_scaffolding(&dir) {
+            continue;
+        }
+        match inspect(&dir) {
+            Some(inspection) => found.push((inspection, folder_name(root))),
+            // A plain folder grouping projects
+            None => {
+                for child in subdirectories(&dir) {
+                    if let Some(inspection) = inspect(&child) {
+                        found.push((inspection, folder_name(&dir)));
+                    }
+                }
+            }
+        }
+    }
+    found
+}
+
+/// Folders one and two levels under `root`, where projects and their groups live
+fn project_dirs(root: &Path) -> HashSet<PathBuf> {
+    subdirectories(root)
+        .into_iter()
+        .flat_map(|dir| {
+            let children = subdirectories(&dir);
+            std::iter::once(dir).chain(children)
+        })
+        .collect()
+}
+
+/// Propose hosts for every project under the projects directory
+pub fn scan() -> Result<Vec<DiscoveredHost>, String> {
+    scan_in(&projects_dir()?, &php_discovery::installed_versions())
+}
+
+fn scan_in(root: &Path, installed: &[PhpVersion]) -> Result<Vec<DiscoveredHost>, String> {
+    Ok(discover(root, &load_virtual_hosts()?, installed))
+}
+
+/// Hosts for the projects under `root`, next to the `existing` ones
+fn discover(
+    root: &Path,
+    existing: &HashMap<String, VirtualHost>,
+    installed: &[PhpVersion],
+) -> Vec<DiscoveredHost> {
+    let docroots: HashSet<&str> = existing.values().map(|h| h.docroot.as_str()).collect();
+    let mut taken: HashSet<String> = existing.keys().cloned().collect();
+
+    let mut discovered = Vec::new();
+    for (inspection, group) in find_projects(root, installed) {
+        let name = slug(&folder_name(Path::new(&inspection.directory)));
+        if name.is_empty() {
+            continue;
+        }
+
+        let registered = docroots.contains(inspection.docroot.as_str());
+        let mut domain = format!("{}.{}", name, DISCOVERED_TLD);
+        if !registered && taken.contains(&domain) {
+            domain = format!("{}-{}.{}", name, slug(&group), DISCOVERED_TLD);
+        }
+        let registered = registered || existing.contains_key(&domain);
+        taken.insert(domain.clone());
+
+        discovered.push(DiscoveredHost {
+            domain,
+            directory: inspection.directory,
+            docroot: inspection.docroot,
+            group,
+            host_type: inspection.host_type,
+            framework: inspection.framework,
+            template: inspection.template,
+            php_version: inspection.php_version,
+            registered,
+        });
+    }
+
+    discovered
+}
+
+fn to_host(discovered: DiscoveredHost) -> VirtualHost {
+    VirtualHost {
+        domain: discovered.domain,
+        docroot: discovered.docroot,
+        aliases: Vec::new(),
+        group: discovered.group,
+        active: true,
+        ssl: true,
+        host_type: discovered.host_type,
+        profile: None,
+        fpm_pool: None,
+        framework: discovered.framework,
+        rewrites: Vec::new(),
+        template: Some(discovered.template),
+        wildcard: false,
+        subdomains: Vec::new(),
+    }
+}
+
+/// Register discovered hosts and apply the configuration once
+fn import(hosts: Vec<DiscoveredHost>) -> Result<usize, String> {
+    let added = add_virtual_hosts(hosts.into_iter().map(to_host).collect())?;
+    if added > 0 {
+        regenerate_and_apply()?;
+    }
+    Ok(added)
+}
+
+/// What the park keeps between watcher events
+struct ParkState {
+    root: PathBuf,
+    /// Project directories seen so far, registered or not
+    known: HashSet<String>,
+    /// Folders one and two levels down at the last look
+    dirs: HashSet<PathBuf>,
+    /// Folders created while parked that hold no project yet; files landing
+    /// in them (a clone or an unpack in progress) trigger another scan
+    pending: HashSet<PathBuf>,
+    /// Discovered once when parking starts; running every PHP binary on each
+    /// event would be slow
+    installed: Vec<PhpVersion>,
+}
+
+impl ParkState {
+    fn new(root: PathBuf) -> Result<Self, String> {
+        let installed = php_discovery::installed_versions();
+        // Only folders created from now on are registered automatically
+        let known = scan_in(&root, &installed)?
+            .into_iter()
+            .map(|host| host.directory)
+            .collect();
+        let dirs = project_dirs(&root);
+
+        Ok(Self {
+            root,
+            known,
+            dirs,
+            pending: HashSet::new(),
+            installed,
+        })
+    }
+
+    /// Scan again when a folder was created or removed one or two levels
+    /// down, or something changed inside a pending folder
+    fn on_events(&mut self, app: &AppHandle, paths: &[PathBuf]) {
+        let depth = |path: &Path| {
+            path.strip_prefix(&self.root)
+                .map(|rel| rel.components().count())
+                .unwrap_or(0)
+        };
+        let shallow = paths.iter().any(|p| (1..=2).contains(&depth(p)));
+        let in_pending = paths
+            .iter()
+            .any(|p| self.pending.iter().any(|dir| p.starts_with(dir)));
+        if !shallow && !in_pending {
+            return;
+        }
+
+        let dirs = project_dirs(&self.root);
+        if dirs == self.dirs && !in_pending {
+            // Files changing in a project root or a group folder
+            return;
+        }
+        self.pending.extend(dirs.difference(&self.dirs).cloned());
+        self.pending.retain(|dir| dirs.contains(dir));
+        self.dirs = dirs;
+
+        self.register_new(app);
+
+        let known = &self.known;
+        self.pending.retain(|dir| {
+            !known
+                .iter()
+                .any(|project| Path::new(project).starts_with(dir))
+        });
+    }
+
+    /// Register projects that weren't there when parking started
+    fn register_new(&mut self, app: &AppHandle) {
+        let discovered = match scan_in(&self.root, &self.installed) {
+            Ok(discovered) => discovered,
+            Err(e) => {
+                emit_progress(app, "error", e);
+                return;
+            }
+        };
+
+        let new: Vec<DiscoveredHost> = discovered
+            .into_iter()
+            .filter(|host| self.known.insert(host.directory.clone()) && !host.registered)
+            .collect();
+        if new.is_empty() {
+            return;
+        }
+
+        let domains: Vec<String> = new.iter().map(|host| host.domain.clone()).collect();
+        emit_progress(
+            app,
+            "registering",
+            format!("Registering {}...", domains.join(", ")),
+        );
+        match import(new) {
+            Ok(_) => emit_progress(app, "done", format!("Registered {}", domains.join(", "))),
+            Err(e) => emit_progress(app, "error", e),
+        }
+    }
+}
+
+impl ProjectsPark {
+    /// Start watching the projects directory. A no-op when it's already
+    /// watched; a park on a previous projects directory moves to the new one.
+    pub fn start(&self, app: &AppHandle) -> Result<(), String> {
+        let mut guard = self
+            .debouncer
+            .lock()
+            .map_err(|_| "Park state poisoned".to_string())?;
+
+        // Drop a park on a directory that is no longer the projects directory
+        let root = projects_dir().inspect_err(|_| *guard = None)?;
+        if guard.as_ref().is_some_and(|(parked, _)| *parked == root) {
+            return Ok(());
+        }
+        *guard = None;
+
+        let mut state = ParkState::new(root.clone())?;
+
+        // Recursive, so files landing in a freshly created folder are seen too
+        let handler_app = app.clone();
+        let mut debouncer =
+            new_debouncer(
+                DEBOUNCE_TIMEOUT,
+                move |result: DebounceEventResult| match result {
+                    Ok(events) => {
+                        let paths: Vec<PathBuf> = events.into_iter().map(|e| e.path).collect();
+                        state.on_events(&handler_app, &paths);
+                    }
+                    Err(e) => emit_progress(&handler_app, "error", format!("Watcher error: {}", e)),
+                },
+            )
+            .map_err(|e| format!("Failed to create watcher: {}", e))?;
+
+        debouncer
+            .watcher()
+            .watch(&root, RecursiveMode::Recursive)
+            .map_err(|e| format!("Failed to watch {}: {}", root.display(), e))?;
+
+        emit_progress(app, "started", format!("Parked {}", root.display()));
+        *guard = Some((root, debouncer));
+
+        Ok(())
+    }
+
+    pub fn stop(&self, app: &AppHandle) -> Result<(), String> {
+        let mut guard = self
+            .debouncer
+            .lock()
+            .map_err(|_| "Park state poisoned".to_string())?;
+
+        if guard.take().is_some() {
+            emit_progress(app, "stopped", "Stopped watching the projects directory");
+        }
+
+        Ok(())
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.debouncer
+            .lock()
+            .map(|guard| guard.is_some())
+            .unwrap_or(false)
+    }
+}
+
+/// Start or stop parking on a background thread: starting scans the whole
+/// projects directory first
+fn follow_setting(app: &AppHandle, park_projects: bool) {
+    let handle = app.clone();
+    std::thread::spawn(move || {
+        let park = handle.state::<ProjectsPark>();
+        let result = if park_projects {
+            park.start(&handle)
+        } else {
+            park.stop(&handle)
+        };
+
+        if let Err(e) = result {
+            emit_progress(&handle, "error", e);
+        }
+    });
+}
+
+/// Park the projects directory if the user enabled it, and follow later
+/// changes of the `parkProjects` and `projectsPath` settings without
+/// requiring a restart
+pub fn init(app: &AppHandle) {
+    if Settings::load().map(|s| s.park_projects).unwrap_or(false) {
+        follow_setting(app, true);
+    }
+
+    let handle = app.clone();
+    app.listen(SETTINGS_CHANGED_EVENT, move |event| {
+        let Ok(settings) = serde_json::from_str::<Settings>(event.payload()) else {
+            return;
+        };
+        follow_setting(&handle, settings.park_projects);
+    });
+}
+
+/// Propose a `<folder>.test` host for every project under the projects directory
+#[tauri::command]
+pub async fn scan_projects() -> Result<Vec<DiscoveredHost>, String> {
+    tauri::async_runtime::spawn_blocking(scan)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Register the chosen discovered hosts and apply the configuration
+#[tauri::command]
+pub async fn import_projects(hosts: Vec<DiscoveredHost>) -> Result<usize, String> {
+    tauri::async_runtime::spawn_blocking(move || import(hosts))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Whether new project folders are being registered automatically
+#[tauri::command]
+pub async fn is_projects_park_running(state: State<'_, ProjectsPark>) -> Result<bool, String> {
+    Ok(state.is_running())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(root: &Path, rel: &str) -> String {
+        let dir = root.join(rel);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("index.html"), "<h1>hi</h1>").unwrap();
+        dir.to_string_lossy().to_string()
+    }
+
+    fn host(domain: &str, docroot: &str) -> VirtualHost {
+        VirtualHost {
+            domain: domain.to_string(),
+            docroot: docroot.to_string(),
+            aliases: Vec::new(),
+            group: String::new(),
+            active: true,
+            ssl: true,
+            host_type: "static".to_string(),
+            profile: None,
+            fpm_pool: None,
+            framework: None,
+            rewrites: Vec::new(),
+            template: None,
+            wildcard: false,
+            subdomains: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn slugs_are_lowercase_hyphenated_labels() {
+        for (name, expected) in [
+            ("My_App", "my-app"),
+            ("shop", "shop"),
+            ("Client  Site (v2)", "client-site-v2"),
+            ("--edge--", "edge"),
+            ("café", "caf"),
+            ("___", ""),
+        ] {
+            assert_eq!(slug(name), expected, "{:?}", name);
+        }
+    }
+
+    #[test]
+    fn discovers_projects_with_groups_and_free_domains() {
+        let root = std::env::temp_dir().join(format!("discovery-test-{}", uuid::Uuid::new_v4()));
+        let shop = project(&root, "shop");
+        let my_app = project(&root, "client-a/My_App");
+        let other_shop = project(&root, "client-b/shop");
+        let blog = project(&root, "blog");
+        project(&root, "client-a/node_modules/pkg");
+        project(&root, ".cache/site");
+        // Nothing servable three levels down
+        project(&root, "client-a/nested/deep/site");
+        std::fs::create_dir_all(root.join("empty")).unwrap();
+
+        let existing = HashMap::from([
+            ("blog.test".to_string(), host("blog.test", &blog)),
+            ("taken.test".to_string(), host("taken.test", "/elsewhere")),
+        ]);
+        let found = discover(&root, &existing, &[]);
+        let root_group = folder_name(&root);
+        let _ = std::fs::remove_dir_all(&root);
+
+        let renamed = format!("shop-{}.test", slug(&root_group));
+        let summary: Vec<(&str, &str, &str, bool)> = found
+            .iter()
+            .map(|h| {
+                (
+                    h.domain.as_str(),
+                    h.directory.as_str(),
+                    h.group.as_str(),
+                    h.registered,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("blog.test", blog.as_str(), root_group.as_str(), true),
+                ("my-app.test", my_app.as_str(), "client-a", false),
+                // Folders are walked in path order, so client-b/shop claims the
+                // plain name and the group tells the other one apart
+                ("shop.test", other_shop.as_str(), "client-b", false),
+                (renamed.as_str(), shop.as_str(), root_group.as_str(), false),
+            ]
+        );
+        assert!(found
+            .iter()
+            .all(|h| h.framework.as_deref() == Some("static")));
+    }
+
+    #[test]
+    fn a_domain_used_by_another_docroot_counts_as_taken() {
+        let root = std::env::temp_dir().join(format!("discovery-test-{}", uuid::Uuid::new_v4()));
+        let taken = project(&root, "group/taken");
+
+        let existing =
+            HashMap::from([("taken.test".to_string(), host("taken.test", "/elsewhere"))]);
+        let found = discover(&root, &existing, &[]);
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].domain, "taken-group.test");
+        assert_eq!(found[0].directory, taken);
+        assert!(!found[0].registered);
+    }
+}
@@ -0,0 +1,766 @@
+// ============================================
+// Project Inspector
+// ============================================
+// Looks at a project folder and proposes how to serve it: the framework,
+// the docroot inside it (`public/`, `web/`, a build directory), the host
+// type, the PHP version it asks for, the vhost template that carries its
+// rewrite rules and the rules themselves. The result fills the "Add host"
+// form; nothing is written.
+//
+// Picking the public directory itself (`myapp/public`) works too: when the
+// folder isn't a project of its own, its parent is inspected and used if it
+// would serve this folder.
+
+use crate::composer;
+use crate::php_discovery;
+use crate::types::PhpVersion;
+use crate::vhost_templates;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Laravel, Symfony, Drupal and CodeIgniter route everything through index.php
+const FRONT_CONTROLLER: &[&str] = &[
+    "RewriteEngine On",
+    "RewriteCond %{REQUEST_FILENAME} !-f",
+    "RewriteCond %{REQUEST_FILENAME} !-d",
+    "RewriteRule ^ index.php [QSA,L]",
+];
+
+/// Rules WordPress writes into .htaccess for pretty permalinks
+const WORDPRESS: &[&str] = &[
+    "RewriteEngine On",
+    "RewriteRule .* - [E=HTTP_AUTHORIZATION:%{HTTP:Authorization}]",
+    "RewriteRule ^index\\.php$ - [L]",
+    "RewriteCond %{REQUEST_FILENAME} !-f",
+    "RewriteCond %{REQUEST_FILENAME} !-d",
+    "RewriteRule . /index.php [L]",
+];
+
+/// Client-side routing: unknown paths get the app shell
+const SPA: &[&str] = &["FallbackResource /index.html"];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectInspection {
+    /// The folder that was inspected (the project root)
+    pub directory: String,
+    /// Folder name, handy as the host name
+    pub name: String,
+    /// `laravel`, `symfony`, `wordpress`, `drupal`, `codeigniter`, `vite`,
+    /// `next`, `nuxt`, `php` or `static`; None when nothing matched
+    pub framework: Option<String>,
+    pub framework_version: Option<String>,
+    /// Directory the web server should serve
+    pub docroot: String,
+    /// `static`, `php`, `vue` or `react`
+    pub host_type: String,
+    /// Composer-style constraint the project declares
+    pub php_constraint: Option<String>,
+    /// Newest installed PHP satisfying the constraint
+    pub php_version: Option<String>,
+    /// Vhost template carrying the rules below (see `vhost_templates`)
+    pub template: String,
+    /// Apache directives for the docroot
+    pub rewrites: Vec<String>,
+    /// Things to do before the host works (build first, missing vendor/...)
+    pub notes: Vec<String>,
+}
+
+struct Detected {
+    framework: &'static str,
+    version: Option<String>,
+    docroot: PathBuf,
+    host_type: &'static str,
+    php_constraint: Option<String>,
+    /// Overrides the framework's default template
+    template: Option<&'static str>,
+    rewrites: &'static [&'static str],
+    notes: Vec<String>,
+}
+
+impl Detected {
+    fn php(framework: &'static str, docroot: PathBuf, rewrites: &'static [&'static str]) -> Self {
+        Detected {
+            framework,
+            version: None,
+            docroot,
+            host_type: "php",
+            php_constraint: None,
+            template: None,
+            rewrites,
+            notes: Vec::new(),
+        }
+    }
+}
+
+fn read_json(path: &Path) -> Option<serde_json::Value> {
+    serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()
+}
+
+/// Whether composer.json requires `package` (in require or require-dev)
+fn requires(manifest: &Option<serde_json::Value>, package: &str) -> bool {
+    manifest.as_ref().is_some_and(|m| {
+        ["require", "require-dev"]
+            .iter()
+            .any(|section| m.get(section).and_then(|r| r.get(package)).is_some())
+    })
+}
+
+/// Installed version of a package, from composer.lock
+fn locked_version(dir: &Path, package: &str) -> Option<String> {
+    read_json(&dir.join("composer.lock"))?
+        .get("packages")?
+        .as_array()?
+        .iter()
+        .find(|p| p.get("name").and_then(|n| n.as_str()) == Some(package))?
+        .get("version")?
+        .as_str()
+        .map(|v| v.trim_start_matches('v').to_string())
+}
+
+/// Value of a PHP assignment or constant: `$wp_version = '6.5.2';`,
+/// `const VERSION = '10.2.4';`, `define('CI_VERSION', '3.1.13');`
+fn php_literal(file: &Path, name: &str) -> Option<String> {
+    let content = std::fs::read_to_string(file).ok()?;
+    let define = format!("define('{}',", name);
+    content.lines().find_map(|line| {
+        let line = line.trim();
+        let value = match line.strip_prefix(&define) {
+            Some(rest) => rest.trim_end_matches(';').trim_end_matches(')'),
+            None => {
+                let (left, right) = line.split_once('=')?;
+                if left.split_whitespace().last() != Some(name) {
+                    return None;
+                }
+                right.trim_end_matches(';')
+            }
+        };
+        Some(value.trim().trim_matches(['\'', '"']).to_string())
+    })
+}
+
+/// Whether wp-config.php turns a constant on: `define('NAME', true)`
+fn wp_flag(config: &Path, name: &str) -> bool {
+    let Ok(content) = std::fs::read_to_string(config) else {
+        return false;
+    };
+    let compact: String = content
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_lowercase();
+    let name = name.to_lowercase();
+    [
+        format!("define('{}',true)", name),
+        format!("define(\"{}\",true)", name),
+    ]
+    .iter()
+    .any(|needle| compact.contains(needle.as_str()))
+}
+
+/// Version of a dependency in package.json, without its range operator
+fn package_version(package: &Option<serde_json::Value>, name: &str) -> Option<String> {
+    let package = package.as_ref()?;
+    ["dependencies", "devDependencies"]
+        .iter()
+        .find_map(|section| {
+            let version = package.get(section)?.get(name)?.as_str()?;
+            Some(
+                version
+                    .trim_start_matches(['^', '~', '>', '=', ' '])
+                    .to_string(),
+            )
+        })
+}
+
+fn has_dependency(package: &Option<serde_json::Value>, name: &str) -> bool {
+    package_version(package, name).is_some()
+}
+
+fn detect_php(dir: &Path) -> Option<Detected> {
+    let manifest = read_json(&dir.join("composer.json"));
+
+    if dir.join("artisan").is_file() && requires(&manifest, "laravel/framework") {
+        let mut detected = Detected::php("laravel", dir.join("public"), FRONT_CONTROLLER);
+        detected.version = locked_version(dir, "laravel/framework");
+        return Some(detected);
+    }
+
+    if dir.join("bin/console").is_file()
+        && (requires(&manifest, "symfony/framework-bundle")
+            || dir.join("config/bundles.php").is_file())
+    {
+        // Symfony 2/3 served web/ through app.php
+        let docroot = if dir.join("web/app.php").is_file() {
+            dir.join("web")
+        } else {
+            dir.join("public")
+        };
+        let mut detected = Detected::php("symfony", docroot, FRONT_CONTROLLER);
+        detected.version = locked_version(dir, "symfony/framework-bundle");
+        return Some(detected);
+    }
+
+    // Bedrock keeps WordPress in web/wp and wp-config.php in web/
+    for docroot in [dir.to_path_buf(), dir.join("web")] {
+        let core = [docroot.join("wp-includes"), docroot.join("wp/wp-includes")]
+            .into_iter()
+            .find(|d| d.is_dir());
+        if let Some(core) = core {
+            let mut detected = Detected::php("wordpress", docroot.clone(), WORDPRESS);
+            detected.version = php_literal(&core.join("version.php"), "$wp_version");
+            detected.php_constraint =
+                php_literal(&core.join("version.php"), "$required_php_version")
+                    .map(|v| format!(">={}", v));
+            let config = docroot.join("wp-config.php");
+            if !config.is_file() {
+                detected
+                    .notes
+                    .push("No wp-config.php yet; the installer will create it".to_string());
+            }
+            // Subdirectory networks need their own rules; subdomain ones don't
+            if wp_flag(&config, "MULTISITE") && !wp_flag(&config, "SUBDOMAIN_INSTALL") {
+                detected.template = Some("wordpress-multisite");
+            }
+            return Some(detected);
+        }
+    }
+
+    // Composer-based Drupal serves web/; tarball installs serve the root
+    for docroot in [dir.join("web"), dir.join("docroot"), dir.to_path_buf()] {
+        let core = docroot.join("core/lib/Drupal.php");
+        if core.is_file() {
+            let mut detected = Detected::php("drupal", docroot, FRONT_CONTROLLER);
+            detected.version = php_literal(&core, "VERSION");
+            return Some(detected);
+        }
+    }
+
+    // CodeIgniter 4 has spark and public/; CodeIgniter 3 serves its root
+    if dir.join("spark").is_file() && dir.join("app/Config/App.php").is_file() {
+        let mut detected = Detected::php("codeigniter", dir.join("public"), FRONT_CONTROLLER);
+        detected.version = locked_version(dir, "codeigniter4/framework")
+            .or_else(|| php_literal(&dir.join("system/CodeIgniter.php"), "CI_VERSION"));
+        return Some(detected);
+    }
+    let ci3 = dir.join("system/core/CodeIgniter.php");
+    if ci3.is_file() {
+        let mut detected = Detected::php("codeigniter", dir.to_path_buf(), FRONT_CONTROLLER);
+        detected.version = php_literal(&ci3, "CI_VERSION");
+        return Some(detected);
+    }
+
+    // Anything else with PHP: serve public/ when it holds the entry point
+    let has_php = |d: &Path| d.join("index.php").is_file();
+    if manifest.is_some() || has_php(dir) || has_php(&dir.join("public")) {
+        let docroot = if has_php(&dir.join("public")) {
+            dir.join("public")
+        } else {
+            dir.to_path_buf()
+        };
+        let routed = manifest.is_some() && has_php(&docroot);
+        let mut detected =
+            Detected::php("php", docroot, if routed { FRONT_CONTROLLER } else { &[] });
+        if routed {
+            detected.template = Some("front-controller");
+        }
+        return Some(detected);
+    }
+
+    None
+}
+
+/// Node apps are served from their static build
+fn detect_node(dir: &Path) -> Option<Detected> {
+    let package = read_json(&dir.join("package.json"));
+    package.as_ref()?;
+
+    let flavor = if has_dependency(&package, "react") {
+        "react"
+    } else if has_dependency(&package, "vue") {
+        "vue"
+    } else {
+        "static"
+    };
+
+    let (framework, build_dir, build_hint, host_type, rewrites): (_, _, _, _, &[&str]) =
+        if has_dependency(&package, "next") {
+            // `output: 'export'` writes one HTML file per route; no fallback needed
+            (
+                "next",
+                "out",
+                "next build with output: 'export'",
+                "react",
+                &[],
+            )
+        } else if has_dependency(&package, "nuxt") {
+            let generated = if dir.join(".output/public").is_dir() || !dir.join("dist").is_dir() {
+                ".output/public"
+            } else {
+                "dist"
+            };
+            ("nuxt", generated, "nuxt generate", "vue", SPA)
+        } else if has_dependency(&package, "vite") {
+            ("vite", "dist", "vite build", flavor, SPA)
+        } else {
+            return None;
+        };
+
+    let docroot = dir.join(build_dir);
+    let mut notes = Vec::new();
+    if !docroot.is_dir() {
+        notes.push(format!(
+            "{} doesn't exist yet; run `{}` first",
+            build_dir, build_hint
+        ));
+    }
+
+    Some(Detected {
+        framework,
+        version: package_version(&package, framework),
+        docroot,
+        host_type,
+        php_constraint: None,
+        template: None,
+        rewrites,
+        notes,
+    })
+}
+
+fn detect(dir: &Path) -> Option<Detected> {
+    detect_php(dir).or_else(|| detect_node(dir)).or_else(|| {
+        dir.join("index.html").is_file().then(|| Detected {
+            framework: "static",
+            version: None,
+            docroot: dir.to_path_buf(),
+            host_type: "static",
+            php_constraint: None,
+            template: None,
+            rewrites: &[],
+            notes: Vec::new(),
+        })
+    })
+}
+
+/// Newest of the installed PHP versions matching a constraint
+fn best_php(constraint: &str, installed: &[PhpVersion]) -> Option<String> {
+    let mut matching: Vec<&PhpVersion> = installed
+        .iter()
+        .filter(|php| composer::satisfies(&php.version, constraint) == Some(true))
+        .collect();
+    matching.sort_by_key(|php| std::cmp::Reverse((php.major, php.minor)));
+    matching.first().map(|php| php.version.clone())
+}
+
+pub fn inspect(directory: &Path) -> Result<ProjectInspection, String> {
+    inspect_with(directory, &php_discovery::installed_versions())
+}
+
+/// `inspect` against an already discovered list of installed versions, for
+/// callers inspecting many folders at once
+pub fn inspect_with(
+    directory: &Path,
+    installed: &[PhpVersion],
+) -> Result<ProjectInspection, String> {
+    if !directory.is_dir() {
+        return Err(format!("Directory not found: {}", directory.display()));
+    }
+
+    // A picked public/ folder belongs to the project above it, even though
+    // its index.php alone would pass for plain PHP
+    let (root, detected) = match detect(directory) {
+        Some(detected) if !matches!(detected.framework, "static" | "php") => {
+            (directory.to_path_buf(), Some(detected))
+        }
+        fallback => match directory
+            .parent()
+            .and_then(|parent| Some((parent, detect(parent)?)))
+            .filter(|(_, parent)| parent.docroot == directory && parent.framework != "static")
+        {
+            Some((parent, detected)) => (parent.to_path_buf(), Some(detected)),
+            None => (directory.to_path_buf(), fallback),
+        },
+    };
+
+    let mut notes = Vec::new();
+    let php_constraint = detected
+        .as_ref()
+        .and_then(|d| d.php_constraint.clone())
+        .or_else(|| {
+            read_json(&root.join("composer.json"))?
+                .get("require")?
+                .get("php")?
+                .as_str()
+                .map(String::from)
+        });
+    let php_version = php_constraint
+        .as_deref()
+        .and_then(|constraint| best_php(constraint, installed));
+    if let (Some(constraint), None) = (&php_constraint, &php_version) {
+        notes.push(format!("No installed PHP version satisfies {}", constraint));
+    }
+    if root.join("composer.json").is_file() && !root.join("vendor/autoload.php").is_file() {
+        notes.push("Dependencies aren't installed; run composer install".to_string());
+    }
+
+    let name = root
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    Ok(match detected {
+        Some(detected) => {
+            notes.extend(detected.notes);
+            ProjectInspection {
+                directory: root.to_string_lossy().to_string(),
+                name,
+                framework: Some(detected.framework.to_string()),
+                framework_version: detected.version,
+                docroot: detected.docroot.to_string_lossy().to_string(),
+                host_type: detected.host_type.to_string(),
+                php_constraint,
+                php_version,
+                template: detected
+                    .template
+                    .unwrap_or_else(|| {
+                        vhost_templates::default_template(
+                            Some(detected.framework),
+                            detected.host_type,
+                        )
+                    })
+                    .to_string(),
+                rewrites: detected.rewrites.iter().map(|r| r.to_string()).collect(),
+                notes,
+            }
+        }
+        None => ProjectInspection {
+            directory: root.to_string_lossy().to_string(),
+            name,
+            framework: None,
+            framework_version: None,
+            docroot: root.to_string_lossy().to_string(),
+            host_type: "static".to_string(),
+            php_constraint,
+            php_version,
+            template: "static".to_string(),
+            rewrites: Vec::new(),
+            notes,
+        },
+    })
+}
+
+/// Propose docroot, host type, PHP version and template for a project folder
+#[tauri::command]
+pub async fn inspect_project(directory: String) -> Result<ProjectInspection, String> {
+    tauri::async_runtime::spawn_blocking(move || inspect(Path::new(&directory)))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn php(version: &str) -> PhpVersion {
+        let parts: Vec<u8> = version.split('.').map(|p| p.parse().unwrap()).collect();
+        PhpVersion {
+            version: version.to_string(),
+            major: parts[0],
+            minor: parts[1],
+            patch: parts[2],
+            installed: true,
+            ..Default::default()
+        }
+    }
+
+    /// Lays out `files` (a trailing `/` makes an empty folder) in a fresh
+    /// directory, inspects `pick` inside it and removes the tree again
+    fn inspect_tree(
+        files: &[(&str, &str)],
+        pick: &str,
+        installed: &[PhpVersion],
+    ) -> (PathBuf, ProjectInspection) {
+        let root = std::env::temp_dir().join(format!("inspector-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+        for (path, content) in files {
+            let path = root.join(path);
+            if path.to_string_lossy().ends_with('/') {
+                std::fs::create_dir_all(&path).unwrap();
+                continue;
+            }
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, content).unwrap();
+        }
+        let inspection = inspect_with(&path(&root, pick), installed);
+        let _ = std::fs::remove_dir_all(&root);
+        (root, inspection.unwrap())
+    }
+
+    fn path_str(root: &Path, rel: &str) -> String {
+        path(root, rel).to_string_lossy().to_string()
+    }
+
+    fn path(root: &Path, rel: &str) -> PathBuf {
+        match rel {
+            "" => root.to_path_buf(),
+            rel => root.join(rel),
+        }
+    }
+
+    const LARAVEL: &[(&str, &str)] = &[
+        ("artisan", "#!/usr/bin/env php"),
+        (
+            "composer.json",
+            r#"{"require": {"php": "^8.2", "laravel/framework": "^11.0"}}"#,
+        ),
+        (
+            "composer.lock",
+            r#"{"packages": [{"name": "laravel/framework", "version": "v11.9.2"}]}"#,
+        ),
+        ("public/index.php", "<?php"),
+    ];
+
+    #[test]
+    fn laravel_serves_public_with_the_newest_matching_php() {
+        let installed = [php("8.1.29"), php("8.3.10"), php("8.2.20")];
+        let (root, inspection) = inspect_tree(LARAVEL, "", &installed);
+
+        assert_eq!(inspection.framework.as_deref(), Some("laravel"));
+        assert_eq!(inspection.framework_version.as_deref(), Some("11.9.2"));
+        assert_eq!(inspection.docroot, path_str(&root, "public"));
+        assert_eq!(inspection.host_type, "php");
+        assert_eq!(inspection.template, "laravel");
+        assert_eq!(inspection.rewrites, FRONT_CONTROLLER);
+        assert_eq!(inspection.php_constraint.as_deref(), Some("^8.2"));
+        assert_eq!(inspection.php_version.as_deref(), Some("8.3.10"));
+        assert!(inspection
+            .notes
+            .iter()
+            .any(|n| n.contains("composer install")));
+    }
+
+    #[test]
+    fn picking_public_inspects_the_project_above_it() {
+        let (root, inspection) = inspect_tree(LARAVEL, "public", &[php("8.1.29")]);
+
+        assert_eq!(inspection.directory, path_str(&root, ""));
+        assert_eq!(inspection.framework.as_deref(), Some("laravel"));
+        assert_eq!(inspection.docroot, path_str(&root, "public"));
+        assert_eq!(inspection.template, "laravel");
+        assert_eq!(inspection.php_version, None);
+        assert!(inspection
+            .notes
+            .iter()
+            .any(|n| n == "No installed PHP version satisfies ^8.2"));
+
+        // A plain subfolder of a project is its own site
+        let mut files = LARAVEL.to_vec();
+        files.push(("docs/index.html", "<h1>docs</h1>"));
+        let (root, inspection) = inspect_tree(&files, "docs", &[]);
+        assert_eq!(inspection.directory, path_str(&root, "docs"));
+        assert_eq!(inspection.framework.as_deref(), Some("static"));
+        assert_eq!(inspection.template, "static");
+    }
+
+    #[test]
+    fn symfony_serves_public_or_the_legacy_web_folder() {
+        let modern = [
+            ("bin/console", "#!/usr/bin/env php"),
+            (
+                "composer.json",
+                r#"{"require": {"php": ">=8.2", "symfony/framework-bundle": "7.1.*"}}"#,
+            ),
+            (
+                "composer.lock",
+                r#"{"packages": [{"name": "symfony/framework-bundle", "version": "v7.1.3"}]}"#,
+            ),
+            ("vendor/autoload.php", "<?php"),
+        ];
+        let (root, inspection) = inspect_tree(&modern, "", &[php("8.3.10")]);
+        assert_eq!(inspection.framework.as_deref(), Some("symfony"));
+        assert_eq!(inspection.framework_version.as_deref(), Some("7.1.3"));
+        assert_eq!(inspection.docroot, path_str(&root, "public"));
+        assert_eq!(inspection.template, "symfony");
+        assert_eq!(inspection.php_constraint.as_deref(), Some(">=8.2"));
+        assert_eq!(inspection.php_version.as_deref(), Some("8.3.10"));
+        assert!(inspection.notes.is_empty());
+
+        let legacy = [
+            ("bin/console", "#!/usr/bin/env php"),
+            ("config/bundles.php", "<?php return [];"),
+            ("web/app.php", "<?php"),
+        ];
+        let (root, inspection) = inspect_tree(&legacy, "", &[]);
+        assert_eq!(inspection.framework.as_deref(), Some("symfony"));
+        assert_eq!(inspection.docroot, path_str(&root, "web"));
+        assert_eq!(inspection.php_constraint, None);
+    }
+
+    #[test]
+    fn wordpress_reads_its_version_and_multisite_flavor() {
+        let version = "<?php\n$wp_version = '6.5.2';\n$required_php_version = '7.0.0';\n";
+
+        // Bedrock: core in web/wp, config in web/
+        let bedrock = [
+            ("composer.json", r#"{"require": {"php": ">=8.1"}}"#),
+            ("web/wp/wp-includes/version.php", version),
+            (
+                "web/wp-config.php",
+                "<?php\ndefine( 'MULTISITE', true );\ndefine('SUBDOMAIN_INSTALL', false);\n",
+            ),
+        ];
+        let (root, inspection) = inspect_tree(&bedrock, "", &[php("7.4.33")]);
+        assert_eq!(inspection.framework.as_deref(), Some("wordpress"));
+        assert_eq!(inspection.framework_version.as_deref(), Some("6.5.2"));
+        assert_eq!(inspection.docroot, path_str(&root, "web"));
+        assert_eq!(inspection.template, "wordpress-multisite");
+        assert_eq!(inspection.rewrites, WORDPRESS);
+        // WordPress' own requirement wins over composer.json
+        assert_eq!(inspection.php_constraint.as_deref(), Some(">=7.0.0"));
+        assert_eq!(inspection.php_version.as_deref(), Some("7.4.33"));
+
+        // Subdomain networks and classic installs keep the plain rules
+        let classic = [
+            ("wp-includes/version.php", version),
+            (
+                "wp-config.php",
+                "<?php\ndefine('MULTISITE', true);\ndefine(\"SUBDOMAIN_INSTALL\", true);\n",
+            ),
+        ];
+        let (root, inspection) = inspect_tree(&classic, "", &[]);
+        assert_eq!(inspection.docroot, path_str(&root, ""));
+        assert_eq!(inspection.template, "wordpress");
+
+        let (_, inspection) = inspect_tree(&[("wp-includes/version.php", version)], "", &[]);
+        assert_eq!(inspection.template, "wordpress");
+        assert!(inspection.notes.iter().any(|n| n.contains("wp-config.php")));
+    }
+
+    #[test]
+    fn drupal_serves_web_for_composer_installs_and_the_root_otherwise() {
+        let drupal = "<?php\nclass Drupal {\n  const VERSION = '10.2.4';\n}\n";
+
+        let composer = [
+            ("composer.json", r#"{"require": {"php": ">=8.1"}}"#),
+            ("web/core/lib/Drupal.php", drupal),
+        ];
+        let (root, inspection) = inspect_tree(&composer, "", &[]);
+        assert_eq!(inspection.framework.as_deref(), Some("drupal"));
+        assert_eq!(inspection.framework_version.as_deref(), Some("10.2.4"));
+        assert_eq!(inspection.docroot, path_str(&root, "web"));
+        assert_eq!(inspection.template, "front-controller");
+        assert_eq!(inspection.php_constraint.as_deref(), Some(">=8.1"));
+
+        let (root, inspection) = inspect_tree(&[("core/lib/Drupal.php", drupal)], "", &[]);
+        assert_eq!(inspection.framework.as_deref(), Some("drupal"));
+        assert_eq!(inspection.docroot, path_str(&root, ""));
+    }
+
+    #[test]
+    fn codeigniter_4_serves_public_and_3_its_root() {
+        let ci4 = [
+            ("spark", "#!/usr/bin/env php"),
+            ("app/Config/App.php", "<?php"),
+            (
+                "composer.lock",
+                r#"{"packages": [{"name": "codeigniter4/framework", "version": "v4.5.1"}]}"#,
+            ),
+        ];
+        let (root, inspection) = inspect_tree(&ci4, "", &[]);
+        assert_eq!(inspection.framework.as_deref(), Some("codeigniter"));
+        assert_eq!(inspection.framework_version.as_deref(), Some("4.5.1"));
+        assert_eq!(inspection.docroot, path_str(&root, "public"));
+        assert_eq!(inspection.template, "front-controller");
+
+        let ci3 = [
+            (
+                "system/core/CodeIgniter.php",
+                "<?php\n\tconst CI_VERSION = '3.1.13';\n",
+            ),
+            ("index.php", "<?php"),
+        ];
+        let (root, inspection) = inspect_tree(&ci3, "", &[]);
+        assert_eq!(inspection.framework.as_deref(), Some("codeigniter"));
+        assert_eq!(inspection.framework_version.as_deref(), Some("3.1.13"));
+        assert_eq!(inspection.docroot, path_str(&root, ""));
+        assert_eq!(inspection.rewrites, FRONT_CONTROLLER);
+    }
+
+    #[test]
+    fn node_apps_serve_their_build() {
+        let vite = [(
+            "package.json",
+            r#"{"dependencies": {"vue": "^3.4.0"}, "devDependencies": {"vite": "^5.2.0"}}"#,
+        )];
+        let (root, inspection) = inspect_tree(&vite, "", &[]);
+        assert_eq!(inspection.framework.as_deref(), Some("vite"));
+        assert_eq!(inspection.framework_version.as_deref(), Some("5.2.0"));
+        assert_eq!(inspection.docroot, path_str(&root, "dist"));
+        assert_eq!(inspection.host_type, "vue");
+        assert_eq!(inspection.template, "spa");
+        assert_eq!(inspection.rewrites, SPA);
+        assert!(inspection.notes.iter().any(|n| n.contains("vite build")));
+
+        let next = [
+            (
+                "package.json",
+                r#"{"dependencies": {"next": "14.2.3", "react": "^18.3.0"}}"#,
+            ),
+            ("out/", ""),
+        ];
+        let (root, inspection) = inspect_tree(&next, "", &[]);
+        assert_eq!(inspection.framework.as_deref(), Some("next"));
+        assert_eq!(inspection.framework_version.as_deref(), Some("14.2.3"));
+        assert_eq!(inspection.docroot, path_str(&root, "out"));
+        assert_eq!(inspection.host_type, "react");
+        assert_eq!(inspection.template, "static");
+        assert!(inspection.rewrites.is_empty());
+        assert!(inspection.notes.is_empty());
+
+        let nuxt = r#"{"dependencies": {"nuxt": "^3.11.0"}}"#;
+        let (root, inspection) = inspect_tree(&[("package.json", nuxt)], "", &[]);
+        assert_eq!(inspection.framework.as_deref(), Some("nuxt"));
+        assert_eq!(inspection.docroot, path_str(&root, ".output/public"));
+        assert_eq!(inspection.host_type, "vue");
+        assert_eq!(inspection.template, "spa");
+        // An older nuxt generate wrote dist/
+        let (root, inspection) = inspect_tree(&[("package.json", nuxt), ("dist/", "")], "", &[]);
+        assert_eq!(inspection.docroot, path_str(&root, "dist"));
+    }
+
+    #[test]
+    fn php_literal_reads_assignments_constants_and_defines() {
+        let file =
+            std::env::temp_dir().join(format!("inspector-test-{}.php", uuid::Uuid::new_v4()));
+        std::fs::write(
+            &file,
+            "<?php\n\
+             $wp_version_extra = 'no';\n\
+             $wp_version = '6.5.2';\n\
+             \tconst VERSION = \"10.2.4\";\n\
+             define('CI_VERSION', '3.1.13');\n",
+        )
+        .unwrap();
+        let read = |name| php_literal(&file, name);
+        let values = [
+            read("$wp_version"),
+            read("VERSION"),
+            read("CI_VERSION"),
+            read("$missing"),
+        ];
+        let _ = std::fs::remove_file(&file);
+
+        assert_eq!(
+            values,
+            [
+                Some("6.5.2".to_string()),
+                Some("10.2.4".to_string()),
+                Some("3.1.13".to_string()),
+                None,
+            ]
+        );
+        assert_eq!(
+            php_literal(Path::new("/nonexistent/version.php"), "VERSION"),
+            None
+        );
+    }
+}
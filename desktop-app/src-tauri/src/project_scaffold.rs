@@ -0,0 +1,408 @@
+// ============================================
+// Project Scaffolding
+// ============================================
+// Creates a new project folder under the projects directory chosen in the
+// setup wizard, registers `<name>.local` for it (SSL on) and applies the
+// configuration, so the site answers as soon as the command returns.
+//
+// Scaffolds:
+//   blank      index.php in an empty folder
+//   laravel    `composer create-project laravel/laravel`, run as a job; the
+//              host is registered once Composer succeeds
+//   wordpress  the archive at settings.wordpressDownloadUrl, checked against
+//              the `.sha256` or `.sha1` published next to it and unpacked in place
+//   static     index.html in an empty folder
+
+use crate::hosts_manager::{add_virtual_hosts, load_virtual_hosts, regenerate_and_apply};
+use crate::jobs::Jobs;
+use crate::paths::Layout;
+use crate::php_discovery;
+use crate::php_switcher;
+use crate::settings::Settings;
+use crate::types::VirtualHost;
+use serde::Serialize;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+
+pub const SCAFFOLDS: &[&str] = &["blank", "laravel", "wordpress", "static"];
+
+/// Hosts created here go to their own group in the hosts list
+const GROUP: &str = "Projects";
+
+/// Folders being scaffolded whose host isn't registered yet
+static SCAFFOLDING: Mutex<Option<HashSet<PathBuf>>> = Mutex::new(None);
+
+/// Marks a folder as being scaffolded until dropped, which happens once its
+/// host is registered or the scaffold failed
+struct Scaffolding(PathBuf);
+
+impl Scaffolding {
+    fn start(directory: &Path) -> Self {
+        if let Ok(mut dirs) = SCAFFOLDING.lock() {
+            dirs.get_or_insert_with(HashSet::new)
+                .insert(directory.to_path_buf());
+        }
+        Scaffolding(directory.to_path_buf())
+    }
+}
+
+impl Drop for Scaffolding {
+    fn drop(&mut self) {
+        if let Ok(mut dirs) = SCAFFOLDING.lock() {
+            if let Some(dirs) = dirs.as_mut() {
+                dirs.remove(&self.0);
+            }
+        }
+    }
+}
+
+/// Whether a scaffold is still creating `directory`; parking the projects
+/// directory leaves it alone so the scaffold registers its own `.local` host
+pub fn is_scaffolding(directory: &Path) -> bool {
+    SCAFFOLDING
+        .lock()
+        .map(|dirs| dirs.as_ref().is_some_and(|dirs| dirs.contains(directory)))
+        .unwrap_or(false)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NewProject {
+    pub domain: String,
+    pub directory: String,
+    pub docroot: String,
+    /// Set for scaffolds that run as a job (Laravel); the host is registered
+    /// and configs applied when it finishes
+    pub job_id: Option<String>,
+}
+
+/// Names become a folder and a hostname label: lowercase letters, digits, dashes
+fn validate_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= 63
+        && !name.starts_with('-')
+        && !name.ends_with('-')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid project name '{}': use lowercase letters, digits and dashes",
+            name
+        ))
+    }
+}
+
+fn new_host(domain: &str, docroot: &Path, host_type: &str, framework: Option<&str>) -> VirtualHost {
+    VirtualHost {
+        domain: domain.to_string(),
+        docroot: docroot.to_string_lossy().to_string(),
+        aliases: Vec::new(),
+        group: GROUP.to_string(),
+        active: true,
+        ssl: true,
+        host_type: host_type.to_string(),
+        profile: None,
+        fpm_pool: None,
+        framework: framework.map(String::from),
+        rewrites: Vec::new(),
+        template: None,
+        wildcard: false,
+        subdomains: Vec::new(),
+    }
+}
+
+/// Add the host to hosts.json and regenerate vhosts, certificates and /etc/hosts
+fn register(host: VirtualHost) -> Result<(), String> {
+    let domain = host.domain.clone();
+    if add_virtual_hosts(vec![host])? == 0 {
+        return Err(format!("Host '{}' already exists", domain));
+    }
+
+    regenerate_and_apply().map(|_| ()).map_err(|e| {
+        format!(
+            "{} was registered, but applying the configuration failed: {}",
+            domain, e
+        )
+    })
+}
+
+fn write_starter(directory: &Path, file: &str, content: String) -> Result<(), String> {
+    std::fs::create_dir_all(directory)
+        .map_err(|e| format!("Failed to create {}: {}", directory.display(), e))?;
+    let path = directory.join(file);
+    std::fs::write(&path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+fn blank_index(name: &str) -> String {
+    format!(
+        "<?php\n// {name}: served at https://{name}.local\n\n?>\n<!doctype html>\n<title>{name}</title>\n<h1>{name}</h1>\n<p>PHP <?= PHP_VERSION ?></p>\n",
+        name = name
+    )
+}
+
+fn static_index(name: &str) -> String {
+    format!(
+        "<!doctype html>\n<html lang=\"en\">\n<head>\n  <meta charset=\"utf-8\">\n  <title>{name}</title>\n</head>\n<body>\n  <h1>{name}</h1>\n</body>\n</html>\n",
+        name = name
+    )
+}
+
+/// Point Laravel's APP_URL at the new host
+fn set_app_url(directory: &Path, url: &str) {
+    let env = directory.join(".env");
+    let Ok(content) = std::fs::read_to_string(&env) else {
+        return;
+    };
+    let updated: Vec<String> = content
+        .lines()
+        .map(|line| {
+            if line.starts_with("APP_URL=") {
+                format!("APP_URL={}", url)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+    let _ = std::fs::write(&env, updated.join("\n") + "\n");
+}
+
+/// `composer create-project` with the global PHP, run from the projects directory
+fn laravel_command(projects_dir: &Path, name: &str) -> Result<Command, String> {
+    let composer =
+        php_switcher::find_composer().ok_or_else(|| "Composer isn't installed".to_string())?;
+    let php = php_switcher::active(&php_discovery::installed_versions(), Some(projects_dir))
+        .map(|active| active.binary)
+        .filter(|binary| !binary.is_empty());
+
+    // Without a known PHP, let the composer script find one itself
+    let mut cmd = match php {
+        Some(php) => {
+            let mut cmd = Command::new(php);
+            cmd.arg(&composer);
+            cmd
+        }
+        None => Command::new(&composer),
+    };
+    cmd.args(["create-project", "laravel/laravel", name])
+        .args(["--no-interaction", "--no-ansi"])
+        .current_dir(projects_dir)
+        .env("COMPOSER_NO_INTERACTION", "1");
+    Ok(cmd)
+}
+
+/// Checksum published next to an archive: `<url>.sha256`, else `<url>.sha1`
+/// (what wordpress.org serves), as the algorithm and its lowercase hex digest
+async fn published_checksum(
+    client: &reqwest::Client,
+    url: &str,
+) -> Result<(&'static str, String), String> {
+    for (algorithm, hex_len) in [("sha256", 64), ("sha1", 40)] {
+        let Ok(response) = client.get(format!("{}.{}", url, algorithm)).send().await else {
+            continue;
+        };
+        if !response.status().is_success() {
+            continue;
+        }
+        let body = response.text().await.unwrap_or_default();
+        let hash = body
+            .split_whitespace()
+            .next()
+            .map(str::to_lowercase)
+            .filter(|h| h.len() == hex_len && h.chars().all(|c| c.is_ascii_hexdigit()));
+        if let Some(hash) = hash {
+            return Ok((algorithm, hash));
+        }
+    }
+
+    Err(format!(
+        "No SHA-256 or SHA-1 checksum published for {}",
+        url
+    ))
+}
+
+fn verify_checksum(bytes: &[u8], algorithm: &str, expected: &str) -> Result<(), String> {
+    let actual = match algorithm {
+        "sha256" => format!("{:x}", Sha256::digest(bytes)),
+        "sha1" => format!("{:x}", Sha1::digest(bytes)),
+        other => return Err(format!("Unsupported checksum algorithm {}", other)),
+    };
+    if actual != expected {
+        return Err(format!(
+            "Checksum mismatch for the WordPress archive: expected {}, got {}",
+            expected, actual
+        ));
+    }
+    Ok(())
+}
+
+/// Download the WordPress archive, verify it and unpack it as `directory`
+async fn download_wordpress(url: &str, directory: &Path) -> Result<(), String> {
+    let parent = directory
+        .parent()
+        .ok_or_else(|| format!("Invalid project directory {}", directory.display()))?;
+    let name = directory
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let staging = parent.join(format!(".{}.partial", name));
+    let _ = std::fs::remove_dir_all(&staging);
+
+    let client = reqwest::Client::new();
+    let (algorithm, expected) = published_checksum(&client, url).await?;
+    let bytes = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Download failed: {}", e))?
+        .bytes()
+        .await
+        .map_err(|e| format!("Download interrupted: {}", e))?;
+    verify_checksum(&bytes, algorithm, &expected)?;
+
+    let target = staging.clone();
+    let unpacked = tauri::async_runtime::spawn_blocking(move || {
+        std::fs::create_dir_all(&target)
+            .map_err(|e| format!("Failed to create {}: {}", target.display(), e))?;
+        // tar's unpack refuses entries escaping the target directory
+        tar::Archive::new(flate2::read::GzDecoder::new(&bytes[..]))
+            .unpack(&target)
+            .map_err(|e| format!("Failed to extract WordPress: {}", e))
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    if let Err(e) = unpacked {
+        let _ = std::fs::remove_dir_all(&staging);
+        return Err(e);
+    }
+
+    // Release archives wrap everything in a wordpress/ folder
+    let root = staging.join("wordpress");
+    let source = if root.join("wp-includes").is_dir() {
+        root
+    } else {
+        staging.clone()
+    };
+    if !source.join("wp-includes").is_dir() {
+        let _ = std::fs::remove_dir_all(&staging);
+        return Err(format!("{} doesn't look like a WordPress archive", url));
+    }
+
+    let moved = std::fs::rename(&source, directory)
+        .map_err(|e| format!("Failed to create {}: {}", directory.display(), e));
+    let _ = std::fs::remove_dir_all(&staging);
+    moved
+}
+
+/// Create `<projects dir>/<name>` from a scaffold, register `<name>.local`
+/// with SSL and apply the configuration
+#[tauri::command]
+pub async fn create_project(
+    name: String,
+    scaffold: String,
+    window: tauri::Window,
+    jobs: tauri::State<'_, Jobs>,
+) -> Result<NewProject, String> {
+    validate_name(&name)?;
+    if !SCAFFOLDS.contains(&scaffold.as_str()) {
+        return Err(format!("Unknown project scaffold '{}'", scaffold));
+    }
+
+    let projects_dir: PathBuf = Layout::resolve()
+        .projects_dir
+        .ok_or_else(|| "No projects directory configured".to_string())?;
+    let directory = projects_dir.join(&name);
+    if directory.exists() {
+        return Err(format!("{} already exists", directory.display()));
+    }
+
+    let domain = format!("{}.local", name);
+    if load_virtual_hosts()?.contains_key(&domain) {
+        return Err(format!("Host '{}' already exists", domain));
+    }
+
+    let scaffolding = Scaffolding::start(&directory);
+    let (docroot, host) = match scaffold.as_str() {
+        "laravel" => {
+            let docroot = directory.join("public");
+            let host = new_host(&domain, &docroot, "php", Some("laravel"));
+            let cmd = laravel_command(&projects_dir, &name)?;
+            let (app_dir, url) = (directory.clone(), format!("https://{}", domain));
+            let job_id = jobs.spawn_then(
+                &window,
+                cmd,
+                &format!("Creating Laravel project {}", name),
+                move || {
+                    // Dropped with the closure, whether or not Composer succeeded
+                    let _scaffolding = scaffolding;
+                    set_app_url(&app_dir, &url);
+                    register(host)
+                },
+            )?;
+
+            return Ok(NewProject {
+                domain,
+                directory: directory.to_string_lossy().to_string(),
+                docroot: docroot.to_string_lossy().to_string(),
+                job_id: Some(job_id),
+            });
+        }
+        "wordpress" => {
+            let url = Settings::load().unwrap_or_default().wordpress_download_url;
+            download_wordpress(&url, &directory).await?;
+            let host = new_host(&domain, &directory, "php", Some("wordpress"));
+            (directory.clone(), host)
+        }
+        "static" => {
+            write_starter(&directory, "index.html", static_index(&name))?;
+            (
+                directory.clone(),
+                new_host(&domain, &directory, "static", None),
+            )
+        }
+        _ => {
+            write_starter(&directory, "index.php", blank_index(&name))?;
+            (
+                directory.clone(),
+                new_host(&domain, &directory, "php", None),
+            )
+        }
+    };
+
+    tauri::async_runtime::spawn_blocking(move || register(host))
+        .await
+        .map_err(|e| e.to_string())??;
+    drop(scaffolding);
+
+    Ok(NewProject {
+        domain,
+        directory: directory.to_string_lossy().to_string(),
+        docroot: docroot.to_string_lossy().to_string(),
+        job_id: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn archives_must_match_their_published_checksum() {
+        let sha256 = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+        let sha1 = "a9993e364706816aba3e25717850c26c9cd0d89d";
+
+        assert!(verify_checksum(b"abc", "sha256", sha256).is_ok());
+        assert!(verify_checksum(b"abc", "sha1", sha1).is_ok());
+        assert!(verify_checksum(b"abd", "sha256", sha256)
+            .unwrap_err()
+            .contains("Checksum mismatch"));
+        assert!(verify_checksum(b"abd", "sha1", sha1).is_err());
+        assert!(verify_checksum(b"abc", "md5", "900150983cd24fb0d6963f7d28e17f72").is_err());
+    }
+}
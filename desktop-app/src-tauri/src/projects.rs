@@ -0,0 +1,374 @@
+// ============================================
+// Project Profiles
+// ============================================
+// A profile pins a project to a PHP version with its own extensions and ini
+// overrides. Profiles are stored as `<id>.json` in
+// `AppConfig.projects_config_dir`. Saving one generates a dedicated PHP-FPM
+// pool (`project-<id>`) whose `php_admin_value` lines carry the overrides, so
+// a project's tweaks never leak into the global php.ini. Virtual hosts bound
+// to a profile (`profile` in hosts.json) send PHP requests to that pool.
+
+use crate::config::AppConfig;
+use crate::hosts_manager::{get_hosts_file_path, run_command, write_hosts_file};
+use crate::php_discovery;
+use crate::php_fpm;
+use crate::php_ini::ZEND_EXTENSIONS;
+use crate::settings::Settings;
+use crate::system::{remove_file_privileged, write_file_privileged};
+use crate::types::{PhpVersion, ProjectProfile};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+/// First port handed out to project pools; the shared pool stays on `ports.phpFpm`
+const FIRST_POOL_PORT: u16 = 9100;
+
+fn profiles_dir() -> PathBuf {
+    AppConfig::load().unwrap_or_default().projects_config_dir
+}
+
+/// IDs end up in file and pool names
+fn validate_id(id: &str) -> Result<(), String> {
+    if !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        Ok(())
+    } else {
+        Err(format!("Invalid project id '{}'", id))
+    }
+}
+
+fn profile_path(id: &str) -> PathBuf {
+    profiles_dir().join(format!("{}.json", id))
+}
+
+fn pool_name(id: &str) -> String {
+    format!("project-{}", id)
+}
+
+/// All saved profiles, sorted by name
+pub fn load_all() -> Vec<ProjectProfile> {
+    let mut profiles: Vec<ProjectProfile> = std::fs::read_dir(profiles_dir())
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|e| e.path())
+                .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("json"))
+                .filter_map(|p| std::fs::read_to_string(p).ok())
+                .filter_map(|content| serde_json::from_str(&content).ok())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    profiles.sort_by_key(|p| p.name.to_lowercase());
+    profiles
+}
+
+pub fn load(id: &str) -> Result<ProjectProfile, String> {
+    validate_id(id)?;
+
+    let content = std::fs::read_to_string(profile_path(id))
+        .map_err(|_| format!("Project profile '{}' not found", id))?;
+    serde_json::from_str(&content).map_err(|e| format!("Invalid project profile '{}': {}", id, e))
+}
+
+fn write(profile: &ProjectProfile) -> Result<(), String> {
+    let dir = profiles_dir();
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+    let path = profile_path(&profile.id);
+    let tmp_file = path.with_extension("json.tmp");
+    let content = serde_json::to_string_pretty(profile)
+        .map_err(|e| format!("Failed to serialize project profile: {}", e))?;
+
+    std::fs::write(&tmp_file, content)
+        .and_then(|_| std::fs::rename(&tmp_file, &path))
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Keep the pool's listen address across saves; new pools take the first free port
+fn assign_listen(profile: &ProjectProfile, others: &[ProjectProfile]) -> String {
+    if let Some(listen) = profile.fpm_listen.as_ref().filter(|l| !l.is_empty()) {
+        return listen.clone();
+    }
+
+    let shared_port = Settings::load().unwrap_or_default().ports.php_fpm;
+    let used: HashSet<u16> = others
+        .iter()
+        .filter(|p| p.id != profile.id)
+        .filter_map(|p| p.fpm_listen.as_deref()?.rsplit(':').next()?.parse().ok())
+        .collect();
+
+    let port = (FIRST_POOL_PORT..=u16::MAX)
+        .find(|port| *port != shared_port && !used.contains(port))
+        .unwrap_or(FIRST_POOL_PORT);
+
+    format!("127.0.0.1:{}", port)
+}
+
+fn validate_ini_key(key: &str) -> Result<(), String> {
+    if !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+    {
+        Ok(())
+    } else {
+        Err(format!("Invalid ini setting name '{}'", key))
+    }
+}
+
+/// Quote values the FPM ini parser would otherwise cut at `;` or whitespace
+fn ini_value(key: &str, value: &str) -> Result<String, String> {
+    if value.contains(['"', '\n', '\r']) {
+        return Err(format!(
+            "Invalid value for {}: quotes and newlines are not allowed",
+            key
+        ));
+    }
+
+    if value.is_empty()
+        || value
+            .chars()
+            .any(|c| c.is_whitespace() || ";#=&|~!^(){}[]".contains(c))
+    {
+        Ok(format!("\"{}\"", value))
+    } else {
+        Ok(value.to_string())
+    }
+}
+
+fn current_user() -> Option<String> {
+    std::env::var("USER")
+        .ok()
+        .filter(|u| !u.is_empty())
+        .or_else(|| run_command("id", &["-un"]).map(|u| u.trim().to_string()))
+}
+
+/// Pool definition for a profile. Extensions the build already loads are
+/// skipped, since loading them twice makes every request log a warning.
+pub fn pool_config(profile: &ProjectProfile, php: &PhpVersion) -> Result<String, String> {
+    let listen = profile
+        .fpm_listen
+        .as_deref()
+        .ok_or_else(|| format!("Project '{}' has no pool address", profile.name))?;
+
+    let loaded: HashSet<String> = php
+        .binary
+        .as_deref()
+        .and_then(|binary| run_command(binary, &["-m"]))
+        .map(|modules| modules.lines().map(|m| m.trim().to_lowercase()).collect())
+        .unwrap_or_default();
+
+    let mut lines = vec![
+        format!(
+            "; Generated by Localhost Manager for project \"{}\" ({})",
+            profile.name, profile.path
+        ),
+        "; Edit the project profile in the app; this file is rewritten on save.".to_string(),
+        format!("[{}]", pool_name(&profile.id)),
+    ];
+
+    // Only honored when the master runs as root (Debian, remi); harmless otherwise
+    if let Some(user) = current_user() {
+        lines.push(format!("user = {}", user));
+    }
+
+    lines.extend([
+        format!("listen = {}", listen),
+        "listen.allowed_clients = 127.0.0.1".to_string(),
+        "pm = ondemand".to_string(),
+        "pm.max_children = 5".to_string(),
+        "pm.process_idle_timeout = 10s".to_string(),
+        format!("chdir = {}", ini_value("chdir", &profile.path)?),
+        "catch_workers_output = yes".to_string(),
+        // Health checks over FastCGI; web servers only forward .php here
+        "ping.path = /ping".to_string(),
+        "pm.status_path = /status".to_string(),
+    ]);
+
+    for extension in &profile.extensions {
+        let name = extension.trim().trim_end_matches(".so").to_lowercase();
+        // `php -m` lists OPcache as "Zend OPcache"
+        if name.is_empty() || loaded.contains(&name) || loaded.contains(&format!("zend {}", name)) {
+            continue;
+        }
+        // Zend extensions can't be loaded through `php_admin_value[extension]`
+        if ZEND_EXTENSIONS.contains(&name.as_str()) {
+            return Err(format!(
+                "{} is a Zend extension and must be enabled for PHP {} itself",
+                name, php.version
+            ));
+        }
+        validate_ini_key(&name)?;
+        lines.push(format!("php_admin_value[extension] = {}", name));
+    }
+
+    let mut settings: Vec<_> = profile.custom_ini_settings.iter().collect();
+    settings.sort();
+    for (key, value) in settings {
+        // Read only when FPM starts; it's what the project needs from its
+        // version (see `xdebug`)
+        if key == "xdebug.mode" {
+            continue;
+        }
+        validate_ini_key(key)?;
+        lines.push(format!(
+            "php_admin_value[{}] = {}",
+            key,
+            ini_value(key, value)?
+        ));
+    }
+
+    lines.push(String::new());
+    Ok(lines.join("\n"))
+}
+
+fn pool_file(php: &PhpVersion, id: &str) -> Result<PathBuf, String> {
+    php_fpm::pool_dir(php)
+        .map(|dir| dir.join(format!("{}.conf", pool_name(id))))
+        .ok_or_else(|| format!("No PHP-FPM pool directory found for PHP {}", php.version))
+}
+
+async fn remove_pool(version: &str, id: &str) -> Result<(), String> {
+    // The version may have been uninstalled since; nothing left to clean up then
+    let Some(php) = php_discovery::find_installed(version) else {
+        return Ok(());
+    };
+    let Ok(file) = pool_file(&php, id) else {
+        return Ok(());
+    };
+
+    if file.exists() {
+        remove_file_privileged(&file).await?;
+        php_fpm::reload(&php).await?;
+    }
+
+    Ok(())
+}
+
+/// Send every host bound to `id` back to the shared pool
+fn unbind_hosts(id: &str) -> Result<(), String> {
+    let hosts_file = get_hosts_file_path();
+    let Ok(content) = std::fs::read_to_string(&hosts_file) else {
+        return Ok(());
+    };
+
+    let mut hosts: HashMap<String, serde_json::Value> =
+        serde_json::from_str(&content).map_err(|e| format!("Failed to parse hosts file: {}", e))?;
+
+    let mut changed = false;
+    for host in hosts.values_mut().filter_map(|h| h.as_object_mut()) {
+        if host.get("profile").and_then(|p| p.as_str()) == Some(id) {
+            host.remove("profile");
+            changed = true;
+        }
+    }
+
+    if changed {
+        let json_content = serde_json::to_string_pretty(&hosts)
+            .map_err(|e| format!("Failed to serialize hosts: {}", e))?;
+        write_hosts_file(&hosts_file, &json_content)?;
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_project_profiles() -> Result<Vec<ProjectProfile>, String> {
+    Ok(load_all())
+}
+
+/// Create or update a profile and (re)install its PHP-FPM pool
+#[tauri::command]
+pub async fn save_project_profile(mut profile: ProjectProfile) -> Result<ProjectProfile, String> {
+    if profile.id.is_empty() {
+        profile.id = uuid::Uuid::new_v4().to_string();
+    }
+    validate_id(&profile.id)?;
+
+    if profile.name.trim().is_empty() {
+        return Err("Project name is required".to_string());
+    }
+    if !std::path::Path::new(&profile.path).is_dir() {
+        return Err(format!("Project directory not found: {}", profile.path));
+    }
+
+    let previous = load(&profile.id).ok();
+    profile.fpm_listen = previous.as_ref().and_then(|p| p.fpm_listen.clone());
+    profile.fpm_listen = Some(assign_listen(&profile, &load_all()));
+
+    // Build everything before touching the FPM config, so bad input changes nothing
+    let php = php_fpm::find(&profile.php_version)?;
+    let config = pool_config(&profile, &php)?;
+    let file = pool_file(&php, &profile.id)?;
+
+    write_file_privileged(&file, &config).await?;
+
+    // Both pools would listen on the same address; keep the old one on failure
+    if let Some(previous) = previous.filter(|p| p.php_version != profile.php_version) {
+        if let Err(e) = remove_pool(&previous.php_version, &profile.id).await {
+            let _ = remove_file_privileged(&file).await;
+            return Err(e);
+        }
+    }
+
+    // Only record the profile once its pool is in place
+    write(&profile)?;
+
+    php_fpm::reload(&php)
+        .await
+        .map_err(|e| format!("Profile saved, but PHP-FPM could not be reloaded: {}", e))?;
+
+    Ok(profile)
+}
+
+/// Delete a profile, its pool, and unbind the hosts using it
+#[tauri::command]
+pub async fn delete_project_profile(id: String) -> Result<(), String> {
+    let profile = load(&id)?;
+
+    remove_pool(&profile.php_version, &profile.id).await?;
+    unbind_hosts(&profile.id)?;
+
+    std::fs::remove_file(profile_path(&profile.id))
+        .map_err(|e| format!("Failed to delete project profile: {}", e))
+}
+
+/// Bind a virtual host to a profile's pool, or back to the shared pool with `None`
+#[tauri::command]
+pub async fn assign_project_profile(
+    domain: String,
+    profile_id: Option<String>,
+) -> Result<(), String> {
+    if let Some(id) = profile_id.as_deref() {
+        load(id)?;
+    }
+
+    let hosts_file = get_hosts_file_path();
+    let content = std::fs::read_to_string(&hosts_file)
+        .map_err(|e| format!("Failed to read hosts file: {}", e))?;
+
+    let mut hosts: HashMap<String, serde_json::Value> =
+        serde_json::from_str(&content).map_err(|e| format!("Failed to parse hosts file: {}", e))?;
+
+    let host = hosts
+        .get_mut(&domain)
+        .and_then(|h| h.as_object_mut())
+        .ok_or_else(|| format!("Host '{}' not found", domain))?;
+
+    match profile_id {
+        Some(id) => {
+            // A profile brings its own pool
+            host.remove("fpm_pool");
+            host.insert("profile".to_string(), id.into())
+        }
+        None => host.remove("profile"),
+    };
+
+    let json_content = serde_json::to_string_pretty(&hosts)
+        .map_err(|e| format!("Failed to serialize hosts: {}", e))?;
+    write_hosts_file(&hosts_file, &json_content)
+}
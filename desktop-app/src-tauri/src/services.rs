@@ -0,0 +1,442 @@
+// ============================================
+// Service Control
+// ============================================
+// Shared start/stop/restart layer for the managed services, used by the
+// `control_service` command and the tray menu. Each platform resolves the
+// installed unit (systemd unit, Homebrew formula or Windows service) first so
+// missing services are reported as skipped instead of failing the whole run.
+
+use crate::hosts_manager::run_windows_command;
+use crate::settings::{Settings, WebServer};
+use std::process::Command;
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Service {
+    Mysql,
+    PhpFpm,
+    WebServer,
+}
+
+impl Service {
+    /// Start order: MySQL first, then PHP-FPM, then the web server that proxies to it
+    pub const START_ORDER: [Service; 3] = [Service::Mysql, Service::PhpFpm, Service::WebServer];
+
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "mysql" => Ok(Service::Mysql),
+            "php" | "php-fpm" => Ok(Service::PhpFpm),
+            "apache" | "nginx" | "web" => Ok(Service::WebServer),
+            _ => Err(format!("Unknown service: {}", name)),
+        }
+    }
+
+    /// Name used by the UI and the `control_service` command
+    pub fn id(&self) -> &'static str {
+        match self {
+            Service::Mysql => "mysql",
+            Service::PhpFpm => "php",
+            Service::WebServer => "apache",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Service::Mysql => "MySQL",
+            Service::PhpFpm => "PHP-FPM",
+            Service::WebServer => "Web server",
+        }
+    }
+
+    /// Services that must be running for this one to be useful.
+    /// The web server proxies PHP requests to FPM, so it is skipped when FPM fails.
+    pub fn depends_on(&self) -> &'static [Service] {
+        match self {
+            Service::Mysql => &[],
+            Service::PhpFpm => &[],
+            Service::WebServer => &[Service::PhpFpm],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServiceAction {
+    Start,
+    Stop,
+    Restart,
+}
+
+impl ServiceAction {
+    pub fn parse(action: &str) -> Result<Self, String> {
+        match action {
+            "start" => Ok(ServiceAction::Start),
+            "stop" => Ok(ServiceAction::Stop),
+            "restart" => Ok(ServiceAction::Restart),
+            _ => Err(format!("Unknown action: {}", action)),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ServiceAction::Start => "start",
+            ServiceAction::Stop => "stop",
+            ServiceAction::Restart => "restart",
+        }
+    }
+
+    pub fn past_tense(&self) -> &'static str {
+        match self {
+            ServiceAction::Start => "started",
+            ServiceAction::Stop => "stopped",
+            ServiceAction::Restart => "restarted",
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ServiceOutcome {
+    pub service: String,
+    pub success: bool,
+    pub skipped: bool,
+    pub message: String,
+}
+
+// ============================================
+// Unit Resolution
+// ============================================
+
+/// List installed systemd service units (without the `.service` suffix)
+fn systemd_units() -> Vec<String> {
+    Command::new("systemctl")
+        .args([
+            "list-unit-files",
+            "--type=service",
+            "--no-legend",
+            "--no-pager",
+        ])
+        .output()
+        .ok()
+        .map(|o| {
+            String::from_utf8_lossy(&o.stdout)
+                .lines()
+                .filter_map(|line| line.split_whitespace().next())
+                .map(|unit| unit.trim_end_matches(".service").to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// List installed Homebrew formulae
+fn brew_formulae() -> Vec<String> {
+    Command::new("brew")
+        .args(["list", "--formula", "-1"])
+        .output()
+        .ok()
+        .map(|o| {
+            String::from_utf8_lossy(&o.stdout)
+                .lines()
+                .map(|l| l.trim().to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Pick the newest versioned entry (`php8.3-fpm`, `php@8.2`, `mysql@8.4`, ...) from a list
+fn newest_versioned(candidates: Vec<&String>) -> Option<String> {
+    candidates
+        .into_iter()
+        .max_by_key(|name| {
+            name.chars()
+                .filter(|c| c.is_ascii_digit() || *c == '.')
+                .collect::<String>()
+                .split('.')
+                .map(|p| p.parse::<u32>().unwrap_or(0))
+                .collect::<Vec<_>>()
+        })
+        .cloned()
+}
+
+/// The web server the configs are generated for; the other one may be
+/// installed too but is left alone
+fn web_server() -> WebServer {
+    Settings::load().unwrap_or_default().web_server
+}
+
+fn resolve_linux_unit(service: Service) -> Option<String> {
+    let units = systemd_units();
+    let first_of = |names: &[&str]| {
+        names
+            .iter()
+            .find(|n| units.iter().any(|u| u == *n))
+            .map(|n| n.to_string())
+    };
+
+    match service {
+        Service::Mysql => first_of(&["mysql", "mysqld", "mariadb"]),
+        Service::PhpFpm => newest_versioned(
+            units
+                .iter()
+                .filter(|u| u.starts_with("php") && u.ends_with("-fpm") && u.len() > 7)
+                .collect(),
+        )
+        .or_else(|| first_of(&["php-fpm"])),
+        Service::WebServer => match web_server() {
+            WebServer::Apache => first_of(&["apache2", "httpd"]),
+            WebServer::Nginx => first_of(&["nginx"]),
+        },
+    }
+}
+
+fn resolve_brew_formula(service: Service) -> Option<String> {
+    let formulae = brew_formulae();
+    let first_of = |names: &[&str]| {
+        names
+            .iter()
+            .find(|n| formulae.iter().any(|f| f == *n))
+            .map(|n| n.to_string())
+    };
+
+    match service {
+        Service::Mysql => first_of(&["mysql", "mariadb"]).or_else(|| {
+            newest_versioned(
+                formulae
+                    .iter()
+                    .filter(|f| f.starts_with("mysql@"))
+                    .collect(),
+            )
+        }),
+        Service::PhpFpm => first_of(&["php"]).or_else(|| {
+            newest_versioned(formulae.iter().filter(|f| f.starts_with("php@")).collect())
+        }),
+        Service::WebServer => match web_server() {
+            WebServer::Apache => first_of(&["httpd"]),
+            WebServer::Nginx => first_of(&["nginx"]),
+        },
+    }
+}
+
+fn resolve_windows_service(service: Service) -> Option<String> {
+    let candidates: &[&str] = match service {
+        Service::Mysql => &["MySQL", "wampmysqld64", "MySQL80"],
+        Service::WebServer => &["Apache2.4", "wampapache64", "Apache"],
+        // PHP-FPM is not a Windows service typically
+        Service::PhpFpm => &[],
+    };
+
+    candidates
+        .iter()
+        .find(|name| {
+            run_windows_command("sc", &["query", name])
+                .map(|o| o.status.success())
+                .unwrap_or(false)
+        })
+        .map(|name| name.to_string())
+}
+
+/// Resolve the platform-specific unit name for a service, if it is installed
+fn resolve_unit(service: Service) -> Option<String> {
+    if cfg!(target_os = "windows") {
+        resolve_windows_service(service)
+    } else if cfg!(target_os = "macos") {
+        resolve_brew_formula(service)
+    } else {
+        resolve_linux_unit(service)
+    }
+}
+
+// ============================================
+// Actions
+// ============================================
+
+fn output_result(output: std::io::Result<std::process::Output>) -> Result<(), String> {
+    match output {
+        Ok(out) if out.status.success() => Ok(()),
+        Ok(out) => Err(String::from_utf8_lossy(&out.stderr).trim().to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+fn run_windows_action(unit: &str, action: ServiceAction) -> Result<(), String> {
+    let run = |verb: &str| {
+        // Try sc first, then net as fallback
+        output_result(run_windows_command("sc", &[verb, unit]))
+            .or_else(|_| output_result(run_windows_command("net", &[verb, unit])))
+    };
+
+    match action {
+        ServiceAction::Start => run("start"),
+        ServiceAction::Stop => run("stop"),
+        ServiceAction::Restart => {
+            // A stopped service can't be stopped; only the start has to succeed
+            let _ = run("stop");
+            std::thread::sleep(std::time::Duration::from_secs(1));
+            run("start")
+        }
+    }
+}
+
+fn run_action(unit: &str, action: ServiceAction) -> Result<(), String> {
+    if cfg!(target_os = "windows") {
+        run_windows_action(unit, action)
+    } else if cfg!(target_os = "macos") {
+        output_result(
+            Command::new("brew")
+                .args(["services", action.as_str(), unit])
+                .output(),
+        )
+    } else {
+        output_result(
+            Command::new("systemctl")
+                .args([action.as_str(), unit])
+                .output(),
+        )
+    }
+}
+
+/// Run an action against a single service
+pub fn control(service: Service, action: ServiceAction) -> Result<String, String> {
+    if cfg!(target_os = "windows") && service == Service::PhpFpm {
+        return Ok(format!(
+            "Service {} does not run as a Windows service",
+            service.id()
+        ));
+    }
+
+    let unit = resolve_unit(service)
+        .ok_or_else(|| format!("Service {} is not installed", service.id()))?;
+
+    run_action(&unit, action)
+        .map(|_| {
+            format!(
+                "Service {} {} successfully",
+                service.id(),
+                action.past_tense()
+            )
+        })
+        .map_err(|e| format!("Failed to {} {}: {}", action.as_str(), service.id(), e))
+}
+
+/// Run an action against every managed service in dependency order.
+/// Starting skips a service whose dependency failed; stopping goes in reverse.
+pub fn control_all(action: ServiceAction) -> Vec<ServiceOutcome> {
+    let mut order = Service::START_ORDER.to_vec();
+
+    if action == ServiceAction::Stop {
+        order.reverse();
+    }
+
+    if action == ServiceAction::Restart {
+        // Stop everything top-down first so dependents never talk to a stopped backend
+        for service in order.iter().rev() {
+            if let Some(unit) = resolve_unit(*service) {
+                let _ = run_action(&unit, ServiceAction::Stop);
+            }
+        }
+    }
+
+    let start_action = if action == ServiceAction::Restart {
+        ServiceAction::Start
+    } else {
+        action
+    };
+
+    let mut failed: Vec<Service> = Vec::new();
+    let mut outcomes = Vec::new();
+
+    for service in order {
+        let label = service.label().to_string();
+
+        if start_action == ServiceAction::Start {
+            if let Some(dep) = service.depends_on().iter().find(|d| failed.contains(d)) {
+                failed.push(service);
+                outcomes.push(ServiceOutcome {
+                    service: label,
+                    success: false,
+                    skipped: true,
+                    message: format!("Skipped because {} failed", dep.label()),
+                });
+                continue;
+            }
+        }
+
+        let Some(unit) = resolve_unit(service) else {
+            outcomes.push(ServiceOutcome {
+                service: label,
+                success: true,
+                skipped: true,
+                message: "Not installed".to_string(),
+            });
+            continue;
+        };
+
+        match run_action(&unit, start_action) {
+            Ok(()) => outcomes.push(ServiceOutcome {
+                service: label,
+                success: true,
+                skipped: false,
+                message: format!("{} {}", unit, action.past_tense()),
+            }),
+            Err(e) => {
+                failed.push(service);
+                outcomes.push(ServiceOutcome {
+                    service: label,
+                    success: false,
+                    skipped: false,
+                    message: e,
+                });
+            }
+        }
+    }
+
+    outcomes
+}
+
+/// One-line-per-service summary for notifications
+fn summarize(outcomes: &[ServiceOutcome]) -> String {
+    outcomes
+        .iter()
+        .map(|o| {
+            let mark = match (o.success, o.skipped) {
+                (true, false) => "✓",
+                (true, true) => "–",
+                (false, _) => "✗",
+            };
+            format!("{} {}: {}", mark, o.service, o.message)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Run `control_all` off the UI thread and report the result as a system notification.
+/// Used by the tray menu, which has no window to show errors in.
+pub fn control_all_with_notification(app: &AppHandle, action: ServiceAction) {
+    let app = app.clone();
+
+    std::thread::spawn(move || {
+        let outcomes = control_all(action);
+        let all_ok = outcomes.iter().all(|o| o.success);
+
+        let title = if all_ok {
+            format!("Services {}", action.past_tense())
+        } else {
+            format!("Some services failed to {}", action.as_str())
+        };
+
+        let _ = app
+            .notification()
+            .builder()
+            .title(title)
+            .body(summarize(&outcomes))
+            .show();
+    });
+}
+
+#[tauri::command]
+pub async fn control_all_services(action: String) -> Result<Vec<ServiceOutcome>, String> {
+    let action = ServiceAction::parse(&action)?;
+
+    tauri::async_runtime::spawn_blocking(move || control_all(action))
+        .await
+        .map_err(|e| e.to_string())
+}
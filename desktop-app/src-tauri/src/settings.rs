@@ -0,0 +1,553 @@
+// ============================================
+// Application Settings
+// ============================================
+// Typed view of settings.json. The setup wizard writes the first version;
+// everything else goes through `update_settings`, which persists the change
+// and emits `settings-changed` so the rest of the app picks it up live.
+
+use crate::config::AppConfig;
+use crate::fastcgi::Address;
+use crate::paths;
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use std::process::Command;
+use tauri::{AppHandle, Emitter};
+
+/// Bump when the on-disk format changes and add a step to `migrate`
+pub const SETTINGS_SCHEMA_VERSION: u32 = 1;
+
+/// Emitted with the full `Settings` payload after every successful update
+pub const SETTINGS_CHANGED_EVENT: &str = "settings-changed";
+
+/// static-php-cli builds: `php-<version>-<sapi>-<os>-<arch>.tar.gz`
+pub const DEFAULT_PHP_MIRROR: &str = "https://dl.static-php.dev/static-php-cli/common";
+
+/// WordPress release archive used when scaffolding new projects
+pub const DEFAULT_WORDPRESS_DOWNLOAD: &str = "https://wordpress.org/latest.tar.gz";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebServer {
+    Apache,
+    Nginx,
+}
+
+/// Where PHP settings and extension toggles are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PhpConfigMode {
+    /// `99-localhost-manager.ini` in each SAPI's scan directory, which
+    /// package upgrades leave alone
+    ConfD,
+    /// Edit the distro-owned php.ini in place
+    PhpIni,
+}
+
+/// Ports and listen addresses of the managed services.
+/// Lets the stack run unprivileged (8080/8443) or next to a proxy that owns 443.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Ports {
+    pub http: u16,
+    pub https: u16,
+    pub php_fpm: u16,
+    pub mysql: u16,
+    /// Address the web server binds its virtual hosts to, `*` for all interfaces
+    pub bind_address: String,
+    /// Host PHP-FPM listens on when using TCP
+    pub php_fpm_host: String,
+    /// Unix socket PHP-FPM listens on; takes precedence over host/port when set
+    pub php_fpm_socket: Option<String>,
+}
+
+impl Default for Ports {
+    fn default() -> Self {
+        Self {
+            http: 80,
+            https: 443,
+            php_fpm: 9000,
+            mysql: 3306,
+            bind_address: "*".to_string(),
+            php_fpm_host: "127.0.0.1".to_string(),
+            php_fpm_socket: None,
+        }
+    }
+}
+
+impl Ports {
+    /// Shared pool address: the socket when set, otherwise `host:port` with
+    /// IPv6 hosts bracketed (`[::1]:9000`)
+    pub fn fpm_address(&self) -> String {
+        match self.php_fpm_socket.as_deref().filter(|s| !s.is_empty()) {
+            Some(socket) => socket.to_string(),
+            None => match self.php_fpm_host.parse::<IpAddr>() {
+                Ok(IpAddr::V6(ip)) => format!("[{}]:{}", ip, self.php_fpm),
+                _ => format!("{}:{}", self.php_fpm_host, self.php_fpm),
+            },
+        }
+    }
+
+    /// Apache `SetHandler` target for a pool's `listen` (`host:port`, a bare
+    /// port or a socket path)
+    pub fn handler_for(listen: &str) -> String {
+        match Address::parse(listen) {
+            Address::Unix(path) => format!("proxy:unix:{}|fcgi://localhost", path.display()),
+            Address::Tcp(addr) => format!("proxy:fcgi://{}", addr),
+        }
+    }
+
+    /// nginx `fastcgi_pass` target for a pool's `listen`
+    pub fn fastcgi_pass_for(listen: &str) -> String {
+        match Address::parse(listen) {
+            Address::Unix(path) => format!("unix:{}", path.display()),
+            Address::Tcp(addr) => addr,
+        }
+    }
+
+    /// Apache `SetHandler` target for PHP files served by the shared pool
+    pub fn fpm_handler(&self) -> String {
+        Self::handler_for(&self.fpm_address())
+    }
+
+    /// IP written to the system hosts file. Follows the bind address when it is a
+    /// specific address so local domains resolve to where the server listens.
+    pub fn hosts_ip(&self) -> String {
+        match self.bind_address.parse::<IpAddr>() {
+            Ok(ip) if !ip.is_unspecified() => ip.to_string(),
+            _ => "127.0.0.1".to_string(),
+        }
+    }
+
+    /// Address to probe when checking that the web server is up
+    pub fn probe_ip(&self) -> IpAddr {
+        self.hosts_ip()
+            .parse()
+            .unwrap_or(IpAddr::from([127, 0, 0, 1]))
+    }
+
+    /// Browser URL for a local domain, omitting the port when it is the scheme default
+    pub fn url(&self, domain: &str, ssl: bool) -> String {
+        match (ssl, self.https, self.http) {
+            (true, 443, _) => format!("https://{}/", domain),
+            (true, port, _) => format!("https://{}:{}/", domain, port),
+            (false, _, 80) => format!("http://{}/", domain),
+            (false, _, port) => format!("http://{}:{}/", domain, port),
+        }
+    }
+
+    /// Bind address as it goes in front of `:port` in Apache and nginx
+    /// directives, with IPv6 addresses bracketed (`[::1]:443`)
+    pub fn listen_address(&self) -> String {
+        match self.bind_address.parse::<IpAddr>() {
+            Ok(IpAddr::V6(ip)) => format!("[{}]", ip),
+            _ => self.bind_address.clone(),
+        }
+    }
+
+    /// Export ports and addresses to the generator and install scripts
+    pub fn apply_env(&self, cmd: &mut Command) {
+        cmd.env("HTTP_PORT", self.http.to_string())
+            .env("HTTPS_PORT", self.https.to_string())
+            .env("MYSQL_PORT", self.mysql.to_string())
+            .env("BIND_ADDRESS", self.listen_address())
+            .env("HOSTS_IP", self.hosts_ip())
+            .env("FPM_HANDLER", self.fpm_handler());
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let mut ports = vec![
+            ("HTTP", self.http),
+            ("HTTPS", self.https),
+            ("MySQL", self.mysql),
+        ];
+        // A socket frees the FPM port, so it can't clash with anything
+        if self.php_fpm_socket.as_deref().unwrap_or("").is_empty() {
+            ports.push(("PHP-FPM", self.php_fpm));
+        }
+
+        for (name, port) in &ports {
+            if *port == 0 {
+                return Err(format!("{} port must be between 1 and 65535", name));
+            }
+        }
+
+        for (i, (name, port)) in ports.iter().enumerate() {
+            if let Some((other, _)) = ports[i + 1..].iter().find(|(_, p)| p == port) {
+                return Err(format!("{} and {} can't share port {}", name, other, port));
+            }
+        }
+
+        if self.bind_address != "*" && self.bind_address.parse::<IpAddr>().is_err() {
+            return Err(format!(
+                "Bind address must be * or an IP address, got '{}'",
+                self.bind_address
+            ));
+        }
+
+        if self.php_fpm_host.parse::<IpAddr>().is_err() && !valid_hostname(&self.php_fpm_host) {
+            return Err(format!(
+                "PHP-FPM host must be an IP address or a hostname, got '{}'",
+                self.php_fpm_host
+            ));
+        }
+
+        if let Some(socket) = self.php_fpm_socket.as_deref().filter(|s| !s.is_empty()) {
+            if !std::path::Path::new(socket).is_absolute() {
+                return Err(format!(
+                    "PHP-FPM socket must be an absolute path: {}",
+                    socket
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// RFC 1123 hostname: dot-separated labels of letters, digits and inner hyphens
+fn valid_hostname(host: &str) -> bool {
+    !host.is_empty()
+        && host.len() <= 253
+        && host.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct UiPreferences {
+    pub language: Option<String>,
+    pub theme: String,
+    pub minimize_to_tray: bool,
+    pub notifications: bool,
+    pub watch_hosts_file: bool,
+}
+
+impl Default for UiPreferences {
+    fn default() -> Self {
+        Self {
+            language: None,
+            theme: "system".to_string(),
+            minimize_to_tray: true,
+            notifications: true,
+            watch_hosts_file: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    pub schema_version: u32,
+    pub stack: String,
+    pub projects_path: Option<String>,
+    /// Register new project folders under projects_path as they appear
+    pub park_projects: bool,
+    pub config_path: Option<String>,
+    pub ssl_path: Option<String>,
+    pub default_php_version: Option<String>,
+    /// Base URL serving prebuilt static PHP archives and their `.sha256` files
+    pub php_mirror_url: String,
+    /// `.tar.gz` of WordPress (a localized build or a local mirror works too)
+    pub wordpress_download_url: String,
+    pub php_config_mode: PhpConfigMode,
+    pub web_server: WebServer,
+    pub ports: Ports,
+    pub ui: UiPreferences,
+    pub setup_completed: bool,
+    pub setup_date: Option<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            schema_version: SETTINGS_SCHEMA_VERSION,
+            stack: "native".to_string(),
+            projects_path: None,
+            park_projects: false,
+            config_path: None,
+            ssl_path: None,
+            default_php_version: None,
+            php_mirror_url: DEFAULT_PHP_MIRROR.to_string(),
+            wordpress_download_url: DEFAULT_WORDPRESS_DOWNLOAD.to_string(),
+            php_config_mode: PhpConfigMode::ConfD,
+            web_server: WebServer::Apache,
+            ports: Ports::default(),
+            ui: UiPreferences::default(),
+            setup_completed: false,
+            setup_date: None,
+        }
+    }
+}
+
+/// Upgrade a raw settings document to the current schema.
+/// Version 0 is the flat file written by the original setup wizard
+/// (stack/projectsPath/configPath/sslPath/setupCompleted/setupDate).
+fn migrate(mut value: serde_json::Value) -> serde_json::Value {
+    let version = value
+        .get("schemaVersion")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0);
+
+    if version < 1 {
+        if let Some(obj) = value.as_object_mut() {
+            // The default PHP version used to live only in config.json
+            if !obj.contains_key("defaultPhpVersion") {
+                if let Some(php) = AppConfig::load().ok().and_then(|c| c.default_php_version) {
+                    obj.insert("defaultPhpVersion".to_string(), php.into());
+                }
+            }
+            obj.insert("schemaVersion".to_string(), 1.into());
+        }
+    }
+
+    value
+}
+
+/// Recursively apply `patch` onto `target`; `null` in the patch drops the
+/// key, so it deserializes back to its default (`None` for optional values)
+fn merge_json(target: &mut serde_json::Value, patch: serde_json::Value) {
+    match (target, patch) {
+        (serde_json::Value::Object(target), serde_json::Value::Object(patch)) => {
+            for (key, value) in patch {
+                if value.is_null() {
+                    target.remove(&key);
+                } else {
+                    merge_json(target.entry(key).or_insert(serde_json::Value::Null), value);
+                }
+            }
+        }
+        (target, patch) => *target = patch,
+    }
+}
+
+/// Settings after merging a partial object into `current`
+fn patched(current: &Settings, patch: serde_json::Value) -> Result<Settings, String> {
+    let mut value = serde_json::to_value(current)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    merge_json(&mut value, patch);
+
+    let mut updated: Settings =
+        serde_json::from_value(value).map_err(|e| format!("Invalid settings: {}", e))?;
+    updated.schema_version = SETTINGS_SCHEMA_VERSION;
+    Ok(updated)
+}
+
+impl Settings {
+    /// Load settings, migrating older files in place.
+    /// Falls back to the pre-layout location next to hosts.json.
+    pub fn load() -> anyhow::Result<Self> {
+        let (content, legacy) = match std::fs::read_to_string(paths::settings_file()) {
+            Ok(content) => (content, false),
+            Err(_) => match std::fs::read_to_string(paths::legacy_settings_file()) {
+                Ok(content) => (content, true),
+                Err(_) => return Ok(Self::default()),
+            },
+        };
+
+        let raw: serde_json::Value = serde_json::from_str(&content)?;
+        let migrated = migrate(raw.clone());
+        // Only a migration (or a move out of the legacy location) rewrites the file
+        let changed = legacy || migrated != raw;
+        let settings: Self = serde_json::from_value(migrated)?;
+
+        if changed {
+            settings.save()?;
+        }
+
+        Ok(settings)
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        let settings_file = paths::settings_file();
+        if let Some(parent) = settings_file.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        // Write to a temp file first so a crash never leaves half a settings file
+        let tmp_file = settings_file.with_extension("json.tmp");
+        std::fs::write(&tmp_file, serde_json::to_string_pretty(self)?)?;
+        std::fs::rename(&tmp_file, &settings_file)?;
+
+        // Keep config.json in sync for code that still reads AppConfig
+        let mut app_config = AppConfig::load().unwrap_or_default();
+        if app_config.default_php_version != self.default_php_version {
+            app_config.default_php_version = self.default_php_version.clone();
+            app_config.save()?;
+        }
+
+        Ok(())
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        // The Windows scripts only generate Apache vhosts
+        if cfg!(target_os = "windows") && self.web_server == WebServer::Nginx {
+            return Err("nginx isn't supported on Windows yet; use Apache".to_string());
+        }
+        self.ports.validate()
+    }
+}
+
+#[tauri::command]
+pub async fn get_settings() -> Result<Settings, String> {
+    Settings::load().map_err(|e| format!("Failed to load settings: {}", e))
+}
+
+/// Merge a partial settings object into the current settings, persist it
+/// and broadcast the result
+#[tauri::command]
+pub async fn update_settings(app: AppHandle, patch: serde_json::Value) -> Result<Settings, String> {
+    let current = Settings::load().map_err(|e| format!("Failed to load settings: {}", e))?;
+
+    let updated = patched(&current, patch)?;
+    updated.validate()?;
+
+    if updated == current {
+        return Ok(updated);
+    }
+
+    updated
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    let _ = app.emit(SETTINGS_CHANGED_EVENT, &updated);
+
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ports(bind_address: &str) -> Ports {
+        Ports {
+            bind_address: bind_address.to_string(),
+            ..Ports::default()
+        }
+    }
+
+    #[test]
+    fn ipv6_bind_addresses_are_bracketed_for_the_generators() {
+        assert_eq!(ports("*").listen_address(), "*");
+        assert_eq!(ports("127.0.0.2").listen_address(), "127.0.0.2");
+        assert_eq!(ports("::1").listen_address(), "[::1]");
+        assert_eq!(ports("fd00::0:1").listen_address(), "[fd00::1]");
+
+        // The hosts file takes the bare address
+        assert_eq!(ports("::1").hosts_ip(), "::1");
+        assert_eq!(ports("::").hosts_ip(), "127.0.0.1");
+    }
+
+    fn fpm(host: &str, socket: Option<&str>) -> Ports {
+        Ports {
+            php_fpm_host: host.to_string(),
+            php_fpm_socket: socket.map(String::from),
+            ..Ports::default()
+        }
+    }
+
+    #[test]
+    fn fpm_handlers_bracket_ipv6_hosts_and_prefer_the_socket() {
+        assert_eq!(
+            fpm("127.0.0.1", None).fpm_handler(),
+            "proxy:fcgi://127.0.0.1:9000"
+        );
+        assert_eq!(fpm("::1", None).fpm_handler(), "proxy:fcgi://[::1]:9000");
+        assert_eq!(fpm("php.internal", None).fpm_address(), "php.internal:9000");
+        assert_eq!(
+            fpm("::1", Some("/run/php/fpm.sock")).fpm_handler(),
+            "proxy:unix:/run/php/fpm.sock|fcgi://localhost"
+        );
+        // An empty socket falls back to host and port
+        assert_eq!(fpm("127.0.0.1", Some("")).fpm_address(), "127.0.0.1:9000");
+
+        assert_eq!(Ports::handler_for("9001"), "proxy:fcgi://127.0.0.1:9001");
+        assert_eq!(Ports::fastcgi_pass_for("[::1]:9001"), "[::1]:9001");
+        assert_eq!(Ports::fastcgi_pass_for("/tmp/a.sock"), "unix:/tmp/a.sock");
+    }
+
+    #[test]
+    fn validates_fpm_hosts() {
+        for host in [
+            "127.0.0.1",
+            "::1",
+            "localhost",
+            "php-fpm",
+            "fpm.docker.internal",
+        ] {
+            assert!(fpm(host, None).validate().is_ok(), "{}", host);
+        }
+        for host in [
+            "",
+            " ",
+            "[::1]",
+            "-fpm",
+            "fpm..local",
+            "fpm:9000",
+            "fpm host",
+        ] {
+            assert!(fpm(host, None).validate().is_err(), "{:?}", host);
+        }
+    }
+
+    #[test]
+    fn validates_bind_addresses() {
+        assert!(ports("*").validate().is_ok());
+        assert!(ports("::1").validate().is_ok());
+        assert!(ports("[::1]").validate().is_err());
+        assert!(ports("localhost").validate().is_err());
+    }
+
+    #[test]
+    fn null_resets_to_the_default() {
+        let mut current = Settings {
+            stack: "xampp".to_string(),
+            php_mirror_url: "https://mirror.test/php".to_string(),
+            projects_path: Some("/srv/projects".to_string()),
+            ..Settings::default()
+        };
+        current.ports.http = 8080;
+        current.ports.https = 8443;
+
+        let updated = patched(
+            &current,
+            serde_json::json!({
+                "stack": null,
+                "phpMirrorUrl": null,
+                "projectsPath": null,
+                "ports": { "http": null },
+            }),
+        )
+        .unwrap();
+
+        let defaults = Settings::default();
+        assert_eq!(updated.stack, defaults.stack);
+        assert_eq!(updated.php_mirror_url, defaults.php_mirror_url);
+        assert_eq!(updated.projects_path, None);
+        assert_eq!(updated.ports.http, defaults.ports.http);
+        // Keys left out of the patch keep their value
+        assert_eq!(updated.ports.https, 8443);
+    }
+
+    #[test]
+    fn patches_still_set_values() {
+        let updated = patched(
+            &Settings::default(),
+            serde_json::json!({ "stack": "mamp", "ui": { "watchHostsFile": true } }),
+        )
+        .unwrap();
+        assert_eq!(updated.stack, "mamp");
+        assert!(updated.ui.watch_hosts_file);
+        assert!(patched(&Settings::default(), serde_json::json!({ "stack": 3 })).is_err());
+    }
+
+    #[test]
+    fn current_documents_need_no_migration() {
+        let current = serde_json::to_value(Settings::default()).unwrap();
+        assert_eq!(migrate(current.clone()), current);
+    }
+}
@@ -3,6 +3,7 @@
 // ============================================
 
 use std::env;
+use std::path::{Path, PathBuf};
 
 /// Get the system's current language/locale
 #[tauri::command]
@@ -68,48 +69,158 @@ fn map_language_code(code: &str) -> String {
 /// On Linux: Uses pkexec or similar
 #[tauri::command]
 pub async fn execute_with_privileges(command: String, args: Vec<String>) -> Result<String, String> {
-    #[cfg(target_os = "macos")]
-    {
-        execute_with_applescript_sudo(command, args).await
-    }
-
     #[cfg(target_os = "windows")]
     {
         execute_with_uac(command, args).await
     }
 
-    #[cfg(target_os = "linux")]
+    #[cfg(not(target_os = "windows"))]
     {
-        execute_with_pkexec(command, args).await
+        let line: Vec<&str> = std::iter::once(command.as_str())
+            .chain(args.iter().map(String::as_str))
+            .collect();
+
+        let output = privileged_command(&line)
+            .output()
+            .map_err(|e| format!("Failed to execute: {}", e))?;
+
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        } else {
+            Err(String::from_utf8_lossy(&output.stderr).to_string())
+        }
     }
 }
 
-#[cfg(target_os = "macos")]
-async fn execute_with_applescript_sudo(
-    command: String,
-    args: Vec<String>,
-) -> Result<String, String> {
-    // Build the full command
-    let full_command = format!("{} {}", command, args.join(" "));
+/// Programs `privileged_command` runs through; what they start belongs to root
+const ELEVATORS: [&str; 3] = ["osascript", "pkexec", "sudo"];
 
-    // Use AppleScript to request admin privileges
-    let script = format!(
-        r#"do shell script "{}" with administrator privileges"#,
-        full_command.replace("\"", "\\\"")
-    );
+/// Command running `args` as root: the administrator dialog on macOS, the
+/// desktop's polkit prompt on Linux
+pub(crate) fn privileged_command(args: &[&str]) -> std::process::Command {
+    if cfg!(target_os = "macos") {
+        let mut cmd = std::process::Command::new("osascript");
+        cmd.arg("-e").arg(admin_script(args));
+        cmd
+    } else {
+        let mut cmd = std::process::Command::new("pkexec");
+        cmd.args(args);
+        cmd
+    }
+}
 
-    let output = std::process::Command::new("osascript")
-        .args(["-e", &script])
-        .output()
-        .map_err(|e| format!("Failed to execute: {}", e))?;
+/// Whether `cmd` goes through an elevation prompt, so the processes it
+/// starts can only be signalled as root
+pub(crate) fn is_privileged(cmd: &std::process::Command) -> bool {
+    ELEVATORS.contains(&cmd.get_program().to_string_lossy().as_ref())
+}
 
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
+/// Write a file, asking for elevation when its directory is root-owned
+pub async fn write_file_privileged(dest: &Path, content: &str) -> Result<(), String> {
+    match std::fs::write(dest, content) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+            let (staging_dir, staged) = stage_private(dest, content)?;
+
+            #[cfg(unix)]
+            let (command, mut args) = (
+                "install".to_string(),
+                vec!["-m".to_string(), "0644".to_string()],
+            );
+            // `cp` is only a PowerShell alias; an elevated process needs a real program
+            #[cfg(not(unix))]
+            let (command, mut args) = (
+                "cmd".to_string(),
+                vec!["/c".to_string(), "copy".to_string(), "/Y".to_string()],
+            );
+            args.push(staged.to_string_lossy().to_string());
+            args.push(dest.to_string_lossy().to_string());
+
+            let result = execute_with_privileges(command, args).await.map(|_| ());
+            let _ = std::fs::remove_dir_all(&staging_dir);
+            result.map_err(|e| format!("Failed to install {}: {}", dest.display(), e))
+        }
+        Err(e) => Err(format!("Failed to write {}: {}", dest.display(), e)),
     }
 }
 
+/// Stage content for a root copy in a fresh directory only we can enter,
+/// so no other local user can plant a symlink or swap the file before root
+/// reads it.
+fn stage_private(dest: &Path, content: &str) -> Result<(PathBuf, PathBuf), String> {
+    use std::io::Write;
+
+    let file_name = dest
+        .file_name()
+        .ok_or_else(|| format!("Invalid file name {}", dest.display()))?;
+    let dir = std::env::temp_dir().join(format!("localhost-manager-{}", uuid::Uuid::new_v4()));
+
+    // `create` (not `create_dir_all`) fails if anything already sits at the path
+    let mut builder = std::fs::DirBuilder::new();
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder
+        .create(&dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+    let staged = dir.join(file_name);
+    let written = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&staged)
+        .and_then(|mut file| file.write_all(content.as_bytes()));
+    if let Err(e) = written {
+        let _ = std::fs::remove_dir_all(&dir);
+        return Err(format!("Failed to write {}: {}", staged.display(), e));
+    }
+
+    Ok((dir, staged))
+}
+
+/// Remove a file, asking for elevation when its directory is root-owned
+pub async fn remove_file_privileged(path: &Path) -> Result<(), String> {
+    match std::fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+            #[cfg(unix)]
+            let (command, mut args) = ("rm".to_string(), vec!["-f".to_string()]);
+            #[cfg(not(unix))]
+            let (command, mut args) = (
+                "cmd".to_string(),
+                vec![
+                    "/c".to_string(),
+                    "del".to_string(),
+                    "/F".to_string(),
+                    "/Q".to_string(),
+                ],
+            );
+            args.push(path.to_string_lossy().to_string());
+
+            execute_with_privileges(command, args)
+                .await
+                .map(|_| ())
+                .map_err(|e| format!("Failed to remove {}: {}", path.display(), e))
+        }
+        Err(e) => Err(format!("Failed to remove {}: {}", path.display(), e)),
+    }
+}
+
+/// AppleScript running a command as root behind the administrator dialog.
+/// Each argument is single-quoted for the shell, then the whole line is
+/// escaped for the AppleScript string, so spaces and quotes survive.
+fn admin_script(args: &[&str]) -> String {
+    let line = args
+        .iter()
+        .map(|arg| format!("'{}'", arg.replace('\'', "'\\''")))
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!(
+        "do shell script \"{}\" with administrator privileges",
+        line.replace('\\', "\\\\").replace('"', "\\\"")
+    )
+}
+
 #[cfg(target_os = "windows")]
 async fn execute_with_uac(command: String, args: Vec<String>) -> Result<String, String> {
     use std::os::windows::process::CommandExt;
@@ -117,15 +228,26 @@ async fn execute_with_uac(command: String, args: Vec<String>) -> Result<String,
     // CREATE_NO_WINDOW = 0x08000000
     const CREATE_NO_WINDOW: u32 = 0x08000000;
 
+    // Start-Process joins -ArgumentList with spaces, so each argument carries
+    // its own double quotes; single quotes are doubled for the PowerShell literal
+    let ps_literal = |value: &str| format!("'{}'", value.replace('\'', "''"));
+    let argument_list = if args.is_empty() {
+        String::new()
+    } else {
+        let quoted = args
+            .iter()
+            .map(|arg| ps_literal(&format!("\"{}\"", arg.replace('"', "\\\""))))
+            .collect::<Vec<_>>();
+        format!(" -ArgumentList @({})", quoted.join(","))
+    };
+    let script = format!(
+        "$p = Start-Process -FilePath {}{} -Verb RunAs -Wait -PassThru -WindowStyle Hidden; exit $p.ExitCode",
+        ps_literal(&command),
+        argument_list
+    );
+
     let output = std::process::Command::new("powershell")
-        .args(&[
-            "-Command",
-            &format!(
-                "Start-Process -FilePath '{}' -ArgumentList '{}' -Verb RunAs -Wait -WindowStyle Hidden",
-                command,
-                args.join(" ")
-            ),
-        ])
+        .args(["-NoProfile", "-Command", &script])
         .creation_flags(CREATE_NO_WINDOW)
         .output()
         .map_err(|e| format!("Failed to execute: {}", e))?;
@@ -137,20 +259,38 @@ async fn execute_with_uac(command: String, args: Vec<String>) -> Result<String,
     }
 }
 
-#[cfg(target_os = "linux")]
-async fn execute_with_pkexec(command: String, args: Vec<String>) -> Result<String, String> {
-    // Try pkexec first (PolicyKit - GNOME/KDE)
-    let mut cmd = std::process::Command::new("pkexec");
-    cmd.arg(&command);
-    cmd.args(&args);
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    let output = cmd
-        .output()
-        .map_err(|e| format!("Failed to execute with pkexec: {}", e))?;
+    #[test]
+    fn elevated_commands_are_recognised() {
+        assert!(is_privileged(&privileged_command(&[
+            "kill", "-TERM", "--", "-1"
+        ])));
+        assert!(is_privileged(&std::process::Command::new("sudo")));
+        assert!(!is_privileged(&std::process::Command::new("brew")));
+    }
 
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
+    #[test]
+    fn admin_script_quotes_for_the_shell_and_applescript() {
+        assert_eq!(
+            admin_script(&["install", "-m", "0644", "/tmp/a", "/etc/hosts"]),
+            r#"do shell script "'install' '-m' '0644' '/tmp/a' '/etc/hosts'" with administrator privileges"#
+        );
+        assert_eq!(
+            admin_script(&[
+                "/usr/local/bin/pecl",
+                "-d",
+                "php_suffix=",
+                "install",
+                "redis"
+            ]),
+            r#"do shell script "'/usr/local/bin/pecl' '-d' 'php_suffix=' 'install' 'redis'" with administrator privileges"#
+        );
+        assert_eq!(
+            admin_script(&["/Users/me/Library/Application Support/x \"dev\"", "it's"]),
+            r#"do shell script "'/Users/me/Library/Application Support/x \"dev\"' 'it'\\''s'" with administrator privileges"#
+        );
     }
 }
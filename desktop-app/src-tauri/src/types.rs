@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PhpVersion {
     pub version: String,
     pub major: u8,
@@ -9,6 +9,28 @@ pub struct PhpVersion {
     pub installed: bool,
     pub install_path: Option<String>,
     pub download_url: Option<String>,
+    /// CLI binary of an installed version
+    #[serde(default)]
+    pub binary: Option<String>,
+    /// Available SAPIs (`cli`, `fpm`, `cgi`, `phpdbg`, `apache2handler`)
+    #[serde(default)]
+    pub sapis: Vec<String>,
+    /// php.ini per SAPI; SAPIs sharing one file all point to it
+    #[serde(default)]
+    pub ini_paths: std::collections::BTreeMap<String, String>,
+    /// Directory scanned for additional .ini files
+    #[serde(default)]
+    pub ini_scan_dir: Option<String>,
+    /// Where the install was found (`debian`, `remi`, `homebrew`, `phpenv`, ...)
+    #[serde(default)]
+    pub source: Option<String>,
+}
+
+impl PhpVersion {
+    /// `8.3` for PHP 8.3.14: how packages, config directories and pools name it
+    pub fn branch(&self) -> String {
+        format!("{}.{}", self.major, self.minor)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,7 +49,6 @@ pub struct PhpConfig {
     pub settings: std::collections::HashMap<String, String>,
 }
 
-#[allow(dead_code)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProjectProfile {
     pub id: String,
@@ -36,11 +57,16 @@ pub struct ProjectProfile {
     pub php_version: String,
     pub extensions: Vec<String>,
     pub custom_ini_settings: std::collections::HashMap<String, String>,
+    /// Address of the project's PHP-FPM pool, assigned on first save
+    #[serde(default)]
+    pub fpm_listen: Option<String>,
 }
 
-#[allow(dead_code)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DownloadProgress {
+    pub version: String,
+    /// `downloading`, `verifying`, `extracting`, `done` or `error`
+    pub stage: String,
     pub downloaded: u64,
     pub total: u64,
     pub percentage: f64,
@@ -64,6 +90,36 @@ pub struct VirtualHost {
     pub ssl: bool,
     #[serde(rename = "type")]
     pub host_type: String,
+    /// Project profile whose PHP-FPM pool serves this host
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// PHP-FPM pool serving this host when it has no profile
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fpm_pool: Option<FpmPoolRef>,
+    /// Framework the project inspector detected (`laravel`, `wordpress`, ...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub framework: Option<String>,
+    /// Extra Apache directives for the docroot (rewrite rules)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rewrites: Vec<String>,
+    /// vhost template to use instead of the one its framework or type implies
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    /// Also serve every subdomain (`*.<domain>`)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub wildcard: bool,
+    /// Tenant labels written to the hosts file as `<label>.<domain>`, since it
+    /// can't hold wildcards; applies to wildcard aliases too
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subdomains: Vec<String>,
+}
+
+/// A pool by PHP version and name, with the address vhosts send PHP to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FpmPoolRef {
+    pub php_version: String,
+    pub name: String,
+    pub listen: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -72,4 +128,17 @@ pub struct ServicesStatus {
     pub mysql: bool,
     pub php: bool,
     pub all_running: bool,
+    pub ports: PortsStatus,
+    /// Every PHP-FPM pool, checked over FastCGI
+    #[serde(default)]
+    pub pools: Vec<crate::php_fpm::PoolHealth>,
+}
+
+/// Whether each configured port accepts connections
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortsStatus {
+    pub http: bool,
+    pub https: bool,
+    pub php_fpm: bool,
+    pub mysql: bool,
 }
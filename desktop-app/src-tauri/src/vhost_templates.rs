@@ -0,0 +1,523 @@
+// ============================================
+// Virtual Host Templates
+// ============================================
+// What goes inside a host's <VirtualHost> (Apache) or server {} (nginx)
+// depends on what it serves: a front controller needs every unknown path
+// sent to index.php, WordPress multisite rewrites per site, an SPA falls back
+// to index.html, a static site serves files with listings off. nginx ignores
+// .htaccess, so these rules have to live in the server config.
+//
+// Each template has a built-in version per web server; a file at
+// `<config dir>/templates/<server>/<name>.conf` replaces it. Before the
+// generator scripts run, every host's template is rendered into
+// `conf/sites/<domain>.<server>.conf`, which the generated vhosts include.
+//
+// Placeholders: {{domain}}, {{docroot}}, {{php_handler}} (Apache SetHandler
+// target), {{fastcgi_pass}} (nginx) and {{rewrites}} (the host's extra
+// Apache directives, one per line, indented like the placeholder). Framework
+// templates already carry their rules, so {{rewrites}} stays empty for them.
+
+use crate::hosts_manager::load_virtual_hosts;
+use crate::paths::{self, Layout};
+use crate::projects;
+use crate::settings::{Ports, Settings, WebServer};
+use crate::types::VirtualHost;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+pub const TEMPLATES: &[&str] = &[
+    "php",
+    "front-controller",
+    "laravel",
+    "symfony",
+    "wordpress",
+    "wordpress-multisite",
+    "spa",
+    "static",
+];
+
+const SERVERS: &[WebServer] = &[WebServer::Apache, WebServer::Nginx];
+
+const APACHE_PHP_HANDLER: &str = r#"<FilesMatch \.php$>
+    SetHandler "{{php_handler}}"
+</FilesMatch>"#;
+
+const NGINX_PHP_LOCATION: &str = r#"location ~ \.php$ {
+    try_files $uri =404;
+    include fastcgi_params;
+    fastcgi_param SCRIPT_FILENAME $realpath_root$fastcgi_script_name;
+    fastcgi_param DOCUMENT_ROOT $realpath_root;
+    fastcgi_pass {{fastcgi_pass}};
+}
+
+location ~ /\.(?!well-known) {
+    deny all;
+}"#;
+
+fn apache_builtin(name: &str) -> Option<String> {
+    let directory = |options: &str, rules: &str| {
+        format!(
+            "<Directory \"{{{{docroot}}}}\">\n    Options {}\n    AllowOverride All\n    Require all granted\n{}    {{{{rewrites}}}}\n</Directory>",
+            options, rules
+        )
+    };
+    let with_php = |directory: String| format!("{}\n\n{}\n", directory, APACHE_PHP_HANDLER);
+
+    Some(match name {
+        "php" => with_php(directory("Indexes FollowSymLinks", "")),
+        "front-controller" => with_php(directory(
+            "-Indexes +FollowSymLinks",
+            "\n    RewriteEngine On\n    RewriteCond %{REQUEST_FILENAME} !-f\n    RewriteCond %{REQUEST_FILENAME} !-d\n    RewriteRule ^ index.php [QSA,L]\n",
+        )),
+        "laravel" => with_php(directory(
+            "-Indexes +FollowSymLinks",
+            "\n    RewriteEngine On\n    RewriteCond %{HTTP:Authorization} .\n    RewriteRule .* - [E=HTTP_AUTHORIZATION:%{HTTP:Authorization}]\n    RewriteCond %{REQUEST_FILENAME} !-d\n    RewriteCond %{REQUEST_URI} (.+)/$\n    RewriteRule ^ %1 [L,R=301]\n    RewriteCond %{REQUEST_FILENAME} !-f\n    RewriteCond %{REQUEST_FILENAME} !-d\n    RewriteRule ^ index.php [L]\n",
+        )),
+        "symfony" => with_php(directory(
+            "-Indexes +FollowSymLinks",
+            "\n    FallbackResource /index.php\n",
+        )),
+        "wordpress" => with_php(directory(
+            "-Indexes +FollowSymLinks",
+            "\n    RewriteEngine On\n    RewriteRule .* - [E=HTTP_AUTHORIZATION:%{HTTP:Authorization}]\n    RewriteBase /\n    RewriteRule ^index\\.php$ - [L]\n    RewriteCond %{REQUEST_FILENAME} !-f\n    RewriteCond %{REQUEST_FILENAME} !-d\n    RewriteRule . /index.php [L]\n",
+        )),
+        // Subdirectory multisite: /site/wp-admin, /site/wp-content... map to the shared core
+        "wordpress-multisite" => with_php(directory(
+            "-Indexes +FollowSymLinks",
+            "\n    RewriteEngine On\n    RewriteRule .* - [E=HTTP_AUTHORIZATION:%{HTTP:Authorization}]\n    RewriteBase /\n    RewriteRule ^index\\.php$ - [L]\n    RewriteRule ^([_0-9a-zA-Z-]+/)?wp-admin$ $1wp-admin/ [R=301,L]\n    RewriteCond %{REQUEST_FILENAME} -f [OR]\n    RewriteCond %{REQUEST_FILENAME} -d\n    RewriteRule ^ - [L]\n    RewriteRule ^([_0-9a-zA-Z-]+/)?(wp-(content|admin|includes).*) $2 [L]\n    RewriteRule ^([_0-9a-zA-Z-]+/)?(.*\\.php)$ $2 [L]\n    RewriteRule . index.php [L]\n",
+        )),
+        "spa" => format!(
+            "{}\n",
+            directory("-Indexes +FollowSymLinks", "\n    FallbackResource /index.html\n")
+        ),
+        "static" => format!("{}\n", directory("-Indexes +FollowSymLinks", "")),
+        _ => return None,
+    })
+}
+
+fn nginx_builtin(name: &str) -> Option<String> {
+    let with_php = |head: &str| format!("{}\n\n{}\n", head, NGINX_PHP_LOCATION);
+
+    Some(match name {
+        "php" => with_php(
+            "index index.php index.html;\n\nlocation / {\n    try_files $uri $uri/ =404;\n}",
+        ),
+        "front-controller" => with_php(
+            "index index.php;\n\nlocation / {\n    try_files $uri $uri/ /index.php?$query_string;\n}",
+        ),
+        "laravel" => with_php(
+            "index index.php;\ncharset utf-8;\n\nlocation / {\n    try_files $uri $uri/ /index.php?$query_string;\n}\n\nlocation = /favicon.ico { access_log off; log_not_found off; }\nlocation = /robots.txt  { access_log off; log_not_found off; }\n\nerror_page 404 /index.php;",
+        ),
+        // Only the front controller runs; other .php files are never executed
+        "symfony" => "location / {\n    try_files $uri /index.php$is_args$args;\n}\n\nlocation ~ ^/index\\.php(/|$) {\n    fastcgi_split_path_info ^(.+\\.php)(/.*)$;\n    include fastcgi_params;\n    fastcgi_param SCRIPT_FILENAME $realpath_root$fastcgi_script_name;\n    fastcgi_param DOCUMENT_ROOT $realpath_root;\n    fastcgi_pass {{fastcgi_pass}};\n    internal;\n}\n\nlocation ~ \\.php$ {\n    return 404;\n}\n\nlocation ~ /\\.(?!well-known) {\n    deny all;\n}\n".to_string(),
+        "wordpress" => with_php(
+            "index index.php;\n\nlocation / {\n    try_files $uri $uri/ /index.php?$args;\n}",
+        ),
+        "wordpress-multisite" => with_php(
+            "index index.php;\n\nif (!-e $request_filename) {\n    rewrite /wp-admin$ $scheme://$host$request_uri/ permanent;\n    rewrite ^(/[^/]+)?(/wp-.*) $2 last;\n    rewrite ^(/[^/]+)?(/.*\\.php) $2 last;\n}\n\nlocation / {\n    try_files $uri $uri/ /index.php?$args;\n}",
+        ),
+        "spa" => "index index.html;\n\nlocation / {\n    try_files $uri $uri/ /index.html;\n}\n".to_string(),
+        "static" => "index index.html index.htm;\nautoindex off;\n\nlocation / {\n    try_files $uri $uri/ =404;\n}\n".to_string(),
+        _ => return None,
+    })
+}
+
+fn server_name(server: WebServer) -> &'static str {
+    match server {
+        WebServer::Apache => "apache",
+        WebServer::Nginx => "nginx",
+    }
+}
+
+fn builtin(server: WebServer, name: &str) -> Option<String> {
+    match server {
+        WebServer::Apache => apache_builtin(name),
+        WebServer::Nginx => nginx_builtin(name),
+    }
+}
+
+pub fn templates_dir() -> PathBuf {
+    paths::config_dir().join("templates")
+}
+
+fn override_path(server: WebServer, name: &str) -> PathBuf {
+    templates_dir()
+        .join(server_name(server))
+        .join(format!("{}.conf", name))
+}
+
+fn validate_name(name: &str) -> Result<(), String> {
+    if TEMPLATES.contains(&name) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Unknown template '{}'; use {}",
+            name,
+            TEMPLATES.join(", ")
+        ))
+    }
+}
+
+/// The user's override when there is one, else the built-in
+fn source(server: WebServer, name: &str) -> Option<String> {
+    std::fs::read_to_string(override_path(server, name))
+        .ok()
+        .or_else(|| builtin(server, name))
+}
+
+/// Template a host uses: its own choice, else what its framework or type needs
+pub fn template_for(host: &VirtualHost) -> &'static str {
+    if let Some(name) = host.template.as_deref() {
+        if let Some(known) = TEMPLATES.iter().find(|t| **t == name) {
+            return known;
+        }
+    }
+
+    default_template(host.framework.as_deref(), &host.host_type)
+}
+
+/// Template matching a detected framework, or the host type when there's none
+pub fn default_template(framework: Option<&str>, host_type: &str) -> &'static str {
+    match framework {
+        Some("laravel") => "laravel",
+        Some("symfony") => "symfony",
+        Some("wordpress") => "wordpress",
+        Some("drupal") | Some("codeigniter") => "front-controller",
+        Some("vite") | Some("nuxt") => "spa",
+        // A Next.js export has one HTML file per route
+        Some("next") | Some("static") => "static",
+        _ => match host_type {
+            "static" => "static",
+            "vue" | "react" => "spa",
+            _ => "php",
+        },
+    }
+}
+
+/// Templates without framework rules, where a host's own rewrites go
+const PLAIN_TEMPLATES: &[&str] = &["php", "static"];
+
+/// Address of the pool serving a host: its profile's, its mapped pool's,
+/// or None for the shared one
+fn pool_listen(host: &VirtualHost) -> Option<String> {
+    if let Some(id) = host.profile.as_deref() {
+        return projects::load(id).ok()?.fpm_listen;
+    }
+    host.fpm_pool.as_ref().map(|pool| pool.listen.clone())
+}
+
+fn php_handler(listen: Option<&str>, ports: &Ports) -> String {
+    Ports::handler_for(&listen.map_or_else(|| ports.fpm_address(), String::from))
+}
+
+fn fastcgi_pass(listen: Option<&str>, ports: &Ports) -> String {
+    Ports::fastcgi_pass_for(&listen.map_or_else(|| ports.fpm_address(), String::from))
+}
+
+/// Fill in a template for a host
+pub fn render(template: &str, host: &VirtualHost, ports: &Ports) -> String {
+    let listen = pool_listen(host);
+    // Hosts saved from an inspection carry the same rules their template has
+    let rewrites: &[String] = if PLAIN_TEMPLATES.contains(&template_for(host)) {
+        &host.rewrites
+    } else {
+        &[]
+    };
+    let values = [
+        ("{{domain}}", host.domain.clone()),
+        ("{{docroot}}", host.docroot.clone()),
+        ("{{php_handler}}", php_handler(listen.as_deref(), ports)),
+        ("{{fastcgi_pass}}", fastcgi_pass(listen.as_deref(), ports)),
+    ];
+
+    let mut out = Vec::new();
+    for line in template.lines() {
+        if line.trim() == "{{rewrites}}" {
+            let indent = &line[..line.len() - line.trim_start().len()];
+            out.extend(rewrites.iter().map(|r| format!("{}{}", indent, r.trim())));
+            continue;
+        }
+        let mut line = line.to_string();
+        for (placeholder, value) in &values {
+            line = line.replace(placeholder, value);
+        }
+        out.push(line);
+    }
+
+    out.join("\n") + "\n"
+}
+
+/// Render every active host's template for both servers into `conf/sites`,
+/// dropping files of hosts that are gone
+pub fn render_sites() -> Result<(), String> {
+    let layout = Layout::resolve();
+    let sites_dir = layout.conf_dir.join("sites");
+    std::fs::create_dir_all(&sites_dir)
+        .map_err(|e| format!("Failed to create {}: {}", sites_dir.display(), e))?;
+
+    let ports = Settings::load().unwrap_or_default().ports;
+    let mut written = HashSet::new();
+
+    for host in load_virtual_hosts()?.values().filter(|h| h.active) {
+        let name = template_for(host);
+        for server in SERVERS {
+            let template =
+                source(*server, name).ok_or_else(|| format!("Template '{}' not found", name))?;
+            let file = format!("{}.{}.conf", host.domain, server_name(*server));
+            std::fs::write(sites_dir.join(&file), render(&template, host, &ports))
+                .map_err(|e| format!("Failed to write {}: {}", file, e))?;
+            written.insert(file);
+        }
+    }
+
+    for entry in std::fs::read_dir(&sites_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+    {
+        let file = entry.file_name().to_string_lossy().to_string();
+        if file.ends_with(".conf") && !written.contains(&file) {
+            let _ = std::fs::remove_file(entry.path());
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VhostTemplate {
+    pub name: String,
+    pub server: WebServer,
+    pub builtin: String,
+    /// The user's version, when the built-in is overridden
+    pub custom: Option<String>,
+    /// Where an override lives (or would)
+    pub path: String,
+}
+
+/// Every template for both web servers, with the user's overrides
+#[tauri::command]
+pub async fn get_vhost_templates() -> Result<Vec<VhostTemplate>, String> {
+    Ok(SERVERS
+        .iter()
+        .flat_map(|server| {
+            TEMPLATES.iter().map(move |name| {
+                let path = override_path(*server, name);
+                VhostTemplate {
+                    name: name.to_string(),
+                    server: *server,
+                    builtin: builtin(*server, name).unwrap_or_default(),
+                    custom: std::fs::read_to_string(&path).ok(),
+                    path: path.to_string_lossy().to_string(),
+                }
+            })
+        })
+        .collect())
+}
+
+/// Override a template; takes effect the next time configs are generated
+#[tauri::command]
+pub async fn save_vhost_template(
+    server: WebServer,
+    name: String,
+    content: String,
+) -> Result<(), String> {
+    validate_name(&name)?;
+    let path = override_path(server, &name);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    std::fs::write(&path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Go back to the built-in template
+#[tauri::command]
+pub async fn reset_vhost_template(server: WebServer, name: String) -> Result<(), String> {
+    validate_name(&name)?;
+    match std::fs::remove_file(override_path(server, &name)) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(format!("Failed to reset template '{}': {}", name, e))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Preview what a host's vhost will contain for a server
+#[tauri::command]
+pub async fn preview_vhost_template(domain: String, server: WebServer) -> Result<String, String> {
+    let host = load_virtual_hosts()?
+        .remove(&domain)
+        .ok_or_else(|| format!("Host '{}' not found", domain))?;
+    let name = template_for(&host);
+    let template = source(server, name).ok_or_else(|| format!("Template '{}' not found", name))?;
+    Ok(render(
+        &template,
+        &host,
+        &Settings::load().unwrap_or_default().ports,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FpmPoolRef;
+
+    fn host(template: Option<&str>, rewrites: &[&str]) -> VirtualHost {
+        VirtualHost {
+            domain: "shop.test".to_string(),
+            docroot: "/srv/shop/public".to_string(),
+            aliases: Vec::new(),
+            group: String::new(),
+            active: true,
+            ssl: true,
+            host_type: "php".to_string(),
+            profile: None,
+            fpm_pool: None,
+            framework: None,
+            rewrites: rewrites.iter().map(|r| r.to_string()).collect(),
+            template: template.map(String::from),
+            wildcard: false,
+            subdomains: Vec::new(),
+        }
+    }
+
+    fn pool(listen: &str) -> Option<FpmPoolRef> {
+        Some(FpmPoolRef {
+            php_version: "8.3".to_string(),
+            name: "shop".to_string(),
+            listen: listen.to_string(),
+        })
+    }
+
+    #[test]
+    fn render_fills_placeholders_and_indents_rewrites() {
+        let template = "server_name {{domain}};\nroot {{docroot}};\n  {{rewrites}}\npass {{fastcgi_pass}};\nSetHandler \"{{php_handler}}\"";
+        let host = host(
+            None,
+            &["  RewriteEngine On", "RewriteRule ^old$ /new [R=301,L]"],
+        );
+
+        assert_eq!(
+            render(template, &host, &Ports::default()),
+            "server_name shop.test;\n\
+             root /srv/shop/public;\n  \
+             RewriteEngine On\n  \
+             RewriteRule ^old$ /new [R=301,L]\n\
+             pass 127.0.0.1:9000;\n\
+             SetHandler \"proxy:fcgi://127.0.0.1:9000\"\n"
+        );
+
+        let apache = render(&apache_builtin("php").unwrap(), &host, &Ports::default());
+        assert!(apache.contains("<Directory \"/srv/shop/public\">"));
+        assert!(apache
+            .contains("    RewriteEngine On\n    RewriteRule ^old$ /new [R=301,L]\n</Directory>"));
+        assert!(!apache.contains("{{"));
+    }
+
+    #[test]
+    fn framework_templates_drop_the_hosts_own_rewrites() {
+        let host = host(Some("laravel"), &["RewriteRule ^old$ /new [R=301,L]"]);
+
+        let apache = render(
+            &apache_builtin(template_for(&host)).unwrap(),
+            &host,
+            &Ports::default(),
+        );
+        assert!(apache.contains("RewriteRule ^ index.php [L]\n</Directory>"));
+        assert!(!apache.contains("^old$"));
+        assert!(!apache.contains("{{"));
+    }
+
+    #[test]
+    fn hosts_use_their_template_or_what_their_framework_needs() {
+        let mut laravel = host(None, &[]);
+        laravel.framework = Some("laravel".to_string());
+        assert_eq!(template_for(&laravel), "laravel");
+        laravel.template = Some("front-controller".to_string());
+        assert_eq!(template_for(&laravel), "front-controller");
+        // A template that no longer exists falls back to the framework's
+        laravel.template = Some("removed".to_string());
+        assert_eq!(template_for(&laravel), "laravel");
+
+        for (framework, host_type, expected) in [
+            (Some("symfony"), "php", "symfony"),
+            (Some("wordpress"), "php", "wordpress"),
+            (Some("drupal"), "php", "front-controller"),
+            (Some("codeigniter"), "php", "front-controller"),
+            (Some("vite"), "vue", "spa"),
+            (Some("nuxt"), "vue", "spa"),
+            (Some("next"), "react", "static"),
+            (Some("static"), "static", "static"),
+            (Some("php"), "php", "php"),
+            (None, "static", "static"),
+            (None, "vue", "spa"),
+            (None, "react", "spa"),
+            (None, "php", "php"),
+        ] {
+            assert_eq!(
+                default_template(framework, host_type),
+                expected,
+                "{:?} {}",
+                framework,
+                host_type
+            );
+        }
+        assert!(TEMPLATES
+            .iter()
+            .all(|name| apache_builtin(name).is_some() && nginx_builtin(name).is_some()));
+    }
+
+    #[test]
+    fn php_goes_to_the_hosts_pool_or_the_shared_one() {
+        let tcp = Ports::default();
+        let ipv6 = Ports {
+            php_fpm_host: "::1".to_string(),
+            php_fpm: 9074,
+            ..Ports::default()
+        };
+        let socket = Ports {
+            php_fpm_socket: Some("/run/php/fpm.sock".to_string()),
+            ..Ports::default()
+        };
+
+        for (listen, ports, handler, pass) in [
+            (None, &tcp, "proxy:fcgi://127.0.0.1:9000", "127.0.0.1:9000"),
+            (None, &ipv6, "proxy:fcgi://[::1]:9074", "[::1]:9074"),
+            (
+                None,
+                &socket,
+                "proxy:unix:/run/php/fpm.sock|fcgi://localhost",
+                "unix:/run/php/fpm.sock",
+            ),
+            // A pool of its own wins over the shared socket
+            (
+                Some("/run/php/shop.sock"),
+                &socket,
+                "proxy:unix:/run/php/shop.sock|fcgi://localhost",
+                "unix:/run/php/shop.sock",
+            ),
+            (
+                Some("9081"),
+                &socket,
+                "proxy:fcgi://127.0.0.1:9081",
+                "127.0.0.1:9081",
+            ),
+            (
+                Some("[::1]:9082"),
+                &tcp,
+                "proxy:fcgi://[::1]:9082",
+                "[::1]:9082",
+            ),
+        ] {
+            assert_eq!(php_handler(listen, ports), handler, "{:?}", listen);
+            assert_eq!(fastcgi_pass(listen, ports), pass, "{:?}", listen);
+        }
+
+        let mut host = host(None, &[]);
+        host.fpm_pool = pool("/run/php/shop.sock");
+        let nginx = render(&nginx_builtin("php").unwrap(), &host, &socket);
+        assert!(nginx.contains("    fastcgi_pass unix:/run/php/shop.sock;\n"));
+    }
+}
@@ -0,0 +1,769 @@
+// ============================================
+// Xdebug
+// ============================================
+// Turns Xdebug on and off for a PHP version or a single project profile and
+// manages its modes, client address and trigger. A version is configured
+// through the same ini files as every other setting (see `php_config_mode`).
+//
+// `xdebug.mode` is only read when FPM starts, so a pool can't turn a mode on.
+// Instead the version runs every mode its projects need, and when it isn't
+// debugging itself, only on a trigger value nobody else knows. A project's
+// pool then starts requests itself (`php_admin_value[xdebug.start_with_request]`)
+// or accepts any trigger. A profile keeps the modes it needs under
+// `xdebug.mode` in its ini settings; the pool writer leaves that key out.
+// Its status is read from the pool itself, over FastCGI.
+// FPM is reloaded after every change so the next request picks it up.
+
+use crate::fastcgi::{self, Address};
+use crate::hosts_manager::toggle_php_extension;
+use crate::paths;
+use crate::php_discovery;
+use crate::php_extensions;
+use crate::php_fpm;
+use crate::php_ini::{self, IniChange};
+use crate::php_overrides;
+use crate::projects;
+use crate::settings::PhpConfigMode;
+use crate::types::{PhpVersion, ProjectProfile};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Modes accepted by `xdebug.mode`
+pub const MODES: &[&str] = &[
+    "develop", "coverage", "debug", "gcstats", "profile", "trace",
+];
+
+/// Values accepted by `xdebug.start_with_request`
+const START_WITH_REQUEST: &[&str] = &["default", "yes", "no", "trigger"];
+
+const KEYS: &[&str] = &[
+    "xdebug.mode",
+    "xdebug.client_host",
+    "xdebug.client_port",
+    "xdebug.start_with_request",
+    "xdebug.trigger_value",
+];
+
+/// Modes that don't wait for a request to start; a project needing one
+/// turns it on for every site of its version
+const ALWAYS_ON_MODES: &[&str] = &["develop", "coverage", "gcstats"];
+
+/// Prefix of the private trigger value a version gets when only its projects debug
+const PRIVATE_TRIGGER: &str = "localhost-manager-";
+
+const POOL_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Reports what Xdebug runs with inside the pool executing it
+const POOL_PROBE: &str = r#"<?php
+header('Content-Type: application/json');
+echo json_encode([
+    'loaded' => extension_loaded('xdebug'),
+    'modes' => function_exists('xdebug_info')
+        ? xdebug_info('mode')
+        : array_filter(array_map('trim', explode(',', (string) ini_get('xdebug.mode')))),
+    'client_host' => ini_get('xdebug.client_host'),
+    'client_port' => ini_get('xdebug.client_port'),
+    'start_with_request' => ini_get('xdebug.start_with_request'),
+    'trigger_value' => ini_get('xdebug.trigger_value'),
+]);
+"#;
+
+/// Name of the configurations written to `.vscode/launch.json`
+const LAUNCH_NAME: &str = "Listen for Xdebug (Localhost Manager)";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct XdebugSettings {
+    pub modes: Vec<String>,
+    pub client_host: String,
+    pub client_port: u16,
+    /// `trigger` (XDEBUG_TRIGGER cookie/GET/POST), `yes`, `no` or `default`
+    pub start_with_request: String,
+}
+
+impl Default for XdebugSettings {
+    fn default() -> Self {
+        Self {
+            modes: vec!["debug".to_string()],
+            client_host: "127.0.0.1".to_string(),
+            client_port: 9003,
+            start_with_request: "trigger".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct XdebugStatus {
+    pub php_version: String,
+    /// Xdebug is available for the version
+    pub installed: bool,
+    /// The extension is loaded (possibly with `xdebug.mode=off`)
+    pub loaded: bool,
+    /// Loaded with at least one mode active for this scope
+    pub enabled: bool,
+    pub version: Option<String>,
+    pub settings: XdebugSettings,
+    /// Profile the status is scoped to
+    pub profile: Option<String>,
+    pub warnings: Vec<String>,
+}
+
+/// A version, or a profile on one of its versions
+enum Scope {
+    Version(PhpVersion),
+    Profile(ProjectProfile, PhpVersion),
+}
+
+impl Scope {
+    fn resolve(version: Option<String>, profile_id: Option<String>) -> Result<Self, String> {
+        let find = |version: &str| {
+            php_discovery::find_installed(version)
+                .ok_or_else(|| format!("PHP {} not found", version))
+        };
+
+        match (version, profile_id) {
+            (_, Some(id)) => {
+                let profile = projects::load(&id)?;
+                let php = find(&profile.php_version)?;
+                Ok(Scope::Profile(profile, php))
+            }
+            (Some(version), None) => Ok(Scope::Version(find(&version)?)),
+            (None, None) => Err("Choose a PHP version or a project".to_string()),
+        }
+    }
+
+    fn php(&self) -> &PhpVersion {
+        match self {
+            Scope::Version(php) | Scope::Profile(_, php) => php,
+        }
+    }
+}
+
+fn validate(settings: &XdebugSettings) -> Result<(), String> {
+    if let Some(mode) = settings.modes.iter().find(|m| !MODES.contains(&m.as_str())) {
+        return Err(format!(
+            "Unknown Xdebug mode '{}'; use {}",
+            mode,
+            MODES.join(", ")
+        ));
+    }
+    if !START_WITH_REQUEST.contains(&settings.start_with_request.as_str()) {
+        return Err(format!(
+            "Unknown start_with_request value '{}'",
+            settings.start_with_request
+        ));
+    }
+    if settings.client_host.trim().is_empty() || settings.client_host.contains(char::is_whitespace)
+    {
+        return Err(format!("Invalid client host '{}'", settings.client_host));
+    }
+    if settings.client_port == 0 {
+        return Err("Client port must be between 1 and 65535".to_string());
+    }
+    Ok(())
+}
+
+/// Directive values for `settings`; no modes (or disabled) is `xdebug.mode=off`
+fn directives(settings: &XdebugSettings, enabled: bool) -> Vec<(&'static str, String)> {
+    let mode = if enabled && !settings.modes.is_empty() {
+        settings.modes.join(",")
+    } else {
+        "off".to_string()
+    };
+
+    vec![
+        ("xdebug.mode", mode),
+        ("xdebug.client_host", settings.client_host.clone()),
+        ("xdebug.client_port", settings.client_port.to_string()),
+        (
+            "xdebug.start_with_request",
+            settings.start_with_request.clone(),
+        ),
+    ]
+}
+
+fn is_active(mode: Option<&str>) -> bool {
+    mode.is_some_and(|m| !m.trim().is_empty() && m.trim() != "off")
+}
+
+/// Effective xdebug.* values of the SAPI serving web requests (FPM, else CLI)
+fn probe(php: &PhpVersion) -> Result<php_ini::ConfigProbe, String> {
+    let sapi = if php.ini_paths.contains_key("fpm") {
+        "fpm"
+    } else {
+        "cli"
+    };
+    let ini = php
+        .ini_paths
+        .get(sapi)
+        .ok_or_else(|| format!("PHP {} has no php.ini", php.version))?;
+    let scan_dir = php_overrides::scan_dirs(php).remove(sapi);
+
+    php_ini::probe_config(php, Path::new(ini), scan_dir.as_deref(), KEYS)
+}
+
+fn settings_from(values: &dyn Fn(&str) -> Option<String>) -> XdebugSettings {
+    let defaults = XdebugSettings::default();
+    let modes: Vec<String> = values("xdebug.mode")
+        .unwrap_or_default()
+        .split(',')
+        .map(|m| m.trim().to_string())
+        .filter(|m| !m.is_empty() && m != "off")
+        .collect();
+
+    XdebugSettings {
+        modes: if modes.is_empty() {
+            defaults.modes
+        } else {
+            modes
+        },
+        client_host: values("xdebug.client_host")
+            .filter(|v| !v.is_empty())
+            .unwrap_or(defaults.client_host),
+        client_port: values("xdebug.client_port")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(defaults.client_port),
+        start_with_request: values("xdebug.start_with_request")
+            .filter(|v| !v.is_empty())
+            .unwrap_or(defaults.start_with_request),
+    }
+}
+
+/// Settings of a version debugging its own sites; None when it's off or only
+/// runs modes for its projects
+fn own_settings(values: &dyn Fn(&str) -> Option<String>) -> Option<XdebugSettings> {
+    let private =
+        values("xdebug.trigger_value").is_some_and(|trigger| trigger.starts_with(PRIVATE_TRIGGER));
+    (is_active(values("xdebug.mode").as_deref()) && !private).then(|| settings_from(values))
+}
+
+/// What `POOL_PROBE` answers
+#[derive(Debug, Deserialize)]
+struct PoolXdebug {
+    loaded: bool,
+    #[serde(default)]
+    modes: Vec<String>,
+    client_host: Option<String>,
+    client_port: Option<String>,
+    start_with_request: Option<String>,
+    trigger_value: Option<String>,
+}
+
+impl PoolXdebug {
+    fn value(&self, key: &str) -> Option<String> {
+        let value = match key {
+            "xdebug.mode" => Some(self.modes.join(",")),
+            "xdebug.client_host" => self.client_host.clone(),
+            "xdebug.client_port" => self.client_port.clone(),
+            "xdebug.start_with_request" => self.start_with_request.clone(),
+            "xdebug.trigger_value" => self.trigger_value.clone(),
+            _ => None,
+        };
+        value.filter(|v| !v.is_empty())
+    }
+
+    /// Requests of the pool start a session, on their own or on a trigger
+    fn debugging(&self) -> bool {
+        self.loaded
+            && !self.modes.is_empty()
+            && self.start_with_request.as_deref() != Some("no")
+            && !self
+                .trigger_value
+                .as_deref()
+                .is_some_and(|t| t.starts_with(PRIVATE_TRIGGER))
+    }
+}
+
+/// Run `POOL_PROBE` in a profile's pool
+async fn probe_pool(profile: &ProjectProfile) -> Result<PoolXdebug, String> {
+    let listen = profile
+        .fpm_listen
+        .as_deref()
+        .ok_or_else(|| format!("Project '{}' has no pool yet", profile.name))?;
+
+    let script = paths::config_dir().join("probes").join("xdebug.php");
+    if std::fs::read_to_string(&script).ok().as_deref() != Some(POOL_PROBE) {
+        if let Some(dir) = script.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        std::fs::write(&script, POOL_PROBE)
+            .map_err(|e| format!("Failed to write {}: {}", script.display(), e))?;
+    }
+
+    let filename = script.to_string_lossy().to_string();
+    let params = fastcgi::cgi_params(&filename, "/xdebug.php", "");
+    let response = fastcgi::request(&Address::parse(listen), &params, &[], POOL_TIMEOUT).await?;
+    serde_json::from_slice(&response.body).map_err(|_| {
+        format!(
+            "The pool answered {} instead of Xdebug's state: {}",
+            response.status,
+            if response.stderr.is_empty() {
+                String::from_utf8_lossy(&response.body).trim().to_string()
+            } else {
+                response.stderr
+            }
+        )
+    })
+}
+
+async fn status(scope: &Scope) -> Result<XdebugStatus, String> {
+    let php = scope.php().clone();
+    let (extension, probe) = tauri::async_runtime::spawn_blocking(move || {
+        let extension = php_extensions::inventory(&php)?
+            .into_iter()
+            .find(|e| e.name == "xdebug");
+        Ok::<_, String>((extension, probe(&php)?))
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    let global = |key: &str| probe.values.get(key).cloned().flatten();
+    let loaded = extension.as_ref().is_some_and(|e| e.enabled);
+    let mut warnings = probe.warnings.clone();
+
+    let (settings, enabled, profile) = match scope {
+        Scope::Version(_) => match own_settings(&global) {
+            Some(settings) => (settings, loaded, None),
+            None => (settings_from(&global), false, None),
+        },
+        Scope::Profile(profile, _) => {
+            // The modes the project asks for, with the pool's effective values
+            let wanted = |key: &str| profile.custom_ini_settings.get(key).cloned();
+            match probe_pool(profile).await {
+                Ok(pool) => {
+                    let value = |key: &str| match key {
+                        "xdebug.mode" => wanted(key),
+                        _ => pool.value(key),
+                    };
+                    (
+                        settings_from(&value),
+                        pool.debugging(),
+                        Some(profile.id.clone()),
+                    )
+                }
+                Err(e) => {
+                    warnings.push(format!("Couldn't ask the project's pool: {}", e));
+                    let value = |key: &str| wanted(key).or_else(|| global(key));
+                    (settings_from(&value), false, Some(profile.id.clone()))
+                }
+            }
+        }
+    };
+
+    Ok(XdebugStatus {
+        php_version: scope.php().version.clone(),
+        installed: extension.is_some(),
+        loaded,
+        enabled: loaded && enabled,
+        version: extension.and_then(|e| e.version),
+        settings,
+        profile,
+        warnings,
+    })
+}
+
+/// Write xdebug.* for a whole version, through conf.d or php.ini per the
+/// settings; None removes a directive
+async fn write_version(
+    php: &PhpVersion,
+    values: Vec<(&str, Option<String>)>,
+) -> Result<(), String> {
+    let changes: Vec<IniChange> = values
+        .into_iter()
+        .map(|(key, value)| IniChange {
+            key: key.to_string(),
+            value,
+            section: None,
+        })
+        .collect();
+
+    match php_overrides::config_mode(php) {
+        PhpConfigMode::ConfD => php_overrides::update_overrides(php, &changes)
+            .await
+            .map(|_| ()),
+        PhpConfigMode::PhpIni => {
+            let mut paths: Vec<&String> = ["cli", "fpm"]
+                .iter()
+                .filter_map(|sapi| php.ini_paths.get(*sapi))
+                .collect();
+            paths.dedup();
+            for path in paths {
+                php_ini::update(php, Path::new(path), &changes).await?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Profiles on `php` that turn Xdebug on in their own pool
+fn profiles_using(php: &PhpVersion) -> Vec<ProjectProfile> {
+    projects::load_all()
+        .into_iter()
+        .filter(|p| {
+            php_discovery::find_installed(&p.php_version).is_some_and(|v| v.version == php.version)
+        })
+        .filter(|p| is_active(p.custom_ini_settings.get("xdebug.mode").map(String::as_str)))
+        .collect()
+}
+
+/// Load the extension for a version if it isn't yet, with every mode off
+async fn ensure_loaded(php: &PhpVersion, current: &XdebugStatus) -> Result<(), String> {
+    if !current.installed {
+        return Err(format!(
+            "Xdebug isn't installed for PHP {}; install it from the extensions list first",
+            php.version
+        ));
+    }
+    if !current.loaded {
+        // Off first, so loading it doesn't slow down every other site
+        write_version(php, vec![("xdebug.mode", Some("off".to_string()))]).await?;
+        toggle_php_extension(php.version.clone(), "xdebug".to_string(), true).await?;
+    }
+    Ok(())
+}
+
+/// Version-wide values: its own settings when it debugs itself, plus every
+/// mode its projects need. When only projects debug, sessions start on a
+/// private trigger, which their pools replace.
+fn baseline(
+    own: Option<&XdebugSettings>,
+    fallback: &XdebugSettings,
+    project_modes: &[String],
+) -> Vec<(&'static str, Option<String>)> {
+    let mut settings = own.unwrap_or(fallback).clone();
+    if own.is_none() {
+        settings.modes.clear();
+        settings.start_with_request = "trigger".to_string();
+    }
+    for mode in project_modes {
+        if !settings.modes.contains(mode) {
+            settings.modes.push(mode.clone());
+        }
+    }
+
+    let mut values: Vec<(&str, Option<String>)> = directives(&settings, true)
+        .into_iter()
+        .map(|(key, value)| (key, Some(value)))
+        .collect();
+    let trigger = match own {
+        Some(_) => None,
+        None if project_modes.is_empty() => None,
+        None => Some(format!(
+            "{}{}",
+            PRIVATE_TRIGGER,
+            uuid::Uuid::new_v4().simple()
+        )),
+    };
+    values.push(("xdebug.trigger_value", trigger));
+    values
+}
+
+/// Modes the version's projects need
+fn project_modes(php: &PhpVersion) -> Vec<String> {
+    let mut modes: Vec<String> = Vec::new();
+    for profile in profiles_using(php) {
+        let wanted = profile
+            .custom_ini_settings
+            .get("xdebug.mode")
+            .cloned()
+            .unwrap_or_default();
+        for mode in wanted.split(',').map(str::trim) {
+            if !mode.is_empty() && mode != "off" && !modes.iter().any(|m| m == mode) {
+                modes.push(mode.to_string());
+            }
+        }
+    }
+    modes
+}
+
+/// Pool lines for a project debugging in its own pool
+fn pool_directives(settings: &XdebugSettings) -> Vec<(&'static str, String)> {
+    vec![
+        // What the version should run for this project; not written to the pool
+        ("xdebug.mode", settings.modes.join(",")),
+        ("xdebug.client_host", settings.client_host.clone()),
+        ("xdebug.client_port", settings.client_port.to_string()),
+        (
+            "xdebug.start_with_request",
+            settings.start_with_request.clone(),
+        ),
+        // Any trigger, instead of the version's private one
+        ("xdebug.trigger_value", String::new()),
+    ]
+}
+
+async fn restart_fpm(php: &PhpVersion, warnings: &mut Vec<String>) {
+    if php.sapis.iter().any(|s| s == "fpm") {
+        if let Err(e) = php_fpm::reload(php).await {
+            warnings.push(format!("PHP-FPM could not be restarted: {}", e));
+        }
+    }
+}
+
+/// Xdebug state for a PHP version, or for a project profile when `profile_id` is set
+#[tauri::command]
+pub async fn get_xdebug_status(
+    version: Option<String>,
+    profile_id: Option<String>,
+) -> Result<XdebugStatus, String> {
+    let scope = tauri::async_runtime::spawn_blocking(move || Scope::resolve(version, profile_id))
+        .await
+        .map_err(|e| e.to_string())??;
+    status(&scope).await
+}
+
+/// Turn Xdebug on or off for a PHP version or a project profile, optionally
+/// changing its settings, and restart FPM
+#[tauri::command]
+pub async fn set_xdebug(
+    version: Option<String>,
+    profile_id: Option<String>,
+    enabled: bool,
+    settings: Option<XdebugSettings>,
+) -> Result<XdebugStatus, String> {
+    let scope = tauri::async_runtime::spawn_blocking(move || Scope::resolve(version, profile_id))
+        .await
+        .map_err(|e| e.to_string())??;
+    let current = status(&scope).await?;
+    let settings = settings.unwrap_or_else(|| current.settings.clone());
+    validate(&settings)?;
+
+    let mut warnings = Vec::new();
+
+    match &scope {
+        Scope::Version(php) => {
+            let modes = project_modes(php);
+            if enabled {
+                ensure_loaded(php, &current).await?;
+                write_version(php, baseline(Some(&settings), &settings, &modes)).await?;
+            } else if current.loaded {
+                write_version(php, baseline(None, &settings, &modes)).await?;
+                // Keep the extension for projects that still debug; otherwise unload it
+                if modes.is_empty() {
+                    if let Err(e) =
+                        toggle_php_extension(php.version.clone(), "xdebug".to_string(), false).await
+                    {
+                        warnings.push(format!("Xdebug stays loaded with mode=off: {}", e));
+                    }
+                }
+            }
+            restart_fpm(php, &mut warnings).await;
+        }
+        Scope::Profile(profile, php) => {
+            // What the version runs for itself, which a project change keeps
+            let own = {
+                let php = php.clone();
+                tauri::async_runtime::spawn_blocking(move || {
+                    probe(&php)
+                        .map(|probe| own_settings(&|key| probe.values.get(key).cloned().flatten()))
+                })
+                .await
+                .map_err(|e| e.to_string())??
+            };
+            let enabled = enabled && !settings.modes.is_empty();
+            if enabled {
+                ensure_loaded(php, &current).await?;
+                if let Some(mode) = settings
+                    .modes
+                    .iter()
+                    .find(|m| ALWAYS_ON_MODES.contains(&m.as_str()))
+                {
+                    warnings.push(format!(
+                        "Xdebug's {} mode doesn't wait for a request; it runs for every site on PHP {}",
+                        mode, php.version
+                    ));
+                }
+            }
+
+            let mut profile = profile.clone();
+            for (key, value) in pool_directives(&settings) {
+                if enabled {
+                    profile.custom_ini_settings.insert(key.to_string(), value);
+                } else {
+                    profile.custom_ini_settings.remove(key);
+                }
+            }
+            // Rewrites the pool and reloads FPM
+            projects::save_project_profile(profile).await?;
+
+            let modes = project_modes(php);
+            if current.loaded || enabled {
+                write_version(php, baseline(own.as_ref(), &settings, &modes)).await?;
+                restart_fpm(php, &mut warnings).await;
+            }
+        }
+    }
+
+    let mut status = status(&scope).await?;
+    status.warnings.extend(warnings);
+    Ok(status)
+}
+
+/// VS Code debug configurations matching a scope's Xdebug settings: one that
+/// listens for web requests and one that runs the open script with the same PHP
+fn launch_configurations(php: &PhpVersion, settings: &XdebugSettings) -> Vec<serde_json::Value> {
+    let mut listen = serde_json::json!({
+        "name": LAUNCH_NAME,
+        "type": "php",
+        "request": "launch",
+        "port": settings.client_port,
+    });
+    if !matches!(settings.client_host.as_str(), "127.0.0.1" | "localhost") {
+        listen["hostname"] = "0.0.0.0".into();
+    }
+
+    let mut configurations = vec![listen];
+    if let Some(binary) = php.binary.as_deref() {
+        configurations.push(serde_json::json!({
+            "name": format!("Launch current script with PHP {} (Localhost Manager)", php.version),
+            "type": "php",
+            "request": "launch",
+            "program": "${file}",
+            "cwd": "${fileDirname}",
+            "port": 0,
+            "runtimeExecutable": binary,
+            "runtimeArgs": ["-dxdebug.mode=debug", "-dxdebug.start_with_request=yes"],
+        }));
+    }
+    configurations
+}
+
+/// Write (or update) `.vscode/launch.json` in a project for its Xdebug
+/// settings. Configurations the app didn't create are kept.
+#[tauri::command]
+pub async fn write_xdebug_launch_config(
+    version: Option<String>,
+    profile_id: Option<String>,
+    directory: Option<String>,
+) -> Result<String, String> {
+    let scope = Scope::resolve(version, profile_id)?;
+    let directory = match (&scope, directory) {
+        (_, Some(directory)) => PathBuf::from(directory),
+        (Scope::Profile(profile, _), None) => PathBuf::from(&profile.path),
+        (Scope::Version(_), None) => return Err("Choose the project directory".to_string()),
+    };
+    if !directory.is_dir() {
+        return Err(format!("Directory not found: {}", directory.display()));
+    }
+
+    let settings = status(&scope).await?.settings;
+    let path = directory.join(".vscode").join("launch.json");
+
+    let mut launch = match std::fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str::<serde_json::Value>(&content).map_err(|_| {
+            format!(
+                "{} isn't plain JSON (comments?); add the configuration by hand",
+                path.display()
+            )
+        })?,
+        Err(_) => serde_json::json!({ "version": "0.2.0", "configurations": [] }),
+    };
+
+    let generated = launch_configurations(scope.php(), &settings);
+    let configurations = launch
+        .get_mut("configurations")
+        .and_then(|c| c.as_array_mut())
+        .ok_or_else(|| format!("{} has no configurations list", path.display()))?;
+    configurations.retain(|c| {
+        !c.get("name")
+            .and_then(|n| n.as_str())
+            .is_some_and(|n| n.ends_with("(Localhost Manager)"))
+    });
+    configurations.splice(0..0, generated);
+
+    let content = serde_json::to_string_pretty(&launch)
+        .map_err(|e| format!("Failed to serialize launch.json: {}", e))?;
+    std::fs::create_dir_all(path.parent().unwrap_or(&directory))
+        .map_err(|e| format!("Failed to create .vscode: {}", e))?;
+    std::fs::write(&path, content + "\n")
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    Ok(path.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn value<'a>(values: &'a [(&str, Option<String>)], key: &str) -> Option<&'a str> {
+        values
+            .iter()
+            .find(|(k, _)| *k == key)
+            .and_then(|(_, v)| v.as_deref())
+    }
+
+    #[test]
+    fn projects_get_their_modes_behind_a_private_trigger() {
+        let defaults = XdebugSettings::default();
+        let values = baseline(None, &defaults, &["debug".to_string()]);
+        assert_eq!(value(&values, "xdebug.mode"), Some("debug"));
+        assert_eq!(value(&values, "xdebug.start_with_request"), Some("trigger"));
+        assert!(
+            value(&values, "xdebug.trigger_value").is_some_and(|t| t.starts_with(PRIVATE_TRIGGER))
+        );
+
+        // Nobody debugging: off, no trigger left behind
+        let values = baseline(None, &defaults, &[]);
+        assert_eq!(value(&values, "xdebug.mode"), Some("off"));
+        assert_eq!(value(&values, "xdebug.trigger_value"), None);
+    }
+
+    #[test]
+    fn a_debugging_version_keeps_its_settings_and_adds_project_modes() {
+        let own = XdebugSettings {
+            start_with_request: "yes".to_string(),
+            ..XdebugSettings::default()
+        };
+        let values = baseline(
+            Some(&own),
+            &own,
+            &["profile".to_string(), "debug".to_string()],
+        );
+        assert_eq!(value(&values, "xdebug.mode"), Some("debug,profile"));
+        assert_eq!(value(&values, "xdebug.start_with_request"), Some("yes"));
+        assert_eq!(value(&values, "xdebug.trigger_value"), None);
+    }
+
+    #[test]
+    fn own_settings_ignore_a_baseline_kept_for_projects() {
+        let values: HashMap<&str, String> = [
+            ("xdebug.mode", "debug".to_string()),
+            ("xdebug.trigger_value", format!("{}abc", PRIVATE_TRIGGER)),
+        ]
+        .into();
+        assert!(own_settings(&|key| values.get(key).cloned()).is_none());
+
+        let values: HashMap<&str, String> = [("xdebug.mode", "debug".to_string())].into();
+        assert!(own_settings(&|key| values.get(key).cloned()).is_some());
+        assert!(own_settings(&|_| Some("off".to_string())).is_none());
+    }
+
+    #[test]
+    fn pools_debug_unless_held_by_the_private_trigger() {
+        let pool = |modes: &[&str], start: &str, trigger: &str| PoolXdebug {
+            loaded: true,
+            modes: modes.iter().map(|m| m.to_string()).collect(),
+            client_host: None,
+            client_port: None,
+            start_with_request: Some(start.to_string()),
+            trigger_value: Some(trigger.to_string()),
+        };
+        assert!(pool(&["debug"], "yes", "").debugging());
+        assert!(pool(&["debug"], "trigger", "").debugging());
+        assert!(!pool(&["debug"], "trigger", &format!("{}abc", PRIVATE_TRIGGER)).debugging());
+        assert!(!pool(&[], "yes", "").debugging());
+        assert!(!pool(&["debug"], "no", "").debugging());
+    }
+
+    #[test]
+    fn the_profile_mode_stays_out_of_the_pool() {
+        let lines = pool_directives(&XdebugSettings::default());
+        assert!(lines
+            .iter()
+            .any(|(k, v)| *k == "xdebug.mode" && v == "debug"));
+        assert!(lines
+            .iter()
+            .any(|(k, v)| *k == "xdebug.trigger_value" && v.is_empty()));
+    }
+}
@@ -3,16 +3,55 @@
 // ============================================
 
 import { invoke } from "@tauri-apps/api/core";
-import type { PhpVersion, PhpConfig, VirtualHost, ServicesStatus } from './types';
+import { listen } from "@tauri-apps/api/event";
+import type { PhpVersion, PhpConfig, PoolHealth, ProjectProfile, VirtualHost, ServicesStatus } from './types';
 
 export async function getAvailablePhpVersions(): Promise<PhpVersion[]> {
   return await invoke<PhpVersion[]>('get_available_php_versions');
 }
 
+export async function refreshPhpCatalog(): Promise<PhpVersion[]> {
+  return await invoke<PhpVersion[]>('refresh_php_catalog');
+}
+
+export interface DownloadProgress {
+  version: string;
+  stage: 'downloading' | 'verifying' | 'extracting' | 'done' | 'error';
+  downloaded: number;
+  total: number;
+  percentage: number;
+  speed: string;
+}
+
+export const PHP_DOWNLOAD_EVENT = 'php-download-progress';
+
+export async function installStandalonePhp(version: string): Promise<string> {
+  return await invoke<string>('install_standalone_php', { version });
+}
+
+export async function removeStandalonePhp(version: string): Promise<string> {
+  return await invoke<string>('remove_standalone_php', { version });
+}
+
+export interface ActivePhp {
+  version: string;
+  scope: 'global' | 'local';
+  version_file: string | null;
+  binary: string;
+  shims_dir: string;
+  shims_on_path: boolean;
+}
+
+/** Select the CLI PHP version globally, or for a directory via .php-version */
+export async function usePhpVersion(version: string, directory?: string): Promise<ActivePhp> {
+  return await invoke<ActivePhp>('use_php_version', { version, directory: directory ?? null });
+}
+
 export async function getInstalledPhpVersions(): Promise<PhpVersion[]> {
   return await invoke<PhpVersion[]>('get_installed_php_versions');
 }
 
+/** Starts the install as a background job and returns its ID; see `runJob` */
 export async function installPhpVersion(version: string): Promise<string> {
   return await invoke<string>('install_php_version', { version });
 }
@@ -25,12 +64,384 @@ export async function getPhpConfig(version: string): Promise<PhpConfig> {
   return await invoke<PhpConfig>('get_php_config', { version });
 }
 
+export interface IniEntry {
+  key: string;
+  value: string;
+  section: string | null;
+  line: number;
+  commented: boolean;
+}
+
+export interface PhpIniFile {
+  path: string;
+  sections: string[];
+  entries: IniEntry[];
+}
+
+/** `value: null` comments the directive out; `section` targets a [PATH=]/[HOST=] section */
+export interface IniChange {
+  key: string;
+  value: string | null;
+  section?: string | null;
+}
+
+export interface IniValidation {
+  path: string;
+  effective: Record<string, string | null>;
+  warnings: string[];
+}
+
 export async function updatePhpIniSetting(
   version: string,
   key: string,
   value: string
-): Promise<void> {
-  await invoke('update_php_ini_setting', { version, key, value });
+): Promise<IniValidation> {
+  return await invoke<IniValidation>('update_php_ini_setting', { version, key, value });
+}
+
+export async function getPhpIni(version: string, sapi?: string): Promise<PhpIniFile> {
+  return await invoke<PhpIniFile>('get_php_ini', { version, sapi: sapi ?? null });
+}
+
+export async function updatePhpIniSettings(
+  version: string,
+  changes: IniChange[],
+  sapi?: string
+): Promise<IniValidation> {
+  return await invoke<IniValidation>('update_php_ini_settings', { version, sapi: sapi ?? null, changes });
+}
+
+export interface PhpOverrides {
+  mode: PhpConfigMode;
+  files: Record<string, string>;
+  settings: Record<string, string>;
+  extensions: string[];
+  effective: Record<string, Record<string, string | null>>;
+}
+
+/** Settings and extensions in 99-localhost-manager.ini, with each SAPI's effective values */
+export async function getPhpOverrides(version: string): Promise<PhpOverrides> {
+  return await invoke<PhpOverrides>('get_php_overrides', { version });
+}
+
+export async function updatePhpOverrides(version: string, changes: IniChange[]): Promise<IniValidation> {
+  return await invoke<IniValidation>('update_php_overrides', { version, changes });
+}
+
+export type XdebugMode = 'develop' | 'coverage' | 'debug' | 'gcstats' | 'profile' | 'trace';
+
+export interface XdebugSettings {
+  modes: XdebugMode[];
+  client_host: string;
+  client_port: number;
+  start_with_request: 'default' | 'yes' | 'no' | 'trigger';
+}
+
+export interface XdebugStatus {
+  php_version: string;
+  installed: boolean;
+  loaded: boolean;
+  enabled: boolean;
+  version: string | null;
+  settings: XdebugSettings;
+  profile: string | null;
+  warnings: string[];
+}
+
+/** Xdebug scope: a PHP version, or a project profile (which wins when both are set) */
+export interface XdebugScope {
+  version?: string;
+  profileId?: string;
+}
+
+export async function getXdebugStatus(scope: XdebugScope): Promise<XdebugStatus> {
+  return await invoke<XdebugStatus>('get_xdebug_status', {
+    version: scope.version ?? null,
+    profileId: scope.profileId ?? null,
+  });
+}
+
+/** Turn Xdebug on or off for a scope, optionally with new settings; restarts PHP-FPM */
+export async function setXdebug(
+  scope: XdebugScope,
+  enabled: boolean,
+  settings?: XdebugSettings
+): Promise<XdebugStatus> {
+  return await invoke<XdebugStatus>('set_xdebug', {
+    version: scope.version ?? null,
+    profileId: scope.profileId ?? null,
+    enabled,
+    settings: settings ?? null,
+  });
+}
+
+/** Write .vscode/launch.json for a scope's Xdebug settings; returns its path */
+export async function writeXdebugLaunchConfig(scope: XdebugScope, directory?: string): Promise<string> {
+  return await invoke<string>('write_xdebug_launch_config', {
+    version: scope.version ?? null,
+    profileId: scope.profileId ?? null,
+    directory: directory ?? null,
+  });
+}
+
+export interface OpcacheStats {
+  enabled: boolean;
+  memory_used: number;
+  memory_free: number;
+  memory_wasted: number;
+  hits: number;
+  misses: number;
+  /** Percentage, 0-100 */
+  hit_rate: number;
+  cached_scripts: number;
+  max_cached_keys: number;
+  oom_restarts: number;
+  manual_restarts: number;
+  jit: boolean;
+  scripts: { path: string; hits: number; memory: number }[];
+}
+
+export interface PoolCacheStatus {
+  pool: string;
+  listen: string;
+  php_version: string | null;
+  pid: number | null;
+  opcache_loaded: boolean;
+  opcache: OpcacheStats | null;
+  realpath: { used: number; limit: string; ttl: number; entries: number } | null;
+  reset: boolean | null;
+  error: string | null;
+}
+
+/** OPcache and realpath cache stats from inside each PHP-FPM pool of a version */
+export async function getPhpCacheStatus(version: string): Promise<PoolCacheStatus[]> {
+  return await invoke<PoolCacheStatus[]>('get_php_cache_status', { version });
+}
+
+/** Clear OPcache through a pool; pools of one version share the cache */
+export async function resetOpcache(version: string, pool: string): Promise<PoolCacheStatus> {
+  return await invoke<PoolCacheStatus>('reset_opcache', { version, pool });
+}
+
+export type PmMode = 'static' | 'dynamic' | 'ondemand';
+
+/** Editable part of a PHP-FPM pool */
+export interface FpmPoolSettings {
+  name: string;
+  /** `127.0.0.1:9001`, a bare port, or a unix socket path */
+  listen: string;
+  pm: PmMode;
+  max_children: number;
+  /** Left unchanged when omitted */
+  user?: string | null;
+}
+
+export interface FpmPool {
+  name: string;
+  php_version: string;
+  listen: string;
+  file: string;
+  ping_path: string | null;
+  ping_response: string | null;
+  status_path: string | null;
+  pm: PmMode | null;
+  max_children: number | null;
+  user: string | null;
+}
+
+export interface FpmPoolOverview extends FpmPool {
+  /** Generated for a project profile; edit the profile instead */
+  profile_pool: boolean;
+  /** Virtual hosts mapped to the pool */
+  hosts: string[];
+  health: PoolHealth;
+}
+
+/** Pools of a PHP version with their health, status page numbers and hosts */
+export async function getFpmPools(version: string): Promise<FpmPoolOverview[]> {
+  return await invoke<FpmPoolOverview[]>('get_fpm_pools', { version });
+}
+
+/** Create a pool or update the one with the same name; reloads that version's PHP-FPM */
+export async function saveFpmPool(version: string, settings: FpmPoolSettings): Promise<FpmPool> {
+  return await invoke<FpmPool>('save_fpm_pool', { version, settings });
+}
+
+export async function deleteFpmPool(version: string, name: string): Promise<void> {
+  return await invoke('delete_fpm_pool', { version, name });
+}
+
+/** Map a host to a pool, or back to the shared pool with nulls */
+export async function assignFpmPool(domain: string, version: string | null, pool: string | null): Promise<void> {
+  return await invoke('assign_fpm_pool', { domain, version, pool });
+}
+
+/** Reload the PHP-FPM master of one version, or of every version when omitted */
+export async function restartPhpFpm(version?: string): Promise<void> {
+  return await invoke('restart_php_fpm', { version: version ?? null });
+}
+
+/** A `php` or `ext-*` requirement and the packages asking for it */
+export interface ComposerRequirement {
+  name: string;
+  constraint: string;
+  /** `root` for composer.json itself, otherwise package names */
+  required_by: string[];
+  /** null when the constraint couldn't be checked */
+  satisfied: boolean | null;
+}
+
+export interface ComposerProject {
+  domain: string;
+  /** Directory holding composer.json */
+  directory: string;
+  has_lock: boolean;
+  vendor_installed: boolean;
+  /** PHP serving the host, and whether it comes from a profile, a pool or the shared pool */
+  php_version: string | null;
+  php_source: 'profile' | 'pool' | 'shared' | null;
+  requirements: ComposerRequirement[];
+  warnings: string[];
+  composer: string | null;
+}
+
+/** Composer project behind a host (composer.json in its docroot or above); null when there is none */
+export async function getComposerProject(domain: string): Promise<ComposerProject | null> {
+  return await invoke<ComposerProject | null>('get_composer_project', { domain });
+}
+
+/** Start `composer install` or `composer update` with the host's PHP; returns the job ID */
+export async function runComposer(domain: string, action: 'install' | 'update'): Promise<string> {
+  return await invoke<string>('run_composer', { domain, action });
+}
+
+/** How to serve a project folder, as proposed by the inspector */
+export interface ProjectInspection {
+  /** Project root (the parent when a public/ folder was picked) */
+  directory: string;
+  name: string;
+  framework: 'laravel' | 'symfony' | 'wordpress' | 'drupal' | 'codeigniter' | 'vite' | 'next' | 'nuxt' | 'php' | 'static' | null;
+  framework_version: string | null;
+  docroot: string;
+  host_type: 'static' | 'php' | 'vue' | 'react';
+  php_constraint: string | null;
+  /** Newest installed PHP satisfying the constraint */
+  php_version: string | null;
+  /** Vhost template carrying the framework's rewrite rules */
+  template: VhostTemplateName;
+  rewrites: string[];
+  notes: string[];
+}
+
+export async function inspectProject(directory: string): Promise<ProjectInspection> {
+  return await invoke<ProjectInspection>('inspect_project', { directory });
+}
+
+export type ProjectScaffold = 'blank' | 'laravel' | 'wordpress' | 'static';
+
+export interface NewProject {
+  /** `<name>.local`, registered with SSL */
+  domain: string;
+  directory: string;
+  docroot: string;
+  /** Laravel runs Composer as a job; the host is registered when it finishes */
+  job_id: string | null;
+}
+
+/** Create `<projects dir>/<name>` from a scaffold, register `<name>.local` and apply configs */
+export async function createProject(name: string, scaffold: ProjectScaffold): Promise<NewProject> {
+  return await invoke<NewProject>('create_project', { name, scaffold });
+}
+
+/** A project found under the projects directory, proposed as `<folder>.test` */
+export interface DiscoveredHost {
+  domain: string;
+  directory: string;
+  docroot: string;
+  /** Parent folder name */
+  group: string;
+  host_type: 'static' | 'php' | 'vue' | 'react';
+  framework: string | null;
+  template: VhostTemplateName;
+  php_version: string | null;
+  /** Already served by an existing host */
+  registered: boolean;
+}
+
+export async function scanProjects(): Promise<DiscoveredHost[]> {
+  return await invoke<DiscoveredHost[]>('scan_projects');
+}
+
+/** Register the chosen hosts and apply configs; returns how many were added */
+export async function importProjects(hosts: DiscoveredHost[]): Promise<number> {
+  return await invoke<number>('import_projects', { hosts });
+}
+
+// Projects park: new folders become hosts while `parkProjects` is on
+export interface ProjectsParkEvent {
+  stage: 'started' | 'stopped' | 'registering' | 'done' | 'error';
+  message: string;
+}
+
+export async function isProjectsParkRunning(): Promise<boolean> {
+  return await invoke<boolean>('is_projects_park_running');
+}
+
+export type VhostTemplateName =
+  | 'php'
+  | 'front-controller'
+  | 'laravel'
+  | 'symfony'
+  | 'wordpress'
+  | 'wordpress-multisite'
+  | 'spa'
+  | 'static';
+
+/** A vhost template for one web server; `custom` is set when the user overrides the built-in */
+export interface VhostTemplate {
+  name: VhostTemplateName;
+  server: WebServer;
+  builtin: string;
+  custom: string | null;
+  /** Where the override lives (or would) */
+  path: string;
+}
+
+export async function getVhostTemplates(): Promise<VhostTemplate[]> {
+  return await invoke<VhostTemplate[]>('get_vhost_templates');
+}
+
+/** Override a template; applied the next time configs are generated */
+export async function saveVhostTemplate(server: WebServer, name: VhostTemplateName, content: string): Promise<void> {
+  await invoke('save_vhost_template', { server, name, content });
+}
+
+/** Drop the override and go back to the built-in template */
+export async function resetVhostTemplate(server: WebServer, name: VhostTemplateName): Promise<void> {
+  await invoke('reset_vhost_template', { server, name });
+}
+
+/** The host's template rendered for a web server, as it would be included */
+export async function previewVhostTemplate(domain: string, server: WebServer): Promise<string> {
+  return await invoke<string>('preview_vhost_template', { domain, server });
+}
+
+export async function getProjectProfiles(): Promise<ProjectProfile[]> {
+  return await invoke<ProjectProfile[]>('get_project_profiles');
+}
+
+/** Create or update a profile; an empty id creates a new one */
+export async function saveProjectProfile(profile: ProjectProfile): Promise<ProjectProfile> {
+  return await invoke<ProjectProfile>('save_project_profile', { profile });
+}
+
+export async function deleteProjectProfile(id: string): Promise<void> {
+  return await invoke('delete_project_profile', { id });
+}
+
+/** Bind a host to a profile's PHP-FPM pool, or back to the shared pool with null */
+export async function assignProjectProfile(domain: string, profileId: string | null): Promise<void> {
+  return await invoke('assign_project_profile', { domain, profileId });
 }
 
 export async function getVirtualHosts(): Promise<Record<string, VirtualHost>> {
@@ -41,10 +452,85 @@ export async function saveVirtualHosts(hosts: Record<string, VirtualHost>): Prom
   await invoke('save_virtual_hosts', { hosts });
 }
 
+// Background jobs
+export type JobStatus =
+  | { state: 'running' }
+  | { state: 'succeeded' }
+  | { state: 'failed'; code: number | null }
+  | { state: 'cancelled' };
+
+export interface JobEvent {
+  job_id: string;
+  stream: 'stdout' | 'stderr' | 'status';
+  line: string;
+  status: JobStatus;
+}
+
+export const JOB_EVENT = 'job-output';
+
+/** Starts a package install as a background job and returns its ID; see `runJob` */
+export async function installPackage(pkg: string, version = ''): Promise<string> {
+  return await invoke<string>('install_package', { package: pkg, version });
+}
+
+/** Starts installing a missing PHP extension as a background job and returns its ID; see `runJob` */
+export async function installPhpExtension(version: string, extension: string): Promise<string> {
+  return await invoke<string>('install_php_extension', { version, extension });
+}
+
+export async function cancelJob(jobId: string): Promise<void> {
+  await invoke('cancel_job', { jobId });
+}
+
+export async function listRunningJobs(): Promise<string[]> {
+  return await invoke<string[]>('list_running_jobs');
+}
+
+/**
+ * Start a job and follow it to completion, passing every output line to `onEvent`.
+ * Resolves with the final event on success and rejects with its message otherwise.
+ */
+export async function runJob(
+  start: () => Promise<string>,
+  onEvent?: (event: JobEvent) => void
+): Promise<JobEvent> {
+  let jobId: string | null = null;
+  const early: JobEvent[] = [];
+  let finish!: (event: JobEvent) => void;
+  const finished = new Promise<JobEvent>((resolve) => (finish = resolve));
+
+  const handle = (event: JobEvent) => {
+    if (event.job_id !== jobId) return;
+    onEvent?.(event);
+    if (event.status.state !== 'running') finish(event);
+  };
+
+  // Listen before starting so fast jobs can't finish unnoticed
+  const unlisten = await listen<JobEvent>(JOB_EVENT, ({ payload }) => {
+    if (jobId) handle(payload);
+    else early.push(payload);
+  });
+
+  try {
+    jobId = await start();
+    early.splice(0).forEach(handle);
+
+    const last = await finished;
+    if (last.status.state !== 'succeeded') throw new Error(last.line);
+    return last;
+  } finally {
+    unlisten();
+  }
+}
+
 export async function getServicesStatus(): Promise<ServicesStatus> {
   return await invoke<ServicesStatus>('get_services_status');
 }
 
+export async function getHostUrl(domain: string, ssl: boolean): Promise<string> {
+  return await invoke<string>('get_host_url', { domain, ssl });
+}
+
 export async function getSystemLanguage(): Promise<string> {
   return await invoke<string>('get_system_language');
 }
@@ -53,8 +539,8 @@ export async function executeWithPrivileges(command: string, args: string[]): Pr
   return await invoke<string>('execute_with_privileges', { command, args });
 }
 
-export async function getCurrentPhpVersion(): Promise<string> {
-  return await invoke<string>('get_current_php_version');
+export async function getCurrentPhpVersion(directory?: string): Promise<string> {
+  return await invoke<string>('get_current_php_version', { directory: directory ?? null });
 }
 
 export async function getCurrentApacheVersion(): Promise<string> {
@@ -77,6 +563,17 @@ export async function controlService(action: string, service: string): Promise<s
   return await invoke<string>('control_service', { action, service });
 }
 
+export interface ServiceOutcome {
+  service: string;
+  success: boolean;
+  skipped: boolean;
+  message: string;
+}
+
+export async function controlAllServices(action: string): Promise<ServiceOutcome[]> {
+  return await invoke<ServiceOutcome[]>('control_all_services', { action });
+}
+
 export async function deleteHost(domain: string): Promise<void> {
   await invoke('delete_host', { domain });
 }
@@ -89,6 +586,81 @@ export async function getScriptsPath(): Promise<string> {
   return await invoke<string>('get_scripts_path');
 }
 
+export interface Layout {
+  config_dir: string;
+  base_dir: string;
+  conf_dir: string;
+  hosts_file: string;
+  scripts_dir: string;
+  certs_dir: string;
+  projects_dir: string | null;
+  php_install_dir: string;
+  projects_config_dir: string;
+}
+
+export async function getLayout(): Promise<Layout> {
+  return await invoke<Layout>('get_layout');
+}
+
+// Settings functions
+export type WebServer = 'apache' | 'nginx';
+
+/** `conf-d` writes 99-localhost-manager.ini to the scan directory; `php-ini` edits php.ini */
+export type PhpConfigMode = 'conf-d' | 'php-ini';
+
+export interface Ports {
+  http: number;
+  https: number;
+  phpFpm: number;
+  mysql: number;
+  bindAddress: string;
+  phpFpmHost: string;
+  phpFpmSocket: string | null;
+}
+
+export interface UiPreferences {
+  language: string | null;
+  theme: string;
+  minimizeToTray: boolean;
+  notifications: boolean;
+  watchHostsFile: boolean;
+}
+
+export interface Settings {
+  schemaVersion: number;
+  stack: string;
+  projectsPath: string | null;
+  /** Register new folders under projectsPath as `<folder>.test` hosts automatically */
+  parkProjects: boolean;
+  configPath: string | null;
+  sslPath: string | null;
+  defaultPhpVersion: string | null;
+  phpMirrorUrl: string;
+  /** WordPress `.tar.gz` used by the "new project" scaffold */
+  wordpressDownloadUrl: string;
+  phpConfigMode: PhpConfigMode;
+  webServer: WebServer;
+  ports: Ports;
+  ui: UiPreferences;
+  setupCompleted: boolean;
+  setupDate: string | null;
+}
+
+/** Deep partial used to patch nested settings such as `{ ui: { theme: 'dark' } }` */
+export type SettingsPatch = {
+  [K in keyof Settings]?: Settings[K] extends object | null ? Partial<NonNullable<Settings[K]>> | null : Settings[K];
+};
+
+export const SETTINGS_CHANGED_EVENT = 'settings-changed';
+
+export async function getSettings(): Promise<Settings> {
+  return await invoke<Settings>('get_settings');
+}
+
+export async function updateSettings(patch: SettingsPatch): Promise<Settings> {
+  return await invoke<Settings>('update_settings', { patch });
+}
+
 // Setup Wizard functions
 export interface DetectedStack {
   name: string;
@@ -123,3 +695,28 @@ export async function readFile(path: string): Promise<string> {
 export async function writeFile(path: string, content: string): Promise<void> {
   await invoke('write_file', { path, content });
 }
+
+// hosts.json watcher
+export interface HostsWatcherEvent {
+  stage: 'started' | 'stopped' | 'validating' | 'invalid' | 'generating' | 'done' | 'error';
+  message: string;
+}
+
+export const HOSTS_WATCHER_EVENT = 'hosts-watcher';
+
+/** Follow the hosts.json watcher; resolves with the function that stops listening */
+export async function onHostsWatcherEvent(handler: (event: HostsWatcherEvent) => void): Promise<() => void> {
+  return await listen<HostsWatcherEvent>(HOSTS_WATCHER_EVENT, ({ payload }) => handler(payload));
+}
+
+export async function startHostsWatcher(): Promise<void> {
+  await invoke('start_hosts_watcher');
+}
+
+export async function stopHostsWatcher(): Promise<void> {
+  await invoke('stop_hosts_watcher');
+}
+
+export async function isHostsWatcherRunning(): Promise<boolean> {
+  return await invoke<boolean>('is_hosts_watcher_running');
+}
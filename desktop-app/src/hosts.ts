@@ -9,7 +9,6 @@ import { showToast } from './ui';
 import * as api from './api';
 import type { ApacheAction, VirtualHost, ServicesStatus } from './types';
 
-const SCRIPTS_PATH = '/Users/mario/localhost-manager/scripts';
 
 // Stack configuration
 interface StackConfig {
@@ -98,9 +97,14 @@ let servicesStatus: ServicesStatus = {
   apache: false,
   mysql: false,
   php: false,
-  all_running: false
+  all_running: false,
+  ports: { http: false, https: false, php_fpm: false, mysql: false },
+  pools: []
 };
 let currentHost: VirtualHost | null = null;
+
+/** Inspector proposal for the folder picked in the Add Host modal */
+let modalInspection: api.ProjectInspection | null = null;
 // let currentStack: StackConfig = STACK_CONFIGS.native;
 
 export async function loadVirtualHosts() {
@@ -481,6 +485,53 @@ function selectHost(domain: string, linkElement: HTMLElement) {
 
   // Render aliases with remove buttons
   renderAliases(domain);
+  renderPoolSelector(domain);
+}
+
+async function renderPoolSelector(domain: string) {
+  const select = document.getElementById('detail-fpm-pool') as HTMLSelectElement;
+  if (!select) return;
+
+  const host = virtualHosts[domain];
+  const current = host?.fpm_pool ? `${host.fpm_pool.php_version}|${host.fpm_pool.name}` : '';
+
+  // Remove previous listener
+  const newSelect = select.cloneNode(false) as HTMLSelectElement;
+  select.parentNode?.replaceChild(newSelect, select);
+  newSelect.innerHTML = '<option value="">Shared pool</option>';
+
+  try {
+    const versions = await api.getInstalledPhpVersions();
+    for (const { version } of versions) {
+      const pools = await api.getFpmPools(version).catch(() => []);
+      const options = pools
+        .filter(pool => !pool.profile_pool)
+        .map(pool => `<option value="${pool.php_version}|${pool.name}">PHP ${pool.php_version} · ${pool.name} (${pool.listen})</option>`)
+        .join('');
+      if (options) newSelect.insertAdjacentHTML('beforeend', `<optgroup label="PHP ${version}">${options}</optgroup>`);
+    }
+  } catch (error) {
+    console.error('Error loading PHP-FPM pools:', error);
+  }
+
+  // Another host was selected while the pools loaded
+  if (!newSelect.isConnected) return;
+  newSelect.value = current;
+
+  newSelect.addEventListener('change', async () => {
+    const [version, pool] = newSelect.value ? newSelect.value.split('|') : [null, null];
+    try {
+      await api.assignFpmPool(domain, version, pool);
+      await loadVirtualHosts();
+      const hostLink = document.querySelector(`.host-link[data-domain="${domain}"]`) as HTMLElement;
+      if (hostLink) selectHost(domain, hostLink);
+      showToast(pool ? `${domain} now uses pool ${pool} of PHP ${version}` : `${domain} now uses the shared pool`, 'success');
+    } catch (error) {
+      console.error('Error assigning PHP-FPM pool:', error);
+      showToast(`Failed to assign pool: ${error}`, 'error');
+      newSelect.value = current;
+    }
+  });
 }
 
 function renderAliases(domain: string) {
@@ -759,10 +810,8 @@ export async function openCurrentHost() {
     return;
   }
 
-  const protocol = currentHost.ssl ? 'https://' : 'http://';
-  const url = `${protocol}${currentHost.domain}`;
-
   try {
+    const url = await api.getHostUrl(currentHost.domain, currentHost.ssl);
     await openUrl(url);
     showToast(`Opening ${currentHost.domain}...`, 'success');
   } catch (error) {
@@ -786,6 +835,10 @@ function updateServicesUI() {
 
   if (phpLed) {
     phpLed.className = `service-led ${servicesStatus.php ? 'on' : ''}`;
+    // One line per FPM pool, as checked over FastCGI
+    phpLed.title = servicesStatus.pools
+      .map(p => `PHP ${p.php_version} [${p.pool}] ${p.answering && p.ping !== false ? 'OK' : p.error ?? 'not answering'}`)
+      .join('\n');
   }
 
   // Update toggle button
@@ -803,7 +856,7 @@ function updateServicesUI() {
 
 export async function executeApacheCommand(action: ApacheAction) {
   try {
-    const scriptPath = `${SCRIPTS_PATH}/${action}-apache-native.sh`;
+    const scriptPath = `${await api.getScriptsPath()}/${action}-apache-native.sh`;
     const command = Command.create('bash', [scriptPath]);
 
     const actionVerb = action.charAt(0).toUpperCase() + action.slice(1);
@@ -843,35 +896,64 @@ export async function generateConfigs() {
   }
 }
 
+/**
+ * Report what the hosts.json watcher does with outside edits, and bind the
+ * configuration modal's switch to the `watchHostsFile` preference
+ */
+export async function initHostsWatcher() {
+  await api.onHostsWatcherEvent(async (event) => {
+    switch (event.stage) {
+      case 'invalid':
+        showToast(`hosts.json was not applied: ${event.message}`, 'error');
+        break;
+      case 'error':
+        showToast(`hosts.json watcher: ${event.message}`, 'error');
+        break;
+      case 'done':
+        showToast('hosts.json changed, configuration applied', 'success');
+        await loadVirtualHosts();
+        break;
+    }
+  });
+
+  const toggle = document.getElementById('config-watch-hosts') as HTMLInputElement | null;
+  if (!toggle) return;
+
+  document.getElementById('config-modal')?.addEventListener('show.bs.modal', async () => {
+    try {
+      toggle.checked = (await api.getSettings()).ui.watchHostsFile;
+    } catch (error) {
+      console.error('Error loading settings:', error);
+    }
+  });
+
+  toggle.addEventListener('change', async () => {
+    try {
+      // The backend starts or stops the watcher when the setting changes
+      await api.updateSettings({ ui: { watchHostsFile: toggle.checked } });
+    } catch (error) {
+      console.error('Error saving settings:', error);
+      showToast(`Failed to save setting: ${error}`, 'error');
+      toggle.checked = !toggle.checked;
+    }
+  });
+}
+
 export async function toggleServices() {
   const action = servicesStatus.all_running ? 'stop' : 'start';
 
   try {
     showToast(`${action === 'start' ? 'Starting' : 'Stopping'} services...`, 'warning');
 
-    // Detect installed services dynamically
-    const detectCmd = Command.create('sh', ['-c', '/opt/homebrew/bin/brew services list | grep -E "httpd|mysql|php" | awk \'{print $1}\'']);
-    const detectOutput = await detectCmd.execute();
-
-    let services = ['httpd', 'mysql', 'php']; // defaults
-    if (detectOutput.code === 0 && detectOutput.stdout) {
-      const detected = detectOutput.stdout.trim().split('\n').filter(s => s);
-      if (detected.length > 0) {
-        services = detected;
-      }
-    }
-
-    // Build command with detected services
-    const serviceCommands = services.map(s => `/opt/homebrew/bin/brew services ${action} ${s}`).join(' && ');
-    const command = Command.create('sh', ['-c', serviceCommands]);
-    const output = await command.execute();
+    const outcomes = await api.controlAllServices(action);
+    const failed = outcomes.filter(o => !o.success);
 
-    if (output.code === 0) {
+    if (failed.length === 0) {
       showToast('Services toggled successfully', 'success');
-      setTimeout(() => loadServicesStatus(), 2000);
     } else {
-      showToast(`Failed to toggle services: ${output.stderr}`, 'error');
+      showToast(`Failed to toggle services: ${failed.map(o => `${o.service}: ${o.message}`).join(', ')}`, 'error');
     }
+    setTimeout(() => loadServicesStatus(), 2000);
   } catch (error) {
     console.error('Error toggling services:', error);
     showToast('Failed to toggle services', 'error');
@@ -901,6 +983,8 @@ export function showAddHostModal() {
   (document.getElementById('modal-active') as HTMLInputElement).checked = true;
   (document.getElementById('modal-ssl') as HTMLInputElement).checked = true;
   (document.getElementById('modal-aliases') as HTMLTextAreaElement).value = '';
+  (document.getElementById('modal-subdomains') as HTMLTextAreaElement).value = '';
+  modalInspection = null;
 
   // Show modal using Bootstrap
   const modal = new (window as any).bootstrap.Modal(modalElement);
@@ -919,13 +1003,17 @@ export function showAddHostModal() {
 }
 
 async function saveHost(modal: any) {
-  const domain = (document.getElementById('modal-domain') as HTMLInputElement).value.trim();
+  const domainInput = (document.getElementById('modal-domain') as HTMLInputElement).value.trim();
+  // `*.tenant.test` registers tenant.test with every subdomain
+  const wildcard = domainInput.startsWith('*.');
+  const domain = wildcard ? domainInput.slice(2) : domainInput;
   const docroot = (document.getElementById('modal-docroot') as HTMLInputElement).value.trim();
   const group = (document.getElementById('modal-group') as HTMLInputElement).value.trim() || 'Uncategorized';
   const type = (document.getElementById('modal-type') as HTMLSelectElement).value;
   const active = (document.getElementById('modal-active') as HTMLInputElement).checked;
   const ssl = (document.getElementById('modal-ssl') as HTMLInputElement).checked;
   const aliasesText = (document.getElementById('modal-aliases') as HTMLTextAreaElement).value.trim();
+  const subdomainsText = (document.getElementById('modal-subdomains') as HTMLTextAreaElement).value.trim();
 
   // Validation
   if (!domain) {
@@ -945,6 +1033,13 @@ async function saveHost(modal: any) {
     .filter(a => a.length > 0)
     .map(value => ({ value }));
 
+  // Tenant labels for wildcard names; `acme.tenant.test` is accepted as `acme`
+  const subdomains = subdomainsText
+    .split('\n')
+    .map(s => s.trim())
+    .map(s => s.endsWith(`.${domain}`) ? s.slice(0, -(domain.length + 1)) : s)
+    .filter(s => s.length > 0);
+
   // Create host object
   const newHost: VirtualHost = {
     domain,
@@ -955,6 +1050,15 @@ async function saveHost(modal: any) {
     ssl,
     aliases: aliases.length > 0 ? aliases as any : []
   };
+  if (wildcard) newHost.wildcard = true;
+  if (subdomains.length > 0) newHost.subdomains = subdomains;
+
+  // Keep the inspector's framework and template while the docroot is the proposed one;
+  // the template already carries the framework's rewrite rules
+  if (modalInspection && modalInspection.docroot === docroot) {
+    if (modalInspection.framework) newHost.framework = modalInspection.framework;
+    newHost.template = modalInspection.template;
+  }
 
   try {
     // TODO: Call Tauri backend to save host
@@ -1109,6 +1213,7 @@ export async function browseModalDocrootFolder() {
       if (docrootInput) {
         docrootInput.value = selected;
       }
+      await applyProjectInspection(selected);
     }
   } catch (error) {
     console.error('Error browsing folder:', error);
@@ -1116,6 +1221,35 @@ export async function browseModalDocrootFolder() {
   }
 }
 
+/** Fill the Add Host form from what the inspector finds in the picked folder */
+async function applyProjectInspection(directory: string) {
+  try {
+    const inspection = await api.inspectProject(directory);
+    modalInspection = inspection;
+
+    (document.getElementById('modal-docroot') as HTMLInputElement).value = inspection.docroot;
+    (document.getElementById('modal-type') as HTMLSelectElement).value = inspection.host_type;
+
+    const domainInput = document.getElementById('modal-domain') as HTMLInputElement;
+    if (!domainInput.value.trim() && inspection.name) {
+      domainInput.value = `${inspection.name.toLowerCase().replace(/[^a-z0-9-]+/g, '-')}.test`;
+    }
+
+    if (inspection.framework && inspection.framework !== 'static') {
+      const version = inspection.framework_version ? ` ${inspection.framework_version}` : '';
+      const php = inspection.php_constraint
+        ? ` · PHP ${inspection.php_constraint}${inspection.php_version ? ` (using ${inspection.php_version})` : ''}`
+        : '';
+      showToast(`Detected ${inspection.framework}${version}${php}`, 'success');
+    }
+    for (const note of inspection.notes) {
+      showToast(note, 'warning');
+    }
+  } catch (error) {
+    console.error('Error inspecting project:', error);
+  }
+}
+
 // ============================================
 // Drag & Drop Functionality
 // ============================================
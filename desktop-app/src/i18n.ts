@@ -62,6 +62,13 @@ const translations: Record<string, Translations> = {
     'versions.detecting': 'Detecting...',
     'versions.notInstalled': 'Not installed',
     'versions.installMore': 'Install More',
+    'versions.cancelInstall': 'Cancel',
+    'pools.title': 'PHP-FPM Pools',
+    'pools.hostPool': 'PHP-FPM Pool',
+    'pools.shared': 'Shared pool',
+    'pools.hostPoolHint': 'Changes apply to the vhost configs right away.',
+    'pools.name': 'Name',
+    'pools.save': 'Save',
 
     // Modal
     'modal.addHost': 'Add Virtual Host',
@@ -78,6 +85,9 @@ const translations: Record<string, Translations> = {
     'modal.aliases': 'Aliases',
     'modal.aliasesPlaceholder': 'www.example.test\\nalias.example.test',
     'modal.aliasesHint': 'One alias per line',
+    'modal.subdomains': 'Tenant subdomains',
+    'modal.subdomainsPlaceholder': 'acme\\nglobex',
+    'modal.subdomainsHint': 'For wildcard domains (*.example.test): one subdomain per line, added to the hosts file',
     'modal.cancel': 'Cancel',
     'modal.save': 'Save Host',
 
@@ -105,6 +115,8 @@ const translations: Record<string, Translations> = {
     'config.scriptsPath': 'Scripts Directory Path',
     'config.autoDetect': 'Auto-detect Paths',
     'config.saveConfig': 'Save Configuration',
+    'config.watchHosts': 'Watch hosts.json',
+    'config.watchHostsHint': 'Apply edits made outside the app (editor, git) automatically',
 
     // Setup Wizard
     'wizard.title': 'Welcome to Localhost Manager',
@@ -377,6 +389,13 @@ const translations: Record<string, Translations> = {
     'versions.detecting': 'Detectando...',
     'versions.notInstalled': 'No instalado',
     'versions.installMore': 'Instalar Más',
+    'versions.cancelInstall': 'Cancelar',
+    'pools.title': 'Pools de PHP-FPM',
+    'pools.hostPool': 'Pool de PHP-FPM',
+    'pools.shared': 'Pool compartido',
+    'pools.hostPoolHint': 'Los cambios se aplican a los vhosts al momento.',
+    'pools.name': 'Nombre',
+    'pools.save': 'Guardar',
 
     // Modal
     'modal.addHost': 'Agregar Host Virtual',
@@ -393,6 +412,9 @@ const translations: Record<string, Translations> = {
     'modal.aliases': 'Alias',
     'modal.aliasesPlaceholder': 'www.ejemplo.test\\nalias.ejemplo.test',
     'modal.aliasesHint': 'Un alias por línea',
+    'modal.subdomains': 'Subdominios de tenants',
+    'modal.subdomainsPlaceholder': 'acme\\nglobex',
+    'modal.subdomainsHint': 'Para dominios wildcard (*.ejemplo.test): un subdominio por línea, se añaden al archivo hosts',
     'modal.cancel': 'Cancelar',
     'modal.save': 'Guardar Host',
 
@@ -420,6 +442,8 @@ const translations: Record<string, Translations> = {
     'config.scriptsPath': 'Ruta del Directorio de Scripts',
     'config.autoDetect': 'Auto-detectar Rutas',
     'config.saveConfig': 'Guardar Configuración',
+    'config.watchHosts': 'Vigilar hosts.json',
+    'config.watchHostsHint': 'Aplica automáticamente los cambios hechos fuera de la app (editor, git)',
 
     // Setup Wizard
     'wizard.title': 'Bienvenido a Localhost Manager',
@@ -692,6 +716,13 @@ const translations: Record<string, Translations> = {
     'versions.detecting': 'Détection...',
     'versions.notInstalled': 'Non installé',
     'versions.installMore': 'Installer Plus',
+    'versions.cancelInstall': 'Annuler',
+    'pools.title': 'Pools PHP-FPM',
+    'pools.hostPool': 'Pool PHP-FPM',
+    'pools.shared': 'Pool partagé',
+    'pools.hostPoolHint': 'Les changements sont appliqués aux vhosts immédiatement.',
+    'pools.name': 'Nom',
+    'pools.save': 'Enregistrer',
 
     // Modal
     'modal.addHost': 'Ajouter un Hôte Virtuel',
@@ -708,6 +739,9 @@ const translations: Record<string, Translations> = {
     'modal.aliases': 'Alias',
     'modal.aliasesPlaceholder': 'www.exemple.test\\nalias.exemple.test',
     'modal.aliasesHint': 'Un alias par ligne',
+    'modal.subdomains': 'Sous-domaines des tenants',
+    'modal.subdomainsPlaceholder': 'acme\\nglobex',
+    'modal.subdomainsHint': 'Pour les domaines wildcard (*.exemple.test) : un sous-domaine par ligne, ajouté au fichier hosts',
     'modal.cancel': 'Annuler',
     'modal.save': 'Enregistrer',
 
@@ -735,6 +769,8 @@ const translations: Record<string, Translations> = {
     'config.scriptsPath': 'Chemin du Répertoire des Scripts',
     'config.autoDetect': 'Auto-détecter les Chemins',
     'config.saveConfig': 'Enregistrer la Configuration',
+    'config.watchHosts': 'Surveiller hosts.json',
+    'config.watchHostsHint': 'Applique automatiquement les modifications faites hors de l\'app (éditeur, git)',
 
     // Setup Wizard
     'wizard.title': 'Bienvenue dans Localhost Manager',
@@ -1007,6 +1043,13 @@ const translations: Record<string, Translations> = {
     'versions.detecting': 'Erkennung...',
     'versions.notInstalled': 'Nicht installiert',
     'versions.installMore': 'Mehr Installieren',
+    'versions.cancelInstall': 'Abbrechen',
+    'pools.title': 'PHP-FPM-Pools',
+    'pools.hostPool': 'PHP-FPM-Pool',
+    'pools.shared': 'Gemeinsamer Pool',
+    'pools.hostPoolHint': 'Änderungen werden sofort auf die Vhosts angewendet.',
+    'pools.name': 'Name',
+    'pools.save': 'Speichern',
 
     // Modal
     'modal.addHost': 'Virtuellen Host Hinzufügen',
@@ -1023,6 +1066,9 @@ const translations: Record<string, Translations> = {
     'modal.aliases': 'Aliase',
     'modal.aliasesPlaceholder': 'www.beispiel.test\\nalias.beispiel.test',
     'modal.aliasesHint': 'Ein Alias pro Zeile',
+    'modal.subdomains': 'Tenant-Subdomains',
+    'modal.subdomainsPlaceholder': 'acme\\nglobex',
+    'modal.subdomainsHint': 'Für Wildcard-Domains (*.beispiel.test): eine Subdomain pro Zeile, wird in die hosts-Datei eingetragen',
     'modal.cancel': 'Abbrechen',
     'modal.save': 'Host Speichern',
 
@@ -1050,6 +1096,8 @@ const translations: Record<string, Translations> = {
     'config.scriptsPath': 'Skript-Verzeichnispfad',
     'config.autoDetect': 'Pfade Auto-erkennen',
     'config.saveConfig': 'Konfiguration Speichern',
+    'config.watchHosts': 'hosts.json überwachen',
+    'config.watchHostsHint': 'Änderungen außerhalb der App (Editor, git) automatisch übernehmen',
 
     // Setup Wizard
     'wizard.title': 'Willkommen bei Localhost Manager',
@@ -1322,6 +1370,13 @@ const translations: Record<string, Translations> = {
     'versions.detecting': 'Detectando...',
     'versions.notInstalled': 'Não instalado',
     'versions.installMore': 'Instalar Mais',
+    'versions.cancelInstall': 'Cancelar',
+    'pools.title': 'Pools PHP-FPM',
+    'pools.hostPool': 'Pool PHP-FPM',
+    'pools.shared': 'Pool compartilhado',
+    'pools.hostPoolHint': 'As alterações são aplicadas aos vhosts imediatamente.',
+    'pools.name': 'Nome',
+    'pools.save': 'Salvar',
 
     // Modal
     'modal.addHost': 'Adicionar Host Virtual',
@@ -1338,6 +1393,9 @@ const translations: Record<string, Translations> = {
     'modal.aliases': 'Aliases',
     'modal.aliasesPlaceholder': 'www.exemplo.test\\nalias.exemplo.test',
     'modal.aliasesHint': 'Um alias por linha',
+    'modal.subdomains': 'Subdomínios de tenants',
+    'modal.subdomainsPlaceholder': 'acme\\nglobex',
+    'modal.subdomainsHint': 'Para domínios wildcard (*.exemplo.test): um subdomínio por linha, adicionado ao arquivo hosts',
     'modal.cancel': 'Cancelar',
     'modal.save': 'Salvar Host',
 
@@ -1365,6 +1423,8 @@ const translations: Record<string, Translations> = {
     'config.scriptsPath': 'Caminho do Diretório de Scripts',
     'config.autoDetect': 'Auto-detectar Caminhos',
     'config.saveConfig': 'Salvar Configuração',
+    'config.watchHosts': 'Monitorar hosts.json',
+    'config.watchHostsHint': 'Aplica automaticamente as alterações feitas fora do app (editor, git)',
 
     // Setup Wizard
     'wizard.title': 'Bem-vindo ao Localhost Manager',
@@ -142,12 +142,24 @@ function initializeEventListeners() {
     const version = (e.target as HTMLSelectElement).value;
     if (version) {
       try {
-        await api.controlService('switch', `php@${version}`);
+        const active = await api.usePhpVersion(version);
+        if (!active.shims_on_path) {
+          console.warn(`Add ${active.shims_dir} to your PATH to use PHP ${active.version} from the terminal`);
+        }
         await phpManager.loadCurrentVersions();
       } catch (error) {
         console.error('Error switching PHP version:', error);
       }
     }
+    await phpManager.loadFpmPools(version);
+  });
+
+  document.getElementById('btn-save-pool')?.addEventListener('click', () => {
+    phpManager.saveFpmPool();
+  });
+
+  document.getElementById('btn-cancel-php-install')?.addEventListener('click', () => {
+    phpManager.cancelInstall();
   });
 
   document.getElementById('apache-version-selector')?.addEventListener('change', async (e) => {
@@ -232,6 +244,7 @@ async function loadInitialData() {
   }
 
   await hosts.loadVirtualHosts();
+  await hosts.initHostsWatcher();
   await phpManager.loadAvailableVersions();
   await phpManager.loadInstalledVersions();
   hosts.startServicesPolling();
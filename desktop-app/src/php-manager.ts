@@ -5,6 +5,7 @@
 import { state } from './state';
 import * as api from './api';
 import { showToast } from './ui';
+import type { FpmPoolOverview, PhpOverrides, PmMode } from './api';
 
 export async function loadAvailableVersions() {
   try {
@@ -128,18 +129,60 @@ export function renderInstalledVersions() {
   `).join('');
 }
 
+/** Job ID of the PHP install in progress, so it can be cancelled */
+let installJob: string | null = null;
+let installCancelled = false;
+
 export async function install(version: string) {
+  const progress = document.getElementById('php-install-progress');
+  const logEl = document.getElementById('php-install-log');
+  const cancelBtn = document.getElementById('btn-cancel-php-install') as HTMLButtonElement | null;
+
+  progress?.classList.remove('d-none');
+  if (logEl) logEl.textContent = `Installing PHP ${version}...\n`;
+  if (cancelBtn) cancelBtn.disabled = false;
+  installCancelled = false;
+
   try {
     showToast(`Installing PHP ${version}...`, 'warning');
 
-    const result = await api.installPhpVersion(version);
+    const result = await api.runJob(
+      async () => (installJob = await api.installPhpVersion(version)),
+      (event) => {
+        if (logEl) {
+          logEl.textContent += event.line + '\n';
+          logEl.scrollTop = logEl.scrollHeight;
+        }
+      }
+    );
 
-    showToast(result, 'success');
+    showToast(result.line, 'success');
     await loadInstalledVersions();
     await loadAvailableVersions();
   } catch (error) {
-    console.error('Installation error:', error);
-    showToast(`Installation failed: ${error}`, 'error');
+    if (installCancelled) {
+      showToast(`PHP ${version} installation cancelled`, 'warning');
+    } else {
+      console.error('Installation error:', error);
+      if (logEl) logEl.textContent += `Error: ${error}\n`;
+      showToast(`Installation failed: ${error}`, 'error');
+    }
+  } finally {
+    installJob = null;
+    if (cancelBtn) cancelBtn.disabled = true;
+  }
+}
+
+export async function cancelInstall() {
+  if (!installJob) return;
+
+  try {
+    installCancelled = true;
+    await api.cancelJob(installJob);
+  } catch (error) {
+    installCancelled = false;
+    console.error('Cancel error:', error);
+    showToast(`Failed to cancel the installation: ${error}`, 'error');
   }
 }
 
@@ -222,6 +265,7 @@ async function populateServerVersionSelects() {
       console.error('Error populating PHP versions:', error);
       phpSelect.innerHTML = '<option value="">Error loading versions</option>';
     }
+    await loadFpmPools();
   }
 
   // Apache versions select
@@ -285,6 +329,111 @@ async function populateServerVersionSelects() {
   }
 }
 
+// ============================================
+// PHP-FPM Pools
+// ============================================
+
+let fpmPools: FpmPoolOverview[] = [];
+
+function selectedPhpVersion(): string {
+  return (document.getElementById('php-version-selector') as HTMLSelectElement | null)?.value || '';
+}
+
+export async function loadFpmPools(version: string = selectedPhpVersion()) {
+  const container = document.getElementById('php-fpm-pools');
+  if (!container) return;
+
+  if (!version) {
+    fpmPools = [];
+    container.innerHTML = '<small class="text-muted">Select a PHP version to manage its pools</small>';
+    return;
+  }
+
+  try {
+    fpmPools = await api.getFpmPools(version);
+    renderFpmPools();
+  } catch (error) {
+    console.error('Error loading PHP-FPM pools:', error);
+    fpmPools = [];
+    container.innerHTML = `<small class="text-muted">${error}</small>`;
+  }
+}
+
+function renderFpmPools() {
+  const container = document.getElementById('php-fpm-pools');
+  if (!container) return;
+
+  if (fpmPools.length === 0) {
+    container.innerHTML = '<small class="text-muted">No pools found</small>';
+    return;
+  }
+
+  container.innerHTML = fpmPools.map(pool => {
+    const healthy = pool.health.answering && pool.health.ping !== false;
+    const stats = pool.health.stats;
+    return `
+      <div class="d-flex align-items-center gap-2 mb-1">
+        <span class="badge ${healthy ? 'bg-success' : 'bg-danger'}" title="${pool.health.error ?? ''}">${pool.name}</span>
+        <small class="text-muted">${pool.listen} · ${pool.pm ?? '?'} · ${pool.max_children ?? '?'} children${stats ? ` · ${stats.active_processes}/${stats.total_processes} busy` : ''}</small>
+        <small class="text-muted flex-fill">${pool.hosts.length > 0 ? pool.hosts.join(', ') : ''}</small>
+        ${pool.profile_pool
+          ? '<small class="text-muted">project profile</small>'
+          : `<button class="btn btn-sm btn-ghost-secondary p-0" onclick="window.phpManager.editFpmPool('${pool.name}')"><i class="ti ti-pencil"></i></button>
+             <button class="btn btn-sm btn-ghost-danger p-0" onclick="window.phpManager.deleteFpmPool('${pool.name}')"><i class="ti ti-trash"></i></button>`
+        }
+      </div>
+    `;
+  }).join('');
+}
+
+export function editFpmPool(name: string) {
+  const pool = fpmPools.find(p => p.name === name);
+  if (!pool) return;
+
+  (document.getElementById('pool-name') as HTMLInputElement).value = pool.name;
+  (document.getElementById('pool-listen') as HTMLInputElement).value = pool.listen;
+  (document.getElementById('pool-pm') as HTMLSelectElement).value = pool.pm ?? 'dynamic';
+  (document.getElementById('pool-max-children') as HTMLInputElement).value = String(pool.max_children ?? 5);
+}
+
+export async function saveFpmPool() {
+  const version = selectedPhpVersion();
+  const name = (document.getElementById('pool-name') as HTMLInputElement).value.trim();
+  const listen = (document.getElementById('pool-listen') as HTMLInputElement).value.trim();
+  const pm = (document.getElementById('pool-pm') as HTMLSelectElement).value as PmMode;
+  const maxChildren = parseInt((document.getElementById('pool-max-children') as HTMLInputElement).value, 10);
+
+  if (!version || !name || !listen) {
+    showToast('Pool name and listen address are required', 'error');
+    return;
+  }
+
+  try {
+    await api.saveFpmPool(version, { name, listen, pm, max_children: maxChildren || 1 });
+    showToast(`Pool ${name} saved for PHP ${version}`, 'success');
+    await loadFpmPools(version);
+  } catch (error) {
+    console.error('Error saving PHP-FPM pool:', error);
+    showToast(`Failed to save pool: ${error}`, 'error');
+  }
+}
+
+export async function deleteFpmPool(name: string) {
+  const version = selectedPhpVersion();
+  if (!version || !confirm(`Delete pool ${name} of PHP ${version}? Its hosts go back to the shared pool.`)) {
+    return;
+  }
+
+  try {
+    await api.deleteFpmPool(version, name);
+    showToast(`Pool ${name} deleted`, 'success');
+    await loadFpmPools(version);
+  } catch (error) {
+    console.error('Error deleting PHP-FPM pool:', error);
+    showToast(`Failed to delete pool: ${error}`, 'error');
+  }
+}
+
 export async function loadConfig(version: string) {
   if (!version) {
     clearConfig();
@@ -293,6 +442,8 @@ export async function loadConfig(version: string) {
 
   try {
     const config = await api.getPhpConfig(version);
+    // Versions without a scan directory have no overrides
+    overrides = await api.getPhpOverrides(version).catch(() => null);
     state.setCurrentConfig(config);
     renderIniSettings();
     renderExtensions();
@@ -304,6 +455,7 @@ export async function loadConfig(version: string) {
 
 function clearConfig() {
   state.setCurrentConfig(null);
+  overrides = null;
   const settingsContainer = document.getElementById('php-ini-settings');
   const extensionsContainer = document.getElementById('php-extensions');
 
@@ -316,23 +468,37 @@ function clearConfig() {
   }
 }
 
+/** Overrides of the loaded version, when it's in conf-d mode */
+let overrides: PhpOverrides | null = null;
+
 function renderIniSettings() {
   const container = document.getElementById('php-ini-settings');
   if (!container || !state.currentConfig) return;
 
-  const settings = Object.entries(state.currentConfig.settings).slice(0, 20);
+  const overridden = overrides?.mode === 'conf-d' ? overrides.settings : {};
+  // Settings the app manages come first
+  const settings = Object.entries(state.currentConfig.settings)
+    .sort(([a], [b]) => Number(b in overridden) - Number(a in overridden))
+    .slice(0, 20);
 
   if (settings.length === 0) {
     container.innerHTML = '<p class="empty-state">No settings found</p>';
     return;
   }
 
-  container.innerHTML = settings.map(([key, value]) => `
-    <div class="setting-item">
-      <span class="setting-key">${key}</span>
-      <span class="setting-value">${value}</span>
-    </div>
-  `).join('');
+  container.innerHTML = settings.map(([key, value]) => {
+    const effective = Object.entries(overrides?.effective ?? {})
+      .filter(([, values]) => key in overridden && values[key] != null && values[key] !== value)
+      .map(([sapi, values]) => `${sapi}: ${values[key]}`);
+    return `
+      <div class="setting-item">
+        <span class="setting-key">${key}</span>
+        <span class="setting-value">${value}</span>
+        ${key in overridden ? `<span class="version-badge installed" title="${overrides?.files.cli ?? ''}">override</span>` : ''}
+        ${effective.length > 0 ? `<small class="text-muted">(${effective.join(', ')})</small>` : ''}
+      </div>
+    `;
+  }).join('');
 }
 
 function renderExtensions() {
@@ -4,20 +4,40 @@
 
 import { state } from './state';
 import { showToast, showModal, hideModal, getElementValue, clearForm } from './ui';
+import * as api from './api';
 import type { ProjectProfile } from './types';
 import { open } from "@tauri-apps/plugin-dialog";
 
-const STORAGE_KEY = 'localhost-manager-projects';
+// Profiles used to live in localStorage; they are imported once into the backend
+const LEGACY_STORAGE_KEY = 'localhost-manager-projects';
 
-export function load() {
-  const stored = localStorage.getItem(STORAGE_KEY);
-  const projects = stored ? JSON.parse(stored) : [];
-  state.setProjects(projects);
-  render();
-}
+export async function load() {
+  try {
+    const legacy = localStorage.getItem(LEGACY_STORAGE_KEY);
+    if (legacy) {
+      const failed: ProjectProfile[] = [];
+      for (const project of JSON.parse(legacy) as ProjectProfile[]) {
+        try {
+          await api.saveProjectProfile(project);
+        } catch (error) {
+          console.error(`Error importing project ${project.name}:`, error);
+          failed.push(project);
+        }
+      }
 
-export function save() {
-  localStorage.setItem(STORAGE_KEY, JSON.stringify(state.projects));
+      if (failed.length > 0) {
+        localStorage.setItem(LEGACY_STORAGE_KEY, JSON.stringify(failed));
+      } else {
+        localStorage.removeItem(LEGACY_STORAGE_KEY);
+      }
+    }
+
+    state.setProjects(await api.getProjectProfiles());
+  } catch (error) {
+    console.error('Error loading projects:', error);
+    state.setProjects([]);
+  }
+  render();
 }
 
 export function render() {
@@ -65,7 +85,7 @@ export async function browsePath() {
   }
 }
 
-export function saveProject() {
+export async function saveProject() {
   const name = getElementValue('project-name');
   const path = getElementValue('project-path');
   const phpVersion = getElementValue('project-php-version');
@@ -75,18 +95,21 @@ export function saveProject() {
     return;
   }
 
-  const project: ProjectProfile = {
-    id: Date.now().toString(),
-    name,
-    path,
-    php_version: phpVersion,
-    extensions: [],
-    custom_ini_settings: {}
-  };
-
-  state.addProject(project);
-  save();
-  render();
-  hideAddModal();
-  showToast('Project added successfully', 'success');
+  try {
+    const project = await api.saveProjectProfile({
+      id: '',
+      name,
+      path,
+      php_version: phpVersion,
+      extensions: [],
+      custom_ini_settings: {}
+    });
+
+    state.addProject(project);
+    render();
+    hideAddModal();
+    showToast('Project added successfully', 'success');
+  } catch (error) {
+    showToast(`Error saving project: ${error}`, 'error');
+  }
 }
@@ -327,12 +327,13 @@ async function installSelectedComponents(): Promise<boolean> {
       if (logEl) logEl.textContent += `\n[*] Installing ${pkg.name}...\n`;
 
       try {
-        const result = await invoke<string>('install_package', {
-          package: pkg.name,
-          version: pkg.version || ''
+        await api.runJob(() => api.installPackage(pkg.name, pkg.version || ''), (event) => {
+          if (logEl && event.stream !== 'status') {
+            logEl.textContent += event.line + '\n';
+            logEl.scrollTop = logEl.scrollHeight;
+          }
         });
 
-        if (logEl) logEl.textContent += result + '\n';
         if (logEl) logEl.textContent += `[OK] ${pkg.name} installed successfully\n`;
 
         // Update status badge
@@ -520,8 +521,8 @@ async function runSetup() {
     // Step 3: Import hosts if available
     if (importedHosts.length > 0) {
       if (statusEl) statusEl.textContent = `Importing ${importedHosts.length} hosts...`;
-      const hostsPath = `${config.configPath}/hosts.json`;
-      await api.writeFile(hostsPath, JSON.stringify(importedHosts, null, 2));
+      const { hosts_file } = await api.getLayout();
+      await api.writeFile(hosts_file, JSON.stringify(importedHosts, null, 2));
       await sleep(500);
     }
 
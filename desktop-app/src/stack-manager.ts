@@ -3,6 +3,7 @@
 // ============================================
 
 import { invoke } from "@tauri-apps/api/core";
+import * as api from "./api";
 
 // ============================================
 // Types
@@ -18,6 +19,10 @@ interface ExtensionInfo {
   name: string;
   enabled: boolean;
   installed: boolean;
+  builtin: boolean;
+  zend: boolean;
+  version: string | null;
+  path: string | null;
 }
 
 interface MysqlUser {
@@ -165,13 +170,13 @@ async function installPackage(packageName: string) {
       version = versionSelect?.value || '8.3';
     }
 
-    const result = await invoke<string>('install_package', {
-      package: packageName,
-      version
+    await api.runJob(() => api.installPackage(packageName, version), (event) => {
+      if (logEl) {
+        logEl.textContent += event.line + '\n';
+        logEl.scrollTop = logEl.scrollHeight;
+      }
     });
 
-    if (logEl) logEl.textContent += result + '\n';
-
     await checkInstalledPackages();
     showToast('Success', `${packageName} installed successfully!`, 'success');
   } catch (error) {
@@ -289,13 +294,16 @@ async function loadPhpExtensions() {
             <div class="d-flex justify-content-between align-items-center">
               <div>
                 <span class="fw-medium">${ext.name}</span>
+                ${ext.version ? `<small class="text-muted ms-1">${ext.version}</small>` : ''}
                 ${isCommon ? '<span class="badge bg-primary-lt ms-1">Popular</span>' : ''}
+                ${ext.builtin ? '<span class="badge bg-secondary-lt ms-1">Built-in</span>' : ''}
+                ${ext.zend ? '<span class="badge bg-info-lt ms-1">Zend</span>' : ''}
               </div>
               <label class="form-check form-switch mb-0">
                 <input class="form-check-input ext-toggle" type="checkbox"
                        data-ext="${ext.name}"
                        ${ext.enabled ? 'checked' : ''}
-                       ${!ext.installed ? 'disabled' : ''}>
+                       ${!ext.installed || ext.builtin ? 'disabled' : ''}>
               </label>
             </div>
           </div>
@@ -313,11 +321,17 @@ async function loadPhpExtensions() {
         const enabled = checkbox.checked;
 
         try {
-          await invoke('toggle_php_extension', {
-            version,
-            extension: extName,
-            enable: enabled
-          });
+          if (extName === 'xdebug') {
+            // Keeps its modes and restarts PHP-FPM
+            const status = await api.setXdebug({ version }, enabled);
+            status.warnings.forEach(w => console.warn(w));
+          } else {
+            await invoke('toggle_php_extension', {
+              version,
+              extension: extName,
+              enable: enabled
+            });
+          }
           showToast('Success', `${extName} ${enabled ? 'enabled' : 'disabled'}`, 'success');
         } catch (error) {
           checkbox.checked = !enabled; // Revert
@@ -335,11 +349,76 @@ async function loadPhpExtensions() {
 function filterExtensions() {
   const search = (document.getElementById('ext-search') as HTMLInputElement)?.value.toLowerCase() || '';
   const items = document.querySelectorAll('.extension-item');
+  let matches = 0;
 
   items.forEach(item => {
     const name = (item as HTMLElement).dataset.name || '';
-    (item as HTMLElement).style.display = name.includes(search) ? '' : 'none';
+    const visible = name.includes(search);
+    (item as HTMLElement).style.display = visible ? '' : 'none';
+    if (visible) matches++;
   });
+
+  // Offer to install an extension the build doesn't have
+  document.getElementById('ext-install-item')?.remove();
+  const name = search.trim();
+  const grid = document.getElementById('extensions-grid');
+  if (matches > 0 || !/^[a-z0-9_]+$/.test(name) || !grid) return;
+
+  const col = document.createElement('div');
+  col.id = 'ext-install-item';
+  col.className = 'col-12';
+  col.innerHTML = `
+    <div class="card card-sm">
+      <div class="card-body py-2 px-3">
+        <div class="d-flex justify-content-between align-items-center">
+          <span><span class="fw-medium">${name}</span> is not installed for this PHP version</span>
+          <button class="btn btn-sm btn-primary" id="btn-ext-install">
+            <i class="ti ti-download me-1"></i>Install
+          </button>
+        </div>
+        <pre class="ext-install-log small mb-0 mt-2" style="display: none; max-height: 200px; overflow: auto;"></pre>
+      </div>
+    </div>
+  `;
+  grid.appendChild(col);
+
+  col.querySelector('#btn-ext-install')?.addEventListener('click', () => installPhpExtension(name));
+}
+
+async function installPhpExtension(name: string) {
+  const version = (document.getElementById('ext-php-version') as HTMLSelectElement)?.value;
+  const btn = document.getElementById('btn-ext-install') as HTMLButtonElement | null;
+  const logEl = document.querySelector('#ext-install-item .ext-install-log') as HTMLElement | null;
+  if (!version) return;
+
+  if (btn) {
+    btn.disabled = true;
+    btn.innerHTML = '<span class="spinner-border spinner-border-sm me-1"></span>Installing...';
+  }
+  if (logEl) {
+    logEl.style.display = '';
+    logEl.textContent = '';
+  }
+
+  try {
+    await api.runJob(() => api.installPhpExtension(version, name), (event) => {
+      if (logEl) {
+        logEl.textContent += event.line + '\n';
+        logEl.scrollTop = logEl.scrollHeight;
+      }
+    });
+
+    showToast('Success', `${name} installed and enabled`, 'success');
+    (document.getElementById('ext-search') as HTMLInputElement).value = name;
+    await loadPhpExtensions();
+    filterExtensions();
+  } catch (error) {
+    showToast('Error', `Failed to install ${name}: ${error}`, 'danger');
+    if (btn) {
+      btn.disabled = false;
+      btn.innerHTML = '<i class="ti ti-download me-1"></i>Install';
+    }
+  }
 }
 
 async function restartPhpFpm() {
@@ -350,7 +429,9 @@ async function restartPhpFpm() {
   }
 
   try {
-    await invoke('restart_php_fpm');
+    // Only the master of the version being edited
+    const version = (document.getElementById('ext-php-version') as HTMLSelectElement | null)?.value;
+    await api.restartPhpFpm(version || undefined);
     showToast('Success', 'PHP-FPM restarted successfully', 'success');
   } catch (error) {
     showToast('Error', `Failed to restart PHP-FPM: ${error}`, 'danger');
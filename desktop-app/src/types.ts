@@ -10,6 +10,11 @@ export interface PhpVersion {
   installed: boolean;
   install_path?: string;
   download_url?: string;
+  binary?: string;
+  sapis?: string[];
+  ini_paths?: Record<string, string>;
+  ini_scan_dir?: string;
+  source?: string;
 }
 
 export interface PhpConfig {
@@ -33,6 +38,7 @@ export interface ProjectProfile {
   php_version: string;
   extensions: string[];
   custom_ini_settings: Record<string, string>;
+  fpm_listen?: string | null;
 }
 
 export interface VirtualHostAlias {
@@ -49,6 +55,25 @@ export interface VirtualHost {
   active: boolean;
   ssl: boolean;
   type: string;
+  profile?: string;
+  /** PHP-FPM pool serving the host when it has no profile */
+  fpm_pool?: FpmPoolRef | null;
+  /** Framework the project inspector detected */
+  framework?: string;
+  /** Vhost template name; derived from framework and type when unset */
+  template?: string;
+  /** Also serve every subdomain (`*.<domain>`) */
+  wildcard?: boolean;
+  /** Tenant labels written to the hosts file as `<label>.<domain>` */
+  subdomains?: string[];
+  /** Extra Apache directives for the docroot */
+  rewrites?: string[];
+}
+
+export interface FpmPoolRef {
+  php_version: string;
+  name: string;
+  listen: string;
 }
 
 export interface ServicesStatus {
@@ -56,6 +81,39 @@ export interface ServicesStatus {
   mysql: boolean;
   php: boolean;
   all_running: boolean;
+  ports: PortsStatus;
+  pools: PoolHealth[];
+}
+
+export interface PoolStats {
+  process_manager: string;
+  start_since: number;
+  accepted_conn: number;
+  listen_queue: number;
+  idle_processes: number;
+  active_processes: number;
+  total_processes: number;
+  max_children_reached: number;
+  slow_requests: number;
+}
+
+/** A PHP-FPM pool checked over FastCGI */
+export interface PoolHealth {
+  pool: string;
+  php_version: string;
+  listen: string;
+  answering: boolean;
+  /** Whether ping.path answered as expected; null when the pool has none */
+  ping: boolean | null;
+  stats: PoolStats | null;
+  error: string | null;
+}
+
+export interface PortsStatus {
+  http: boolean;
+  https: boolean;
+  php_fpm: boolean;
+  mysql: boolean;
 }
 
 export type ToastType = 'success' | 'error' | 'warning';
@@ -17,8 +17,9 @@ if [ -z "$SUDO_PASSWORD" ]; then
     echo "Ejecuta primero: bash ~/localhost-manager/scripts/setup-keychain.sh"
     exit 1
 fi
-CONF_DIR="$HOME/localhost-manager/conf"
-CERT_DIR="$HOME/localhost-manager/certs"
+MANAGER_DIR="${LOCALHOST_MANAGER_HOME:-$HOME/localhost-manager}"
+CONF_DIR="$MANAGER_DIR/conf"
+CERT_DIR="${CERT_DIR:-$MANAGER_DIR/certs}"
 SSL_DIR="/etc/apache2/ssl"
 APACHE_VHOSTS="/etc/apache2/extra/httpd-vhosts.conf"
 ETC_HOSTS="/etc/hosts"
@@ -34,7 +34,26 @@ echo ""
 
 # Configurar MySQL 8.4
 echo -e "${YELLOW}Configurando MySQL 8.4...${NC}"
-brew services start mysql@8.4
+
+# Puerto de MySQL (ajustes de la app, 3306 por defecto) en el my.cnf de Homebrew
+MYSQL_PORT="${MYSQL_PORT:-3306}"
+MY_CNF="$(brew --prefix)/etc/my.cnf"
+if [ -f "$MY_CNF" ]; then
+    if grep -q "^port[[:space:]]*=" "$MY_CNF"; then
+        sed -i '' "s/^port[[:space:]]*=.*/port = ${MYSQL_PORT}/" "$MY_CNF"
+    elif grep -q "^\[mysqld\]" "$MY_CNF"; then
+        sed -i '' "/^\[mysqld\]/a\\
+port = ${MYSQL_PORT}
+" "$MY_CNF"
+    else
+        printf '\n[mysqld]\nport = %s\n' "$MYSQL_PORT" >> "$MY_CNF"
+    fi
+else
+    printf '[mysqld]\nport = %s\n' "$MYSQL_PORT" > "$MY_CNF"
+fi
+echo "  Puerto: ${MYSQL_PORT}"
+
+brew services restart mysql@8.4
 if [ $? -eq 0 ]; then
     echo -e "${GREEN}✓ MySQL 8.4 configurado${NC}"
 else
@@ -4,7 +4,7 @@
 # Localhost Manager - Configuration Generator
 # ============================================
 # Este script genera todos los archivos de configuración necesarios:
-# - Virtual Hosts de Apache
+# - Virtual Hosts de Apache (o servidores de nginx)
 # - Certificados SSL
 # - Archivo /etc/hosts
 # ============================================
@@ -19,9 +19,11 @@ BLUE='\033[0;34m'
 NC='\033[0m' # No Color
 
 # Configuración
-SCRIPTS_DIR="$HOME/localhost-manager/scripts"
-CONF_DIR="$HOME/localhost-manager/conf"
-HOSTS_FILE="$CONF_DIR/hosts.json"
+MANAGER_DIR="${LOCALHOST_MANAGER_HOME:-$HOME/localhost-manager}"
+SCRIPTS_DIR="$MANAGER_DIR/scripts"
+CONF_DIR="$MANAGER_DIR/conf"
+HOSTS_FILE="${HOSTS_JSON:-$CONF_DIR/hosts.json}"
+WEB_SERVER="${WEB_SERVER:-apache}"
 
 echo -e "${BLUE}━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━${NC}"
 echo -e "${BLUE}  Localhost Manager - Generador de Configuración${NC}"
@@ -34,9 +36,15 @@ if [ ! -f "$HOSTS_FILE" ]; then
     exit 1
 fi
 
-# 1. Generar Virtual Hosts de Apache
-echo -e "${YELLOW}[1/2]${NC} Generando Virtual Hosts de Apache..."
-if bash "$SCRIPTS_DIR/generate-vhosts-config.sh" > /dev/null 2>&1; then
+# 1. Generar Virtual Hosts de Apache (o servidores de nginx)
+if [ "$WEB_SERVER" = "nginx" ]; then
+    echo -e "${YELLOW}[1/2]${NC} Generando servidores de nginx..."
+    VHOSTS_SCRIPT="generate-nginx-config.sh"
+else
+    echo -e "${YELLOW}[1/2]${NC} Generando Virtual Hosts de Apache..."
+    VHOSTS_SCRIPT="generate-vhosts-config.sh"
+fi
+if bash "$SCRIPTS_DIR/$VHOSTS_SCRIPT" > /dev/null 2>&1; then
     echo -e "${GREEN}✓${NC} Virtual Hosts generados"
 else
     echo -e "${RED}✗${NC} Error generando Virtual Hosts"
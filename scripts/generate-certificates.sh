@@ -15,8 +15,9 @@
 set -e
 
 # Configuration
-CERT_DIR="${CERT_DIR:-$HOME/localhost-manager/certs}"
-HOSTS_JSON="${HOSTS_JSON:-$HOME/localhost-manager/conf/hosts.json}"
+MANAGER_DIR="${LOCALHOST_MANAGER_HOME:-$HOME/localhost-manager}"
+CERT_DIR="${CERT_DIR:-$MANAGER_DIR/certs}"
+HOSTS_JSON="${HOSTS_JSON:-$MANAGER_DIR/conf/hosts.json}"
 DAYS_VALID=3650  # 10 years
 MIN_DAYS_REMAINING=30  # Regenerate if less than this many days remain
 
@@ -66,6 +67,30 @@ cert_is_valid() {
     [ "$expiry_epoch" -gt "$min_epoch" ]
 }
 
+# Check that a certificate lists every DNS name in a SAN string
+# (aliases added after it was issued need a new certificate)
+cert_covers() {
+    local cert_file="$1"
+    local san_string="$2"
+    local cert_text
+    cert_text=$(openssl x509 -noout -text -in "$cert_file" 2>/dev/null) || return 1
+
+    # Entradas SAN una por línea ("DNS:a.test, DNS:*.a.test" -> dos líneas),
+    # para comparar nombres completos: DNS:a.test no debe casar con DNS:a.test.b
+    local sans
+    sans=$(grep -A1 "Subject Alternative Name" <<< "$cert_text" | tail -n1)
+    sans="${sans#"${sans%%[! ]*}"}"
+    sans="${sans//, /$'\n'}"
+
+    local entry
+    IFS=',' read -ra ENTRIES <<< "$san_string"
+    for entry in "${ENTRIES[@]}"; do
+        case "$entry" in
+            DNS:*) grep -qxF "$entry" <<< "$sans" || return 1 ;;
+        esac
+    done
+}
+
 # Generate a single certificate
 generate_cert() {
     local domain="$1"
@@ -73,28 +98,28 @@ generate_cert() {
     local cert_file="$CERT_DIR/${domain}.crt"
     local key_file="$CERT_DIR/${domain}.key"
 
-    # Check if certificate already exists and is valid
-    if cert_is_valid "$cert_file"; then
-        echo -e "  ${YELLOW}⊘${NC} $domain (valid, skipped)"
-        ((SKIPPED++))
-        return 0
-    fi
-
     # Build SAN string
     local san_string="DNS:${domain}"
 
-    # Add aliases to SAN
+    # Add aliases to SAN (wildcard aliases like *.tenant.test included)
     if [ -n "$san_list" ]; then
         IFS=',' read -ra SANS <<< "$san_list"
         for san in "${SANS[@]}"; do
             san=$(echo "$san" | xargs 2>/dev/null || echo "$san")
-            [ -n "$san" ] && san_string="${san_string},DNS:${san}"
+            [ -n "$san" ] && [ "$san" != "*.${domain}" ] && san_string="${san_string},DNS:${san}"
         done
     fi
 
     # Add wildcard and IP
     san_string="${san_string},DNS:*.${domain},IP:127.0.0.1"
 
+    # Check if certificate already exists, is valid and covers every name
+    if cert_is_valid "$cert_file" && cert_covers "$cert_file" "$san_string"; then
+        echo -e "  ${YELLOW}⊘${NC} $domain (valid, skipped)"
+        ((SKIPPED++))
+        return 0
+    fi
+
     # Generate certificate
     if openssl req -x509 -nodes -days "$DAYS_VALID" \
         -newkey rsa:2048 \
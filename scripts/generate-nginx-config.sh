@@ -0,0 +1,137 @@
+#!/bin/bash
+# Script para generar configuración de servidores de nginx desde hosts.json
+# Autor: Localhost Manager
+
+MANAGER_DIR="${LOCALHOST_MANAGER_HOME:-$HOME/localhost-manager}"
+OUTPUT_FILE="$MANAGER_DIR/conf/nginx-vhosts.conf"
+HOSTS_JSON="${HOSTS_JSON:-$MANAGER_DIR/conf/hosts.json}"
+CERT_DIR="${CERT_DIR:-$MANAGER_DIR/certs}"
+
+# Puertos y direcciones (exportados por la app desde settings.json)
+HTTP_PORT="${HTTP_PORT:-80}"
+HTTPS_PORT="${HTTPS_PORT:-443}"
+BIND_ADDRESS="${BIND_ADDRESS:-*}"
+# IPv6 va entre corchetes delante del puerto: [::1]:443
+case "$BIND_ADDRESS" in
+    \[*|\*) ;;
+    *:*) BIND_ADDRESS="[$BIND_ADDRESS]" ;;
+esac
+# Plantillas de cada host ya renderizadas por la app
+SITES_DIR="${SITES_DIR:-$MANAGER_DIR/conf/sites}"
+# PHP del PATH (Linux, Homebrew); si no hay, el php@8.3 de Homebrew
+PHP_BIN="${PHP_BIN:-$(command -v php || echo /opt/homebrew/opt/php@8.3/bin/php)}"
+
+echo "======================================"
+echo " Generador de servidores nginx"
+echo "======================================"
+echo ""
+
+# Verificar que existe hosts.json
+if [ ! -f "$HOSTS_JSON" ]; then
+    echo "Error: No se encontró $HOSTS_JSON"
+    exit 1
+fi
+
+# nginx no acepta "*" como dirección: se escucha en todas las interfaces
+LISTEN_PREFIX=""
+if [ "$BIND_ADDRESS" != "*" ]; then
+    LISTEN_PREFIX="$BIND_ADDRESS:"
+fi
+
+cat > "$OUTPUT_FILE" <<EOF
+# nginx servers - Generated $(date +"%Y-%m-%d %H:%M:%S")
+
+server {
+    listen ${LISTEN_PREFIX}${HTTPS_PORT} ssl default_server;
+    server_name _;
+    ssl_certificate "$CERT_DIR/default.crt";
+    ssl_certificate_key "$CERT_DIR/default.key";
+    return 404;
+}
+EOF
+
+# Procesar cada host del JSON usando PHP para parsear
+"$PHP_BIN" -r '
+$hostsFile = "'$HOSTS_JSON'";
+$certDir = "'$CERT_DIR'";
+$httpPort = "'"$HTTP_PORT"'";
+$httpsPort = "'"$HTTPS_PORT"'";
+$listenPrefix = "'"$LISTEN_PREFIX"'";
+$sitesDir = "'"$SITES_DIR"'";
+$httpsSuffix = $httpsPort === "443" ? "" : ":$httpsPort";
+$hosts = json_decode(file_get_contents($hostsFile), true);
+
+foreach ($hosts as $domain => $config) {
+    // Solo hosts activos
+    if (!isset($config["active"]) || $config["active"] !== true) {
+        continue;
+    }
+
+    $docroot = $config["docroot"];
+    $aliases = isset($config["aliases"]) && is_array($config["aliases"]) ? $config["aliases"] : [];
+
+    // Construir lista de nombres activos (dominio + aliases)
+    $names = [$domain];
+    foreach ($aliases as $alias) {
+        if (is_string($alias) && !empty(trim($alias))) {
+            $names[] = $alias;
+        } elseif (is_array($alias) && isset($alias["value"]) && !empty(trim($alias["value"]))) {
+            $isActive = !isset($alias["active"]) || $alias["active"] === true;
+            if ($isActive) {
+                $names[] = $alias["value"];
+            }
+        }
+    }
+    if (!empty($config["wildcard"])) {
+        $names[] = "*." . $domain;
+    }
+
+    // Nombres wildcard como regex; el subdominio queda en $subdomain
+    $names = array_map(function ($name) {
+        if (strpos($name, "*.") !== 0) {
+            return $name;
+        }
+        return "\"~^(?<subdomain>.+)\\." . str_replace(".", "\\.", substr($name, 2)) . "\$\"";
+    }, $names);
+    $serverName = implode(" ", $names);
+
+    // Servidor HTTP - Redirige a HTTPS
+    echo "\nserver {\n";
+    echo "    listen {$listenPrefix}{$httpPort};\n";
+    echo "    server_name $serverName;\n";
+    echo "    return 301 https://\$host$httpsSuffix\$request_uri;\n";
+    echo "}\n";
+
+    // Servidor HTTPS
+    echo "\nserver {\n";
+    echo "    listen {$listenPrefix}{$httpsPort} ssl;\n";
+    echo "    server_name $serverName;\n";
+    echo "    root \"$docroot\";\n";
+    echo "\n";
+    echo "    ssl_certificate \"$certDir/{$domain}.crt\";\n";
+    echo "    ssl_certificate_key \"$certDir/{$domain}.key\";\n";
+    echo "\n";
+
+    // Plantilla del host (reglas por framework); sin ella solo se sirven archivos
+    $siteFile = $sitesDir . "/" . $domain . ".nginx.conf";
+    if (is_file($siteFile)) {
+        echo "    include \"$siteFile\";\n";
+    } else {
+        echo "    index index.html index.htm;\n";
+        echo "    location / {\n";
+        echo "        try_files \$uri \$uri/ =404;\n";
+        echo "    }\n";
+    }
+    echo "}\n";
+}
+' >> "$OUTPUT_FILE"
+
+echo ""
+echo "======================================"
+echo " Configuración generada exitosamente"
+echo "======================================"
+echo "Archivo: $OUTPUT_FILE"
+echo ""
+echo "Para aplicar la configuración ejecuta:"
+echo "  bash $MANAGER_DIR/scripts/install.sh"
+echo ""
@@ -5,9 +5,23 @@ export PATH="/usr/sbin:/usr/bin:/bin:/sbin:$PATH"
 # Script para generar configuración de Virtual Hosts de Apache desde hosts.json
 # Autor: Localhost Manager
 
-OUTPUT_FILE="$HOME/localhost-manager/conf/vhosts.conf"
-HOSTS_JSON="$HOME/localhost-manager/conf/hosts.json"
-CERT_DIR="$HOME/localhost-manager/certs"
+MANAGER_DIR="${LOCALHOST_MANAGER_HOME:-$HOME/localhost-manager}"
+OUTPUT_FILE="$MANAGER_DIR/conf/vhosts.conf"
+HOSTS_JSON="${HOSTS_JSON:-$MANAGER_DIR/conf/hosts.json}"
+CERT_DIR="${CERT_DIR:-$MANAGER_DIR/certs}"
+
+# Puertos y direcciones (exportados por la app desde settings.json)
+HTTP_PORT="${HTTP_PORT:-80}"
+HTTPS_PORT="${HTTPS_PORT:-443}"
+BIND_ADDRESS="${BIND_ADDRESS:-*}"
+# IPv6 va entre corchetes delante del puerto: [::1]:443
+case "$BIND_ADDRESS" in
+    \[*|\*) ;;
+    *:*) BIND_ADDRESS="[$BIND_ADDRESS]" ;;
+esac
+FPM_HANDLER="${FPM_HANDLER:-proxy:fcgi://127.0.0.1:9000}"
+# Plantillas de cada host ya renderizadas por la app
+SITES_DIR="${SITES_DIR:-$MANAGER_DIR/conf/sites}"
 
 echo "======================================"
 echo " Generador de Virtual Hosts"
@@ -20,11 +34,28 @@ if [ ! -f "$HOSTS_JSON" ]; then
     exit 1
 fi
 
+# httpd.conf y httpd-ssl.conf ya escuchan en 80/443; solo se agregan
+# Listen para puertos personalizados (un Listen duplicado impide arrancar Apache)
+LISTEN_PREFIX=""
+if [ "$BIND_ADDRESS" != "*" ]; then
+    LISTEN_PREFIX="$BIND_ADDRESS:"
+fi
+LISTEN_LINES=""
+if [ "$HTTP_PORT" != "80" ]; then
+    LISTEN_LINES="${LISTEN_LINES}Listen ${LISTEN_PREFIX}${HTTP_PORT}
+"
+fi
+if [ "$HTTPS_PORT" != "443" ]; then
+    LISTEN_LINES="${LISTEN_LINES}Listen ${LISTEN_PREFIX}${HTTPS_PORT} https
+"
+fi
+
 # Crear archivo de configuración
 cat > "$OUTPUT_FILE" <<EOF
 # Virtual Hosts - Generated $(date +"%Y-%m-%d %H:%M:%S")
 
-<VirtualHost *:443>
+${LISTEN_LINES}
+<VirtualHost $BIND_ADDRESS:$HTTPS_PORT>
     ServerName _default_
     SSLEngine on
     SSLCertificateFile "$CERT_DIR/default.crt"
@@ -41,6 +72,12 @@ EOF
 /opt/homebrew/opt/php@8.3/bin/php -r '
 $hostsFile = "'$HOSTS_JSON'";
 $certDir = "'$CERT_DIR'";
+$httpPort = "'"$HTTP_PORT"'";
+$httpsPort = "'"$HTTPS_PORT"'";
+$bindAddress = "'"$BIND_ADDRESS"'";
+$fpmHandler = "'"$FPM_HANDLER"'";
+$sitesDir = "'"$SITES_DIR"'";
+$httpsSuffix = $httpsPort === "443" ? "" : ":$httpsPort";
 $hosts = json_decode(file_get_contents($hostsFile), true);
 
 foreach ($hosts as $domain => $config) {
@@ -65,17 +102,31 @@ foreach ($hosts as $domain => $config) {
         }
     }
 
-    // VirtualHost HTTP (puerto 80) - Redirige a HTTPS
-    echo "\n<VirtualHost *:80>\n";
+    // Hosts wildcard: todos los subdominios (*.dominio) van al mismo sitio
+    if (!empty($config["wildcard"])) {
+        array_unshift($activeAliases, "*." . $domain);
+    }
+    $hasWildcard = count(array_filter($activeAliases, function ($alias) {
+        return strpos($alias, "*") !== false;
+    })) > 0;
+
+    // VirtualHost HTTP - Redirige a HTTPS
+    echo "\n<VirtualHost $bindAddress:$httpPort>\n";
     echo "    ServerName $domain\n";
     foreach ($activeAliases as $alias) {
         echo "    ServerAlias $alias\n";
     }
-    echo "    Redirect permanent / https://$domain/\n";
+    if ($hasWildcard) {
+        // Conservar el subdominio pedido (cada tenant en su propio host)
+        echo "    RewriteEngine On\n";
+        echo "    RewriteRule ^ https://%{SERVER_NAME}$httpsSuffix%{REQUEST_URI} [R=301,L]\n";
+    } else {
+        echo "    Redirect permanent / https://$domain$httpsSuffix/\n";
+    }
     echo "</VirtualHost>\n";
 
-    // VirtualHost HTTPS (puerto 443)
-    echo "\n<VirtualHost *:443>\n";
+    // VirtualHost HTTPS
+    echo "\n<VirtualHost $bindAddress:$httpsPort>\n";
     echo "    ServerName $domain\n";
     foreach ($activeAliases as $alias) {
         echo "    ServerAlias $alias\n";
@@ -83,15 +134,30 @@ foreach ($hosts as $domain => $config) {
 
     echo "    DocumentRoot \"$docroot\"\n";
     echo "\n";
-    echo "    <Directory \"$docroot\">\n";
-    echo "        Options Indexes FollowSymLinks\n";
-    echo "        AllowOverride All\n";
-    echo "        Require all granted\n";
-    echo "    </Directory>\n";
-    echo "\n";
-    echo "    <FilesMatch \.php$>\n";
-    echo "        SetHandler \"proxy:fcgi://127.0.0.1:9000\"\n";
-    echo "    </FilesMatch>\n";
+    // Plantilla del host (reglas por framework y handler de su pool de
+    // PHP-FPM), si la app la renderizó; sin ella se usa el pool compartido
+    $siteFile = $sitesDir . "/" . $domain . ".apache.conf";
+    if (is_file($siteFile)) {
+        echo "    Include \"$siteFile\"\n";
+    } else {
+        echo "    <Directory \"$docroot\">\n";
+        echo "        Options Indexes FollowSymLinks\n";
+        echo "        AllowOverride All\n";
+        echo "        Require all granted\n";
+        // Reglas de reescritura propuestas por el inspector de proyectos
+        if (isset($config["rewrites"]) && is_array($config["rewrites"])) {
+            foreach ($config["rewrites"] as $rule) {
+                if (is_string($rule) && trim($rule) !== "") {
+                    echo "        " . trim($rule) . "\n";
+                }
+            }
+        }
+        echo "    </Directory>\n";
+        echo "\n";
+        echo "    <FilesMatch \.php$>\n";
+        echo "        SetHandler \"$fpmHandler\"\n";
+        echo "    </FilesMatch>\n";
+    }
     echo "\n";
     echo "    SSLEngine on\n";
     echo "    SSLCertificateFile \"$certDir/{$domain}.crt\"\n";
@@ -107,5 +173,5 @@ echo "======================================"
 echo "Archivo: $OUTPUT_FILE"
 echo ""
 echo "Para aplicar la configuración ejecuta:"
-echo "  bash ~/localhost-manager/scripts/install.sh"
+echo "  bash $MANAGER_DIR/scripts/install.sh"
 echo ""
@@ -4,6 +4,24 @@
 
 # Capturar el HOME del usuario ANTES de ejecutar como root
 USER_HOME="$HOME"
+USER_MANAGER_DIR="${LOCALHOST_MANAGER_HOME:-$USER_HOME/localhost-manager}"
+USER_CERT_DIR="${CERT_DIR:-$USER_MANAGER_DIR/certs}"
+USER_HOSTS_JSON="${HOSTS_JSON:-$USER_MANAGER_DIR/conf/hosts.json}"
+USER_HOSTS_IP="${HOSTS_IP:-127.0.0.1}"
+USER_WEB_SERVER="${WEB_SERVER:-apache}"
+# nginx: Homebrew en macOS, el paquete de la distribución en Linux
+if [ "$(uname -s)" = "Darwin" ]; then
+    DEFAULT_NGINX_SERVERS_DIR="$(brew --prefix 2>/dev/null || echo /opt/homebrew)/etc/nginx/servers"
+else
+    DEFAULT_NGINX_SERVERS_DIR="/etc/nginx/conf.d"
+fi
+USER_NGINX_SERVERS_DIR="${NGINX_SERVERS_DIR:-$DEFAULT_NGINX_SERVERS_DIR}"
+# Se busca con el PATH del usuario: el de root no suele incluir Homebrew
+USER_NGINX_BIN="$(command -v nginx || true)"
+if [ "$USER_WEB_SERVER" = "nginx" ] && [ -z "$USER_NGINX_BIN" ]; then
+    echo "[ERROR] No se encontró nginx en el PATH" >&2
+    exit 1
+fi
 
 echo "======================================"
 echo " Localhost Manager - Instalación"
@@ -23,9 +41,12 @@ APACHE_CONF="/etc/apache2/httpd.conf"
 APACHE_SSL_CONF="/etc/apache2/extra/httpd-ssl.conf"
 APACHE_VHOSTS_CONF="/etc/apache2/extra/httpd-vhosts.conf"
 SSL_DIR="/etc/apache2/ssl"
-MANAGER_DIR="\${USER_HOME}/localhost-manager"
-CERT_DIR="\${MANAGER_DIR}/certs"
+MANAGER_DIR="$USER_MANAGER_DIR"
+CERT_DIR="$USER_CERT_DIR"
 CONF_DIR="\${MANAGER_DIR}/conf"
+WEB_SERVER="$USER_WEB_SERVER"
+NGINX_SERVERS_DIR="$USER_NGINX_SERVERS_DIR"
+NGINX_BIN="$USER_NGINX_BIN"
 
 echo "[1/8] Configurando módulos Apache para PHP-FPM..."
 
@@ -71,7 +92,12 @@ if [ -d "\${CERT_DIR}" ]; then
 fi
 
 echo "[6/8] Aplicando configuración de Virtual Hosts..."
-if [ -f "\${CONF_DIR}/vhosts.conf" ]; then
+if [ "\${WEB_SERVER}" = "nginx" ]; then
+    if [ -f "\${CONF_DIR}/nginx-vhosts.conf" ]; then
+        mkdir -p "\${NGINX_SERVERS_DIR}"
+        cp -f "\${CONF_DIR}/nginx-vhosts.conf" "\${NGINX_SERVERS_DIR}/localhost-manager.conf"
+    fi
+elif [ -f "\${CONF_DIR}/vhosts.conf" ]; then
     cp -f "\${CONF_DIR}/vhosts.conf" "\${APACHE_VHOSTS_CONF}"
 fi
 
@@ -79,23 +105,48 @@ echo "[7/8] Actualizando /etc/hosts..."
 cp /etc/hosts /etc/hosts.backup 2>/dev/null || true
 sed -i.bak '/# Localhost Manager/,/# End Localhost Manager/d' /etc/hosts 2>/dev/null || true
 
-HOSTS_JSON="\${USER_HOME}/localhost-manager/conf/hosts.json"
+HOSTS_JSON="$USER_HOSTS_JSON"
+HOSTS_IP="$USER_HOSTS_IP"
 if [ -f "\${HOSTS_JSON}" ]; then
     echo "# Localhost Manager" >> /etc/hosts
     /usr/bin/jq -r 'to_entries[] | select(.value.active == true) | .key' "\${HOSTS_JSON}" 2>/dev/null | while read -r domain; do
-        echo "127.0.0.1    \${domain}" >> /etc/hosts
-        /usr/bin/jq -r --arg d "\${domain}" '.[\$d].aliases[]? | select(.active == true) | .value' "\${HOSTS_JSON}" 2>/dev/null | while read -r alias; do
-            [ -n "\${alias}" ] && echo "127.0.0.1    \${alias}" >> /etc/hosts
+        echo "\${HOSTS_IP}    \${domain}" >> /etc/hosts
+        # /etc/hosts no admite wildcards: se omiten los alias *.x
+        /usr/bin/jq -r --arg d "\${domain}" '.[\$d].aliases[]? | select(.active == true) | .value | select(startswith("*.") | not)' "\${HOSTS_JSON}" 2>/dev/null | while read -r alias; do
+            [ -n "\${alias}" ] && echo "\${HOSTS_IP}    \${alias}" >> /etc/hosts
+        done
+        # ...y en su lugar se escribe cada subdominio de tenant declarado
+        /usr/bin/jq -r --arg d "\${domain}" '.[\$d] as \$h | (\$h.subdomains // [])[] as \$s | ((if \$h.wildcard == true then [\$d] else [] end) + [(\$h.aliases // [])[] | select(.active == true) | .value | select(startswith("*.")) | ltrimstr("*.")])[] | "\(\$s).\(.)"' "\${HOSTS_JSON}" 2>/dev/null | while read -r tenant; do
+            [ -n "\${tenant}" ] && echo "\${HOSTS_IP}    \${tenant}" >> /etc/hosts
         done
     done
     echo "# End Localhost Manager" >> /etc/hosts
 fi
 
-echo "[8/8] Verificando y reiniciando Apache..."
-/usr/sbin/apachectl configtest 2>&1 | grep -v "fully qualified domain name" || true
-/usr/sbin/apachectl stop 2>/dev/null || true
-sleep 1
-/usr/sbin/apachectl start
+if [ "\${WEB_SERVER}" = "nginx" ]; then
+    echo "[8/8] Verificando y recargando nginx..."
+    if "\${NGINX_BIN}" -t >/dev/null 2>&1; then
+        # reload si ya está corriendo, si no se arranca
+        "\${NGINX_BIN}" -s reload 2>/dev/null || "\${NGINX_BIN}"
+    else
+        "\${NGINX_BIN}" -t 2>&1 || true
+        echo "[ERROR] Configuración de nginx inválida, no se recarga"
+        exit 1
+    fi
+    echo ""
+    echo "[OK] Instalación completada"
+    exit 0
+fi
+
+echo "[8/8] Verificando y recargando Apache..."
+if /usr/sbin/apachectl configtest >/dev/null 2>&1; then
+    # graceful: recarga sin cortar conexiones activas (arranca Apache si no está corriendo)
+    /usr/sbin/apachectl graceful
+else
+    /usr/sbin/apachectl configtest 2>&1 | grep -v "fully qualified domain name" || true
+    echo "[ERROR] Configuración de Apache inválida, no se recarga"
+    exit 1
+fi
 
 echo ""
 echo "[OK] Instalación completada"
@@ -103,8 +154,12 @@ SUDO_SCRIPT
 
 chmod +x "$TEMP_SCRIPT"
 
-# Ejecutar TODO con un solo Touch ID usando osascript
-osascript -e "do shell script \"$TEMP_SCRIPT\" with administrator privileges" 2>&1
+# Ejecutar TODO con un solo Touch ID usando osascript (pkexec en Linux)
+if command -v osascript >/dev/null 2>&1; then
+    osascript -e "do shell script \"$TEMP_SCRIPT\" with administrator privileges" 2>&1
+else
+    pkexec bash "$TEMP_SCRIPT" 2>&1
+fi
 
 # Limpiar
 rm -f "$TEMP_SCRIPT"
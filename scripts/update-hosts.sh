@@ -3,7 +3,10 @@
 # Script para actualizar /etc/hosts desde hosts.json
 # Ejecutar con: sudo bash update-hosts.sh
 
-HOSTS_JSON="$HOME/localhost-manager/conf/hosts.json"
+MANAGER_DIR="${LOCALHOST_MANAGER_HOME:-$HOME/localhost-manager}"
+HOSTS_JSON="${HOSTS_JSON:-$MANAGER_DIR/conf/hosts.json}"
+# IP donde escucha el servidor web (bindAddress en settings.json)
+HOSTS_IP="${HOSTS_IP:-127.0.0.1}"
 
 if [ "$EUID" -ne 0 ]; then
     echo "Por favor ejecuta con sudo: sudo bash update-hosts.sh"
@@ -31,6 +34,7 @@ echo "" >> /etc/hosts
 # Usar PHP para parsear hosts.json y generar entradas
 /opt/homebrew/opt/php@8.3/bin/php -r '
 $hostsFile = "'$HOSTS_JSON'";
+$hostsIp = "'"$HOSTS_IP"'";
 $hosts = json_decode(file_get_contents($hostsFile), true);
 
 foreach ($hosts as $domain => $config) {
@@ -42,23 +46,45 @@ foreach ($hosts as $domain => $config) {
     $aliases = isset($config["aliases"]) && is_array($config["aliases"]) ? $config["aliases"] : [];
 
     // Crear línea con dominio principal
-    $line = "127.0.0.1    $domain";
+    $line = "$hostsIp    $domain";
+
+    // Dominios wildcard (el propio host y sus alias *.x)
+    $wildcards = !empty($config["wildcard"]) ? [$domain] : [];
 
     // Agregar aliases activos
     foreach ($aliases as $alias) {
         // Soporte para aliases como strings o como objetos con estado
+        $value = "";
         if (is_string($alias) && !empty(trim($alias))) {
-            $line .= "    " . trim($alias);
+            $value = trim($alias);
         } elseif (is_array($alias) && isset($alias["value"]) && !empty(trim($alias["value"]))) {
             // Solo agregar si está activo (por defecto true si no está definido)
             $isActive = !isset($alias["active"]) || $alias["active"] === true;
             if ($isActive) {
-                $line .= "    " . trim($alias["value"]);
+                $value = trim($alias["value"]);
             }
         }
+
+        // /etc/hosts no admite wildcards
+        if (strpos($value, "*.") === 0) {
+            $wildcards[] = substr($value, 2);
+        } elseif ($value !== "") {
+            $line .= "    " . $value;
+        }
     }
 
     echo $line . "\n";
+
+    // En su lugar se escribe cada subdominio de tenant declarado, uno por
+    // línea: un host con muchos tenants no cabe en una sola
+    $subdomains = isset($config["subdomains"]) && is_array($config["subdomains"]) ? $config["subdomains"] : [];
+    foreach ($wildcards as $base) {
+        foreach ($subdomains as $label) {
+            if (is_string($label) && trim($label) !== "") {
+                echo "$hostsIp    " . trim($label) . "." . $base . "\n";
+            }
+        }
+    }
 }
 ' >> /etc/hosts
 
@@ -66,4 +92,4 @@ echo "" >> /etc/hosts
 echo "# End Localhost Manager" >> /etc/hosts
 echo "" >> /etc/hosts
 
-echo "✓ /etc/hosts actualizado exitosamente con $(grep -c '127.0.0.1' /etc/hosts | tail -1) entradas"
+echo "✓ /etc/hosts actualizado exitosamente con $(sed -n '/# Localhost Manager/,/# End Localhost Manager/p' /etc/hosts | grep -c "^$HOSTS_IP") entradas"
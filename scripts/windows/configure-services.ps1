@@ -68,6 +68,25 @@ if (-not (Test-Path $MysqlExe)) {
     Write-Warning "MySQL not found at: $MysqlExe"
 }
 
+# MySQL port from the app settings (3306 by default) in the stack's my.ini
+$MysqlPort = if ($env:MYSQL_PORT) { $env:MYSQL_PORT } else { "3306" }
+$MyIni = @("$MysqlDir\bin\my.ini", "$MysqlDir\my.ini") | Where-Object { Test-Path $_ } | Select-Object -First 1
+if ($MyIni) {
+    $Lines = Get-Content $MyIni
+    if ($Lines -match '^\s*port\s*=') {
+        $Lines = $Lines -replace '^\s*port\s*=.*$', "port = $MysqlPort"
+    } else {
+        $Lines = $Lines | ForEach-Object {
+            $_
+            if ($_ -match '^\s*\[mysqld\]') { "port = $MysqlPort" }
+        }
+    }
+    Set-Content -Path $MyIni -Value $Lines -Encoding ASCII
+    Write-Host "MySQL port: $MysqlPort ($MyIni)"
+} else {
+    Write-Warning "my.ini not found in $MysqlDir; MySQL keeps its default port"
+}
+
 # Get service status
 Write-Host ""
 Write-Host "Service Status:"
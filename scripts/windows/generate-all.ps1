@@ -12,6 +12,12 @@ $ErrorActionPreference = "Stop"
 
 $ScriptsDir = Split-Path -Parent $MyInvocation.MyCommand.Path
 
+# Only Apache vhosts are generated on Windows
+if ($env:WEB_SERVER -eq "nginx") {
+    Write-Error "nginx no esta soportado en Windows; usa Apache"
+    exit 1
+}
+
 Write-Host ""
 Write-Host "======================================" -ForegroundColor Cyan
 Write-Host " Localhost Manager - Generador" -ForegroundColor Cyan
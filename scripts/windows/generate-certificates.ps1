@@ -26,7 +26,7 @@ param(
 $ErrorActionPreference = "Stop"
 
 # Paths
-$ManagerDir = "$env:USERPROFILE\localhost-manager"
+$ManagerDir = if ($env:LOCALHOST_MANAGER_HOME) { $env:LOCALHOST_MANAGER_HOME } else { "$env:USERPROFILE\localhost-manager" }
 $ConfDir = "$ManagerDir\conf"
 $CertDir = "$ManagerDir\certs"
 $HostsJson = "$ConfDir\hosts.json"
@@ -112,6 +112,33 @@ function Test-CertificateValid {
     }
 }
 
+# Check that a certificate lists every DNS name it should
+# (aliases added after it was issued need a new certificate)
+function Test-CertificateCovers {
+    param(
+        [string]$CertFile,
+        [string[]]$Names
+    )
+
+    try {
+        $text = (& $OpenSSL x509 -noout -text -in $CertFile 2>$null) -join "`n"
+        # Compare whole SAN entries, so DNS:a.test doesn't match DNS:a.test.b
+        $sans = @()
+        if ($text -match "Subject Alternative Name:[^`n]*`n\s*([^`n]*)") {
+            $sans = $Matches[1].Trim() -split ",\s*"
+        }
+        foreach ($name in $Names) {
+            if ($sans -notcontains "DNS:$name") {
+                return $false
+            }
+        }
+        return $true
+    }
+    catch {
+        return $false
+    }
+}
+
 # Function to generate certificate
 function New-SelfSignedCert {
     param(
@@ -123,27 +150,30 @@ function New-SelfSignedCert {
     $certFile = "$CertDir\$Domain.crt"
     $keyFile = "$CertDir\$Domain.key"
 
-    # Check if certificate already exists and is valid
-    if (-not $Force -and (Test-CertificateValid -CertFile $certFile)) {
+    # Names to cover: domain, aliases (wildcard ones included) and *.domain
+    $names = @($Domain)
+    foreach ($alias in $Aliases) {
+        if ($alias -and $alias.Trim() -and $alias.Trim() -ne "*.$Domain") {
+            $names += $alias.Trim()
+        }
+    }
+    $names += "*.$Domain"
+
+    # Check if certificate already exists, is valid and covers every name
+    if (-not $Force -and (Test-CertificateValid -CertFile $certFile) -and (Test-CertificateCovers -CertFile $certFile -Names $names)) {
         Write-Host "[SKIP] $Domain (valid, not expiring)" -ForegroundColor Yellow
         $script:Skipped++
         return $true
     }
 
     # Build SAN list
-    $sanList = @("DNS.1 = $Domain")
-    $sanIndex = 2
-
-    foreach ($alias in $Aliases) {
-        if ($alias -and $alias.Trim()) {
-            $sanList += "DNS.$sanIndex = $($alias.Trim())"
-            $sanIndex++
-        }
+    $sanList = @()
+    $sanIndex = 1
+    foreach ($name in $names) {
+        $sanList += "DNS.$sanIndex = $name"
+        $sanIndex++
     }
 
-    # Add wildcard
-    $sanList += "DNS.$sanIndex = *.$Domain"
-
     $sanConfig = $sanList -join "`n"
 
     # OpenSSL config
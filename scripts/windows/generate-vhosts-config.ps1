@@ -12,12 +12,21 @@ param(
 $ErrorActionPreference = "Stop"
 
 # Paths
-$ManagerDir = "$env:USERPROFILE\localhost-manager"
+$ManagerDir = if ($env:LOCALHOST_MANAGER_HOME) { $env:LOCALHOST_MANAGER_HOME } else { "$env:USERPROFILE\localhost-manager" }
 $ConfDir = "$ManagerDir\conf"
 $CertDir = "$ManagerDir\certs"
 $HostsJson = "$ConfDir\hosts.json"
 $OutputFile = "$ConfDir\vhosts.conf"
 
+# Ports and addresses (exported by the app from settings.json)
+$HttpPort = if ($env:HTTP_PORT) { $env:HTTP_PORT } else { "80" }
+$HttpsPort = if ($env:HTTPS_PORT) { $env:HTTPS_PORT } else { "443" }
+$BindAddress = if ($env:BIND_ADDRESS) { $env:BIND_ADDRESS } else { "*" }
+# IPv6 goes in brackets in front of the port: [::1]:443
+if ($BindAddress.Contains(":") -and -not $BindAddress.StartsWith("[")) { $BindAddress = "[$BindAddress]" }
+$FpmHandler = if ($env:FPM_HANDLER) { $env:FPM_HANDLER } else { "proxy:fcgi://127.0.0.1:9000" }
+$HttpsSuffix = if ($HttpsPort -eq "443") { "" } else { ":$HttpsPort" }
+
 # Stack-specific paths
 switch ($Stack.ToLower()) {
     "xampp" {
@@ -63,14 +72,26 @@ if (-not (Test-Path $ConfDir)) {
 # Read hosts.json
 $hosts = Get-Content $HostsJson -Raw | ConvertFrom-Json
 
+# httpd.conf and httpd-ssl.conf already listen on 80/443; only custom ports
+# get a Listen directive (a duplicate Listen keeps Apache from starting)
+$listenPrefix = if ($BindAddress -ne "*") { "${BindAddress}:" } else { "" }
+$listenLines = ""
+if ($HttpPort -ne "80") {
+    $listenLines += "Listen $listenPrefix$HttpPort`n"
+}
+if ($HttpsPort -ne "443") {
+    $listenLines += "Listen $listenPrefix$HttpsPort https`n"
+}
+
 # Start building vhosts config
 $vhostsConfig = @"
 # Virtual Hosts - Generated $(Get-Date -Format "yyyy-MM-dd HH:mm:ss")
 # Stack: $Stack
 # Localhost Manager for Windows
 
+$listenLines
 # Default SSL VirtualHost (catch-all)
-<VirtualHost *:443>
+<VirtualHost ${BindAddress}:$HttpsPort>
     ServerName _default_
     SSLEngine on
     SSLCertificateFile "$CertDir/default.crt"
@@ -116,25 +137,36 @@ foreach ($prop in $hosts.PSObject.Properties) {
         }
     }
 
+    # Wildcard hosts serve every subdomain (*.domain)
+    if ($config.wildcard) {
+        $aliases = @("*.$domain") + $aliases
+    }
+
     $aliasLines = ""
     foreach ($alias in $aliases) {
         $aliasLines += "    ServerAlias $alias`n"
     }
 
+    # Keep the requested subdomain when redirecting wildcard names
+    $redirectLine = "    Redirect permanent / https://$domain$HttpsSuffix/"
+    if ($aliases | Where-Object { $_.StartsWith("*.") }) {
+        $redirectLine = "    RewriteEngine On`n    RewriteRule ^ https://%{SERVER_NAME}$HttpsSuffix%{REQUEST_URI} [R=301,L]"
+    }
+
     # HTTP VirtualHost (redirect to HTTPS)
     $vhostsConfig += @"
 
 # $domain
-<VirtualHost *:80>
+<VirtualHost ${BindAddress}:$HttpPort>
     ServerName $domain
-$aliasLines    Redirect permanent / https://$domain/
+$aliasLines$redirectLine
 </VirtualHost>
 
 "@
 
     # HTTPS VirtualHost
     $vhostsConfig += @"
-<VirtualHost *:443>
+<VirtualHost ${BindAddress}:$HttpsPort>
     ServerName $domain
 $aliasLines
     DocumentRoot "$docroot"
@@ -146,7 +178,7 @@ $aliasLines
     </Directory>
 
     <FilesMatch \.php$>
-        SetHandler "proxy:fcgi://127.0.0.1:9000"
+        SetHandler "$FpmHandler"
     </FilesMatch>
 
     SSLEngine on
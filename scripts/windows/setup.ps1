@@ -38,7 +38,7 @@ Write-Host "======================================" -ForegroundColor Cyan
 Write-Host ""
 
 # Paths
-$ManagerDir = "$env:USERPROFILE\localhost-manager"
+$ManagerDir = if ($env:LOCALHOST_MANAGER_HOME) { $env:LOCALHOST_MANAGER_HOME } else { "$env:USERPROFILE\localhost-manager" }
 $ConfDir = "$ManagerDir\conf"
 $CertDir = "$ManagerDir\certs"
 $BackupDir = "$ManagerDir\backups"
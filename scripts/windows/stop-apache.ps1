@@ -6,7 +6,8 @@
 $ErrorActionPreference = "SilentlyContinue"
 
 # Read configured stack
-$stackFile = "$env:USERPROFILE\localhost-manager\conf\stack.conf"
+$ManagerDir = if ($env:LOCALHOST_MANAGER_HOME) { $env:LOCALHOST_MANAGER_HOME } else { "$env:USERPROFILE\localhost-manager" }
+$stackFile = "$ManagerDir\conf\stack.conf"
 $stack = "xampp"
 if (Test-Path $stackFile) {
     $stack = (Get-Content $stackFile -Raw).Trim()
@@ -9,11 +9,13 @@
 $ErrorActionPreference = "Stop"
 
 # Paths
-$ManagerDir = "$env:USERPROFILE\localhost-manager"
+$ManagerDir = if ($env:LOCALHOST_MANAGER_HOME) { $env:LOCALHOST_MANAGER_HOME } else { "$env:USERPROFILE\localhost-manager" }
 $ConfDir = "$ManagerDir\conf"
 $HostsJson = "$ConfDir\hosts.json"
 $WindowsHosts = "C:\Windows\System32\drivers\etc\hosts"
 $BackupDir = "$ManagerDir\backups"
+# IP where the web server listens (bindAddress in settings.json)
+$HostsIp = if ($env:HOSTS_IP) { $env:HOSTS_IP } else { "127.0.0.1" }
 
 Write-Host "======================================"
 Write-Host " Actualizador de Hosts - Windows"
@@ -72,21 +74,48 @@ foreach ($prop in $hosts.PSObject.Properties) {
     }
 
     $activeCount++
-    $line = "127.0.0.1    $domain"
+    $line = "$HostsIp    $domain"
+
+    # Wildcard names (the host itself and *.x aliases)
+    $wildcards = @()
+    if ($config.wildcard) {
+        $wildcards += $domain
+    }
 
     # Add active aliases
     if ($config.aliases) {
         foreach ($alias in $config.aliases) {
+            $value = ""
             if ($alias -is [string] -and $alias.Trim()) {
-                $line += "    $($alias.Trim())"
+                $value = $alias.Trim()
             }
             elseif ($alias.value -and $alias.active -ne $false) {
-                $line += "    $($alias.value.Trim())"
+                $value = $alias.value.Trim()
+            }
+
+            # The hosts file can't hold wildcards
+            if ($value.StartsWith("*.")) {
+                $wildcards += $value.Substring(2)
+            }
+            elseif ($value) {
+                $line += "    $value"
             }
         }
     }
 
     $newEntries += "$line`n"
+
+    # ...so every declared tenant subdomain is written instead, one per line:
+    # Windows stops reading names after the first few on a line
+    if ($config.subdomains) {
+        foreach ($base in $wildcards) {
+            foreach ($label in $config.subdomains) {
+                if ($label -is [string] -and $label.Trim()) {
+                    $newEntries += "$HostsIp    $($label.Trim()).$base`n"
+                }
+            }
+        }
+    }
 }
 
 $newEntries += @"